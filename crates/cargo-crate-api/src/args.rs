@@ -15,7 +15,10 @@ pub enum Command {
 #[derive(clap::Args)]
 #[clap(about)]
 #[clap(group = clap::ArgGroup::new("mode").multiple(false))]
-#[clap(group = clap::ArgGroup::new("base").multiple(false).requires("diff"))]
+#[clap(group = clap::ArgGroup::new("base-mode").multiple(false))]
+#[clap(group = clap::ArgGroup::new("base").multiple(false).requires("base-mode"))]
+#[clap(group = clap::ArgGroup::new("features-mode").multiple(false))]
+#[clap(group = clap::ArgGroup::new("doc-target").multiple(false))]
 pub struct Api {
     #[clap(long, group = "mode")]
     pub dump_raw: bool,
@@ -23,9 +26,110 @@ pub struct Api {
     #[clap(long, group = "mode")]
     pub api: bool,
 
-    #[clap(short, long, group = "mode")]
+    #[clap(long, value_name = "PATH")]
+    pub scope: Option<String>,
+
+    #[clap(short, long, groups = &["mode", "base-mode"])]
     pub diff: bool,
 
+    #[clap(long, requires = "diff")]
+    pub unfreeze: bool,
+
+    #[clap(long, requires = "diff", conflicts_with_all = &["format", "plugin"])]
+    pub upgrade_guide: bool,
+
+    #[clap(long, value_name = "TOML", group = "mode")]
+    pub compare: Option<std::path::PathBuf>,
+
+    #[clap(long, value_name = "DEP", group = "mode")]
+    pub dep_check: Option<String>,
+
+    #[clap(long, value_name = "VERSION", requires = "dep-check")]
+    pub to: Option<String>,
+
+    #[clap(long, requires = "dep-check")]
+    pub used: bool,
+
+    #[clap(long, group = "mode")]
+    pub breaking: bool,
+
+    #[clap(long, groups = &["mode", "base-mode"])]
+    pub check_release: bool,
+
+    #[clap(long, value_name = "GOOD", group = "mode")]
+    pub bisect: Option<String>,
+
+    #[clap(long, value_name = "REF", default_value = "HEAD", requires = "bisect")]
+    pub bad: String,
+
+    #[clap(long, value_name = "ID", requires = "bisect", possible_values = change_ids())]
+    pub change: Option<String>,
+
+    #[clap(long, groups = &["mode", "features-mode"])]
+    pub feature_powerset: bool,
+
+    #[clap(long, groups = &["mode", "features-mode"])]
+    pub feature_additivity: bool,
+
+    #[clap(long, value_name = "N", requires = "features-mode")]
+    pub depth: Option<usize>,
+
+    #[clap(long, group = "mode")]
+    pub feature_docs: bool,
+
+    #[clap(long, group = "mode")]
+    pub history: bool,
+
+    #[clap(long, group = "mode")]
+    pub since: bool,
+
+    #[clap(long, value_name = "JSON", group = "mode", min_values = 1)]
+    pub merge: Vec<std::path::PathBuf>,
+
+    #[clap(
+        long,
+        value_name = "NAME",
+        default_value = "workspace",
+        requires = "merge"
+    )]
+    pub name: String,
+
+    #[clap(long, group = "mode")]
+    pub metrics: bool,
+
+    #[clap(long, value_name = "NDJSON", requires = "metrics")]
+    pub append: Option<std::path::PathBuf>,
+
+    #[clap(long, group = "mode")]
+    pub unsafe_report: bool,
+
+    #[clap(long, group = "mode")]
+    pub ffi: bool,
+
+    #[clap(long, group = "mode")]
+    pub embed: bool,
+
+    #[clap(long, group = "mode")]
+    pub update_baseline: bool,
+
+    #[clap(long, global = true)]
+    pub check: bool,
+
+    #[clap(long, value_name = "NAME@VERSION", group = "mode")]
+    pub verify_crate: Option<String>,
+
+    #[clap(long, group = "mode")]
+    pub serve: bool,
+
+    #[clap(long, value_name = "ADDR", requires = "serve")]
+    pub listen: Option<std::net::SocketAddr>,
+
+    #[clap(long, group = "mode")]
+    pub watch: bool,
+
+    #[clap(subcommand)]
+    pub command: Option<Subcommand>,
+
     #[clap(long, value_name = "REF", group = "base")]
     pub git: Option<String>,
 
@@ -37,41 +141,290 @@ pub struct Api {
 
     #[clap(
         short,
-        long,
+        long = "format",
+        name = "format",
         arg_enum,
-        default_value_t = Format::Pretty
+        global = true,
+        value_name = "FORMAT"
     )]
+    pub format_arg: Option<Format>,
+
+    /// `--format`, falling back to the project config
+    #[clap(skip = Format::Pretty)]
     pub format: Format,
 
+    #[clap(long, value_name = "WASM", conflicts_with = "format")]
+    pub plugin: Option<std::path::PathBuf>,
+
+    #[clap(long, value_name = "TERA", conflicts_with_all = &["format", "plugin"])]
+    pub template: Option<std::path::PathBuf>,
+
+    #[clap(
+        short,
+        long,
+        value_name = "PATH",
+        conflicts_with_all = &["serve", "watch"]
+    )]
+    pub output: Option<std::path::PathBuf>,
+
+    #[clap(long)]
+    pub with_docs: bool,
+
+    #[clap(long, arg_enum, value_name = "GROUP", requires = "owners")]
+    pub group_by: Option<GroupBy>,
+
+    #[clap(long, value_name = "PATH")]
+    pub owners: Option<std::path::PathBuf>,
+
     #[clap(flatten)]
     pub manifest: clap_cargo::Manifest,
 
     #[clap(flatten)]
     pub workspace: clap_cargo::Workspace,
 
+    #[clap(flatten)]
+    pub features: clap_cargo::Features,
+
+    #[clap(long, group = "doc-target")]
+    pub lib: bool,
+
+    #[clap(long, value_name = "NAME", group = "doc-target")]
+    pub bin: Option<String>,
+
+    #[clap(long, group = "doc-target", conflicts_with = "rustdoc-json")]
+    pub all_targets: bool,
+
+    #[clap(long, value_name = "TRIPLE")]
+    pub target: Option<String>,
+
+    #[clap(long, value_name = "TOOLCHAIN")]
+    pub toolchain: Option<String>,
+
+    #[clap(long)]
+    pub document_private_items: bool,
+
+    #[clap(long)]
+    pub document_hidden_items: bool,
+
+    #[clap(long, value_name = "FLAGS", allow_hyphen_values = true)]
+    pub rustdocflags: Option<String>,
+
+    #[clap(long, value_name = "KEY=VALUE", parse(try_from_str = parse_env))]
+    pub env: Vec<(String, String)>,
+
+    #[clap(long, value_name = "JSON")]
+    pub rustdoc_json: Option<std::path::PathBuf>,
+
+    #[clap(long)]
+    pub no_docs_rs: bool,
+
+    #[clap(long)]
+    pub offline: bool,
+
+    #[clap(long)]
+    pub locked: bool,
+
+    #[clap(long)]
+    pub frozen: bool,
+
+    #[clap(short, long, value_name = "N")]
+    pub jobs: Option<u32>,
+
+    #[clap(long)]
+    pub keep_going: bool,
+
     #[clap(flatten)]
     pub(crate) color: concolor_clap::Color,
 
     #[clap(flatten)]
     pub verbose: clap_verbosity_flag::Verbosity<clap_verbosity_flag::InfoLevel>,
+
+    #[clap(long, arg_enum, value_name = "FORMAT", default_value_t = LogFormat::Human)]
+    pub log_format: LogFormat,
+
+    #[clap(long, arg_enum, value_name = "FORMAT", default_value_t = ErrorFormat::Human)]
+    pub error_format: ErrorFormat,
 }
 
 impl Api {
     pub fn mode(&self) -> Mode {
-        if self.dump_raw {
+        if let Some(Subcommand::Diff { baseline, .. }) = &self.command {
+            if baseline.is_some() {
+                Mode::Diff
+            } else {
+                Mode::DiffManifests
+            }
+        } else if self.dump_raw {
             Mode::DumpRaw
         } else if self.api {
             Mode::Api
         } else if self.diff {
             Mode::Diff
+        } else if self.compare.is_some() {
+            Mode::Compare
+        } else if self.dep_check.is_some() {
+            Mode::DepCheck
+        } else if self.breaking {
+            Mode::Breaking
+        } else if self.check_release {
+            Mode::CheckRelease
+        } else if self.bisect.is_some() {
+            Mode::Bisect
+        } else if self.feature_powerset {
+            Mode::FeaturePowerset
+        } else if self.feature_additivity {
+            Mode::FeatureAdditivity
+        } else if self.feature_docs {
+            Mode::FeatureDocs
+        } else if self.history {
+            Mode::History
+        } else if self.since {
+            Mode::Since
+        } else if !self.merge.is_empty() {
+            Mode::Merge
+        } else if self.metrics {
+            Mode::Metrics
+        } else if self.unsafe_report {
+            Mode::UnsafeReport
+        } else if self.ffi {
+            Mode::Ffi
+        } else if self.embed {
+            Mode::Embed
+        } else if self.update_baseline {
+            Mode::UpdateBaseline
+        } else if self.verify_crate.is_some() {
+            Mode::VerifyCrate
+        } else if self.serve {
+            Mode::Serve
+        } else if self.watch {
+            Mode::Watch
         } else {
             Mode::Api
         }
     }
 
+    /// Fill in what wasn't passed on the command line from `config`
+    pub fn apply_config(&mut self, config: &crate::config::ProjectConfig) {
+        self.format = self.format_arg.or(config.format).unwrap_or(Format::Pretty);
+        let features = &mut self.features;
+        if !features.all_features && !features.no_default_features && features.features.is_empty() {
+            features.features = config.features.clone();
+            features.no_default_features = config.no_default_features;
+            features.all_features = config.all_features;
+        }
+        if self.toolchain.is_none() {
+            self.toolchain = config.toolchain.clone();
+        }
+        if self.target.is_none() {
+            self.target = config.target.clone();
+        }
+        if self.rustdocflags.is_none() && !config.rustdocflags.is_empty() {
+            self.rustdocflags = Some(config.rustdocflags.join(" "));
+        }
+        // Command-line values win by coming last
+        let mut env: Vec<_> = config.env.clone().into_iter().collect();
+        env.append(&mut self.env);
+        self.env = env;
+    }
+
+    /// How to run rustdoc, from the build flags
+    pub fn rustdoc(&self) -> crate_api::RustDocBuilder {
+        let colored_stderr = concolor_control::get(concolor_control::Stream::Stderr).color();
+        // `-q` silences `cargo doc` progress too, leaving only the report
+        let quiet = self
+            .verbose
+            .log_level()
+            .map_or(true, |level| level < log::Level::Info);
+        let mut builder = crate_api::RustDocBuilder::new()
+            .color(colored_stderr)
+            .silence(quiet)
+            .features(
+                self.features
+                    .features
+                    .iter()
+                    .flat_map(|features| features.split(','))
+                    .filter(|feature| !feature.is_empty()),
+            )
+            .no_default_features(self.features.no_default_features);
+        if self.features.all_features {
+            builder = builder.all_features(true);
+        }
+        if let Some(target) = self.target.as_deref() {
+            builder = builder.target(target);
+        }
+        if let Some(toolchain) = self.toolchain.as_deref() {
+            builder = builder.toolchain(toolchain);
+        }
+        if let Some(jobs) = self.jobs {
+            builder = builder.jobs(jobs);
+        }
+        if self.lib {
+            builder = builder.doc_target(crate_api::rustdoc::DocTarget::Lib);
+        }
+        if let Some(bin) = self.bin.as_deref() {
+            builder = builder.doc_target(crate_api::rustdoc::DocTarget::Bin(bin.to_owned()));
+        }
+        for (key, value) in &self.env {
+            builder = builder.env(key, value);
+        }
+        builder
+            .private_items(self.document_private_items)
+            .hidden_items(self.document_hidden_items)
+            .extra_rustdoc_flags(
+                self.rustdocflags
+                    .iter()
+                    .flat_map(|flags| flags.split_whitespace()),
+            )
+            .offline(self.offline)
+            .locked(self.locked)
+            .frozen(self.frozen)
+    }
+
+    /// Whether a crates.io baseline may use docs.rs's rustdoc JSON rather than being built
+    ///
+    /// docs.rs documents the library for its default target with its own toolchain, so any
+    /// flag changing what or how we document rules it out.
+    pub fn docs_rs(&self) -> bool {
+        !self.no_docs_rs
+            && !self.offline
+            && !self.frozen
+            && self.features.features.is_empty()
+            && !self.features.no_default_features
+            && !self.features.all_features
+            && self.target.is_none()
+            && self.toolchain.is_none()
+            && !self.lib
+            && self.bin.is_none()
+            && !self.document_private_items
+            && !self.document_hidden_items
+            && self.rustdocflags.is_none()
+            && self.env.is_empty()
+    }
+
+    /// Flags for `cargo metadata`, so it doesn't update the index when rustdoc won't
+    pub fn cargo_options(&self) -> Vec<String> {
+        let mut options = Vec::new();
+        if self.offline {
+            options.push("--offline".to_owned());
+        }
+        if self.locked {
+            options.push("--locked".to_owned());
+        }
+        if self.frozen {
+            options.push("--frozen".to_owned());
+        }
+        options
+    }
+
     pub fn base(&self) -> Option<Source> {
         #[allow(clippy::manual_map)]
-        if let Some(git) = self.git.as_ref() {
+        if let Some(Subcommand::Diff {
+            baseline: Some(baseline),
+            ..
+        }) = &self.command
+        {
+            Some(baseline.clone())
+        } else if let Some(git) = self.git.as_ref() {
             Some(Source::Git(git.clone()))
         } else if let Some(path) = self.path.as_ref() {
             Some(Source::Path(path.clone()))
@@ -83,22 +436,131 @@ impl Api {
     }
 }
 
+/// A baseline, like `git:v1.2.0`, `path:../old/Cargo.toml`, or `registry:1.2.0`
+///
+/// A bare `registry` is the latest release.
+fn parse_source(raw: &str) -> Result<Source, String> {
+    if raw == "registry" {
+        return Ok(Source::Registry("latest".to_owned()));
+    }
+    match raw.split_once(':') {
+        Some(("git", rev)) => Ok(Source::Git(rev.to_owned())),
+        Some(("path", path)) => Ok(Source::Path(path.into())),
+        Some(("registry", version)) => Ok(Source::Registry(version.to_owned())),
+        _ => Err(format!(
+            "expected `git:<REF>`, `path:<TOML>`, `registry`, or `registry:<VERSION>`, got `{}`",
+            raw
+        )),
+    }
+}
+
+fn parse_env(raw: &str) -> Result<(String, String), String> {
+    raw.split_once('=')
+        .map(|(key, value)| (key.to_owned(), value.to_owned()))
+        .ok_or_else(|| format!("expected `KEY=VALUE`, got `{}`", raw))
+}
+
+fn change_ids() -> Vec<&'static str> {
+    crate_api::diff::ALL_IDS.iter().map(|id| id.name).collect()
+}
+
+#[derive(clap::Subcommand)]
+pub enum Subcommand {
+    /// Print shell completions, including the workspace's package names
+    Completions {
+        #[clap(arg_enum)]
+        shell: clap_complete::Shell,
+    },
+    /// Compare the APIs of two packages, like two versions checked out side by side, or of a
+    /// package against a baseline
+    #[clap(group = clap::ArgGroup::new("against").required(true))]
+    Diff {
+        #[clap(long, value_name = "TOML", group = "against", requires = "new")]
+        old: Option<std::path::PathBuf>,
+
+        #[clap(long, value_name = "TOML")]
+        new: Option<std::path::PathBuf>,
+
+        #[clap(
+            long,
+            value_name = "SOURCE",
+            group = "against",
+            parse(try_from_str = parse_source)
+        )]
+        baseline: Option<Source>,
+    },
+}
+
 #[derive(Copy, Clone, Debug, PartialEq, Eq)]
 pub enum Mode {
     DumpRaw,
     Api,
     Diff,
+    Compare,
+    DepCheck,
+    Breaking,
+    CheckRelease,
+    Bisect,
+    FeaturePowerset,
+    FeatureAdditivity,
+    FeatureDocs,
+    History,
+    Since,
+    Merge,
+    Metrics,
+    UnsafeReport,
+    Ffi,
+    Embed,
+    UpdateBaseline,
+    VerifyCrate,
+    Serve,
+    Watch,
+    DiffManifests,
 }
 
-#[derive(Copy, Clone, Debug, PartialEq, Eq, clap::ArgEnum)]
+#[derive(Copy, Clone, Debug, PartialEq, Eq, clap::ArgEnum, serde::Deserialize)]
+#[serde(rename_all = "kebab-case")]
 pub enum Format {
     Silent,
     Pretty,
     #[clap(alias = "markdown")]
+    #[serde(alias = "markdown")]
     Md,
+    MdChangelog,
+    Json,
+    Csv,
+    Tsv,
+    Github,
+    Sarif,
+    Html,
+}
+
+impl std::fmt::Display for Format {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        clap::ArgEnum::to_possible_value(self)
+            .expect("no values are skipped")
+            .get_name()
+            .fmt(f)
+    }
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq, clap::ArgEnum)]
+pub enum LogFormat {
+    Human,
     Json,
 }
 
+#[derive(Copy, Clone, Debug, PartialEq, Eq, clap::ArgEnum)]
+pub enum ErrorFormat {
+    Human,
+    Json,
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq, clap::ArgEnum)]
+pub enum GroupBy {
+    Owner,
+}
+
 impl Default for Format {
     fn default() -> Self {
         Format::Pretty
@@ -0,0 +1,475 @@
+use std::collections::BTreeMap;
+use std::collections::HashSet;
+
+/// Settings from `[package.metadata.crate-api]`
+#[derive(Clone, Debug, Default, serde::Deserialize)]
+#[serde(rename_all = "kebab-case")]
+#[serde(default)]
+pub struct PackageConfig {
+    /// Fail on any API change, including additions
+    pub frozen: bool,
+    /// Paths not covered by stability guarantees, like `*::__private`
+    pub exclude: Vec<crate_api::PathGlob>,
+    /// Exclude `#[doc(hidden)]` items
+    pub exclude_hidden: bool,
+    /// Exclude items whose documentation calls them unstable
+    pub exclude_unstable: bool,
+    /// Paths whose breaking changes are only reported, in addition to `**Experimental**` in docs
+    pub experimental: Vec<crate_api::PathGlob>,
+    /// Severities to use instead of the defaults, by change id
+    pub severity: BTreeMap<String, crate_api::diff::Severity>,
+    /// Changes acknowledged as intended, so they no longer fail checks
+    pub allow: Vec<Allow>,
+}
+
+/// An intended change, like `{ id = "fn-arity-changed", path = "mycrate::legacy::*" }`
+#[derive(Clone, Debug, serde::Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct Allow {
+    /// The kind of change, like `fn-arity-changed`
+    pub id: String,
+    /// Where the change is allowed, including everything under it, or anywhere if unset
+    #[serde(default)]
+    pub path: Option<crate_api::PathGlob>,
+    /// Why the change is fine, for reviewers
+    #[serde(default)]
+    pub reason: Option<String>,
+}
+
+impl Allow {
+    fn matches(
+        &self,
+        before: &crate_api::Api,
+        after: &crate_api::Api,
+        diff: &crate_api::diff::Diff,
+    ) -> bool {
+        if self.id != diff.id.name {
+            return false;
+        }
+        let glob = match self.path.as_ref() {
+            Some(glob) => glob,
+            None => return true,
+        };
+        let matches = |api: &crate_api::Api, location: Option<crate_api::diff::Location>| {
+            location
+                .and_then(|location| location.path_id)
+                .and_then(|path_id| api.paths.get(path_id))
+                .map(|path| glob.matches(&path.path))
+                .unwrap_or(false)
+        };
+        matches(before, diff.before) || matches(after, diff.after)
+    }
+}
+
+impl PackageConfig {
+    /// The package's settings, on top of those in the nearest [`FILE`]
+    pub fn from_package(pkg: &cargo_metadata::Package) -> Result<Self, eyre::Report> {
+        let dir = pkg
+            .manifest_path
+            .parent()
+            .expect("manifests are always in a directory");
+        let mut config = ProjectConfig::discover(dir.as_std_path())?.package;
+        if let Some(package) = pkg.metadata.get("crate-api") {
+            let package: Self = serde_json::from_value(package.clone()).map_err(|e| {
+                eyre::eyre!(
+                    "Invalid `package.metadata.crate-api` in {}: {}",
+                    pkg.manifest_path,
+                    e
+                )
+            })?;
+            config.update(package);
+        }
+        Ok(config)
+    }
+
+    /// Layer `other` on top
+    fn update(&mut self, other: Self) {
+        self.frozen |= other.frozen;
+        self.exclude.extend(other.exclude);
+        self.exclude_hidden |= other.exclude_hidden;
+        self.exclude_unstable |= other.exclude_unstable;
+        self.experimental.extend(other.experimental);
+        self.severity.extend(other.severity);
+        self.allow.extend(other.allow);
+    }
+
+    /// Paths the crate does not commit to keeping stable, including everything under them
+    pub fn excluded_paths(&self, api: &crate_api::Api) -> HashSet<String> {
+        let excluded: HashSet<_> = api
+            .paths
+            .iter()
+            .filter(|(_, path)| {
+                let item = path.item_id.and_then(|item_id| api.items.get(item_id));
+                self.exclude.iter().any(|glob| glob.matches(&path.path))
+                    || (self.exclude_hidden && item.map(|item| item.is_hidden).unwrap_or(false))
+                    || (self.exclude_unstable
+                        && item
+                            .and_then(|item| item.docs.as_deref())
+                            .map(is_unstable)
+                            .unwrap_or(false))
+            })
+            .map(|(_, path)| path.path.as_str())
+            .collect();
+        api.paths
+            .iter()
+            .map(|(_, path)| path.path.as_str())
+            .filter(|path| {
+                excluded.contains(path)
+                    || path
+                        .rmatch_indices("::")
+                        .any(|(i, _)| excluded.contains(&path[..i]))
+            })
+            .map(|path| path.to_owned())
+            .collect()
+    }
+
+    pub fn mark_experimental(&self, api: &mut crate_api::Api) {
+        for (_, path) in api.paths.iter_mut() {
+            if self
+                .experimental
+                .iter()
+                .any(|glob| glob.matches(&path.path))
+            {
+                path.is_experimental = true;
+            }
+        }
+    }
+
+    /// Drop changes to excluded paths and apply severity overrides
+    pub fn filter_diffs(
+        &self,
+        before: &crate_api::Api,
+        after: &crate_api::Api,
+        diffs: &mut Vec<crate_api::diff::Diff>,
+    ) {
+        let before_excluded = self.excluded_paths(before);
+        let after_excluded = self.excluded_paths(after);
+        let is_excluded = |api: &crate_api::Api,
+                           excluded: &HashSet<String>,
+                           location: Option<crate_api::diff::Location>| {
+            location
+                .and_then(|location| location.path_id)
+                .and_then(|path_id| api.paths.get(path_id))
+                .map(|path| excluded.contains(&path.path))
+                .unwrap_or(false)
+        };
+        diffs.retain(|diff| {
+            !is_excluded(before, &before_excluded, diff.before)
+                && !is_excluded(after, &after_excluded, diff.after)
+        });
+        for diff in diffs.iter_mut() {
+            if let Some(severity) = self.severity.get(diff.id.name) {
+                diff.severity = *severity;
+            }
+        }
+
+        for allow in &self.allow {
+            let mut used = false;
+            for diff in diffs.iter_mut() {
+                if allow.matches(before, after, diff) {
+                    ::log::debug!(
+                        "Allowing `{}`: {}",
+                        diff.id.name,
+                        allow.reason.as_deref().unwrap_or("no reason given")
+                    );
+                    diff.severity = crate_api::diff::Severity::Allow;
+                    used = true;
+                }
+            }
+            // Like an `#[expect]`, so acknowledgements don't outlive what they were for
+            if !used {
+                ::log::warn!(
+                    "`allow` for `{}`{} matched no change; remove it",
+                    allow.id,
+                    allow
+                        .path
+                        .as_ref()
+                        .map(|path| format!(" at `{}`", path))
+                        .unwrap_or_default()
+                );
+            }
+        }
+    }
+}
+
+/// Project-wide defaults, shared by every developer and CI job
+pub const FILE: &str = "crate-api.toml";
+
+/// Settings from [`FILE`]
+///
+/// Command-line flags take precedence, and package settings are the defaults for
+/// `[package.metadata.crate-api]`.
+#[derive(Clone, Debug, Default, serde::Deserialize)]
+#[serde(rename_all = "kebab-case")]
+#[serde(default)]
+pub struct ProjectConfig {
+    pub features: Vec<String>,
+    pub no_default_features: bool,
+    pub all_features: bool,
+    pub toolchain: Option<String>,
+    pub target: Option<String>,
+    pub format: Option<crate::args::Format>,
+    /// Extra flags for rustdoc, like `["--cfg", "docsrs"]`
+    pub rustdocflags: Vec<String>,
+    /// Environment variables for `cargo doc`, like for build scripts
+    pub env: BTreeMap<String, String>,
+    #[serde(flatten)]
+    pub package: PackageConfig,
+}
+
+impl ProjectConfig {
+    /// Load the nearest [`FILE`] in `dir` or its ancestors
+    pub fn discover(dir: &std::path::Path) -> Result<Self, eyre::Report> {
+        match dir
+            .ancestors()
+            .map(|dir| dir.join(FILE))
+            .find(|path| path.is_file())
+        {
+            Some(path) => Self::load(&path),
+            None => Ok(Self::default()),
+        }
+    }
+
+    pub fn load(path: &std::path::Path) -> Result<Self, eyre::Report> {
+        let content = std::fs::read_to_string(path)
+            .map_err(|e| eyre::eyre!("Failed when reading {}: {}", path.display(), e))?;
+        Self::parse(&content).map_err(|e| eyre::eyre!("Invalid {}: {}", path.display(), e))
+    }
+
+    /// Parse the contents of a [`FILE`]
+    fn parse(content: &str) -> Result<Self, eyre::Report> {
+        let config: Self = toml_edit::easy::from_str(content)?;
+        let allowed = config.package.allow.iter().map(|allow| &allow.id);
+        for id in config.package.severity.keys().chain(allowed) {
+            if !crate_api::diff::ALL_IDS
+                .iter()
+                .any(|known| known.name == id)
+            {
+                eyre::bail!("unknown change `{}`", id);
+            }
+        }
+        Ok(config)
+    }
+}
+
+fn is_unstable(docs: &str) -> bool {
+    docs.split(|c: char| !c.is_alphanumeric())
+        .any(|word| word.eq_ignore_ascii_case("unstable"))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    /// Changes from `before` to an API with only its modules, removing everything else
+    fn removed(
+        before: crate_api::ApiBuilder,
+    ) -> (crate_api::Api, crate_api::Api, Vec<crate_api::diff::Diff>) {
+        let before = before.build();
+        let mut after = crate_api::ApiBuilder::new("mycrate");
+        for (_, path) in before.paths.iter() {
+            if path.kind == crate_api::PathKind::Module && path.path.contains("::") {
+                after.path(crate_api::PathKind::Module, path.path.clone());
+            }
+        }
+        let after = after.build();
+        let mut diffs = Vec::new();
+        crate_api::diff::diff(&before, &after, &mut diffs);
+        (before, after, diffs)
+    }
+
+    /// `mycrate::legacy::old`, `mycrate::net::connect`, and `mycrate::Stream`
+    fn builder() -> crate_api::ApiBuilder {
+        let mut builder = crate_api::ApiBuilder::new("mycrate");
+        builder.path(crate_api::PathKind::Function, "mycrate::legacy::old");
+        builder.path(crate_api::PathKind::Function, "mycrate::net::connect");
+        builder.path(crate_api::PathKind::Struct, "mycrate::Stream");
+        builder
+    }
+
+    fn changed_paths(
+        api: &crate_api::Api,
+        diffs: &[crate_api::diff::Diff],
+    ) -> Vec<(String, crate_api::diff::Severity)> {
+        let mut paths: Vec<_> = diffs
+            .iter()
+            .filter_map(|diff| {
+                let path_id = diff.before?.path_id?;
+                Some((api.paths.get(path_id)?.path.clone(), diff.severity))
+            })
+            .collect();
+        paths.sort_by(|a, b| a.0.cmp(&b.0));
+        paths.dedup();
+        paths
+    }
+
+    #[test]
+    fn parse() {
+        let config = ProjectConfig::parse(
+            r#"
+features = ["std"]
+toolchain = "nightly-2022-01-01"
+format = "md"
+exclude = ["*::__private"]
+experimental = ["mycrate::unstable"]
+
+[severity]
+fn-arity-changed = "report"
+
+[[allow]]
+id = "path-removed"
+path = "mycrate::legacy"
+reason = "deprecated for a year"
+"#,
+        )
+        .unwrap();
+        assert_eq!(config.features, ["std"]);
+        assert_eq!(config.toolchain.as_deref(), Some("nightly-2022-01-01"));
+        assert_eq!(config.format, Some(crate::args::Format::Md));
+        assert_eq!(config.package.exclude[0].as_str(), "*::__private");
+        assert_eq!(config.package.experimental[0].as_str(), "mycrate::unstable");
+        assert_eq!(
+            config.package.severity.get("fn-arity-changed"),
+            Some(&crate_api::diff::Severity::Report)
+        );
+        assert_eq!(config.package.allow[0].id, "path-removed");
+        assert_eq!(
+            config.package.allow[0]
+                .path
+                .as_ref()
+                .map(|path| path.as_str()),
+            Some("mycrate::legacy")
+        );
+    }
+
+    #[test]
+    fn parse_empty() {
+        let config = ProjectConfig::parse("").unwrap();
+        assert!(config.features.is_empty());
+        assert!(!config.package.frozen);
+        assert!(config.package.allow.is_empty());
+    }
+
+    #[test]
+    fn parse_unknown_change() {
+        let err = ProjectConfig::parse("[severity]\nno-such-change = \"allow\"\n").unwrap_err();
+        assert_eq!(err.to_string(), "unknown change `no-such-change`");
+
+        let err = ProjectConfig::parse("[[allow]]\nid = \"no-such-change\"\n").unwrap_err();
+        assert_eq!(err.to_string(), "unknown change `no-such-change`");
+    }
+
+    #[test]
+    fn update_layers() {
+        let mut config = ProjectConfig::parse(
+            "exclude = [\"mycrate::a\"]\n[severity]\nfn-arity-changed = \"report\"\n",
+        )
+        .unwrap()
+        .package;
+        let package: PackageConfig = serde_json::from_value(serde_json::json!({
+            "frozen": true,
+            "exclude": ["mycrate::b"],
+            "severity": {"fn-arity-changed": "allow"},
+        }))
+        .unwrap();
+        config.update(package);
+        assert!(config.frozen);
+        let exclude: Vec<_> = config.exclude.iter().map(|glob| glob.as_str()).collect();
+        assert_eq!(exclude, ["mycrate::a", "mycrate::b"]);
+        assert_eq!(
+            config.severity.get("fn-arity-changed"),
+            Some(&crate_api::diff::Severity::Allow)
+        );
+    }
+
+    #[test]
+    fn excluded_paths() {
+        let mut builder = builder();
+        let mut hidden = crate_api::Item::new();
+        hidden.is_hidden = true;
+        builder.path_with(crate_api::PathKind::Function, "mycrate::hidden", hidden);
+        let mut unstable = crate_api::Item::new();
+        unstable.docs = Some("Unstable: may change at any time".to_owned());
+        builder.path_with(crate_api::PathKind::Function, "mycrate::unstable", unstable);
+        let api = builder.build();
+
+        let mut config = PackageConfig::default();
+        config
+            .exclude
+            .push(crate_api::PathGlob::new("mycrate::legacy"));
+        let mut excluded: Vec<_> = config.excluded_paths(&api).into_iter().collect();
+        excluded.sort();
+        assert_eq!(excluded, ["mycrate::legacy", "mycrate::legacy::old"]);
+
+        config.exclude_hidden = true;
+        config.exclude_unstable = true;
+        let mut excluded: Vec<_> = config.excluded_paths(&api).into_iter().collect();
+        excluded.sort();
+        assert_eq!(
+            excluded,
+            [
+                "mycrate::hidden",
+                "mycrate::legacy",
+                "mycrate::legacy::old",
+                "mycrate::unstable"
+            ]
+        );
+    }
+
+    #[test]
+    fn is_unstable_words() {
+        assert!(is_unstable("Unstable: may change"));
+        assert!(is_unstable("This is *unstable*."));
+        assert!(!is_unstable("Unstableness is bad"));
+        assert!(!is_unstable("Stable"));
+    }
+
+    #[test]
+    fn filter_diffs_excludes() {
+        let (before, after, mut diffs) = removed(builder());
+        let mut config = PackageConfig::default();
+        config
+            .exclude
+            .push(crate_api::PathGlob::new("mycrate::legacy"));
+        config.filter_diffs(&before, &after, &mut diffs);
+        let paths: Vec<_> = changed_paths(&before, &diffs)
+            .into_iter()
+            .map(|(path, _)| path)
+            .collect();
+        assert!(!paths.iter().any(|path| path.starts_with("mycrate::legacy")));
+        assert!(paths.iter().any(|path| path == "mycrate::net::connect"));
+    }
+
+    #[test]
+    fn filter_diffs_severity() {
+        let (before, after, mut diffs) = removed(builder());
+        let id = diffs[0].id.name;
+        let mut config = PackageConfig::default();
+        config
+            .severity
+            .insert(id.to_owned(), crate_api::diff::Severity::Report);
+        config.filter_diffs(&before, &after, &mut diffs);
+        assert!(diffs
+            .iter()
+            .filter(|diff| diff.id.name == id)
+            .all(|diff| diff.severity == crate_api::diff::Severity::Report));
+    }
+
+    #[test]
+    fn filter_diffs_allow() {
+        let (before, after, mut diffs) = removed(builder());
+        assert_eq!(changed_paths(&before, &diffs).len(), 3);
+        let id = diffs[0].id.name;
+        let mut config = PackageConfig::default();
+        config.allow.push(Allow {
+            id: id.to_owned(),
+            path: Some(crate_api::PathGlob::new("mycrate::net")),
+            reason: None,
+        });
+        config.filter_diffs(&before, &after, &mut diffs);
+        for (path, severity) in changed_paths(&before, &diffs) {
+            let allowed = severity == crate_api::diff::Severity::Allow;
+            assert_eq!(allowed, path.starts_with("mycrate::net"), "{}", path);
+        }
+    }
+}
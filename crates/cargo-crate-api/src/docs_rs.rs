@@ -0,0 +1,126 @@
+//! Prebuilt rustdoc JSON for published crates, from docs.rs
+//!
+//! Building an old release locally is slow and can fail outright, like from yanked
+//! dependencies or a newer MSRV.  docs.rs keeps the JSON it built for each release, so use it
+//! when it describes the same API we would have built.
+
+/// The oldest rustdoc JSON format docs.rs serves, as it only started keeping JSON with it
+const OLDEST_FORMAT_VERSION: u32 = 45;
+
+/// The rustdoc JSON docs.rs built for `pkg`, if it can stand in for building it locally
+///
+/// We document with all features, so releases that docs.rs documented with some other feature
+/// selection are built locally.  Failures to download are only logged, as the caller falls
+/// back to building.
+pub fn fetch(pkg: &cargo_metadata::Package) -> Option<String> {
+    let url = url(pkg, rustdoc_json_types_fork::FORMAT_VERSION)?;
+    tracing::debug!(%url, "fetching rustdoc json");
+    match download(&url) {
+        Ok(raw) => Some(raw),
+        Err(err) => {
+            tracing::info!(
+                "docs.rs has no rustdoc json for {} {} ({}); building locally",
+                pkg.name,
+                pkg.version,
+                err
+            );
+            None
+        }
+    }
+}
+
+/// Where docs.rs keeps the rustdoc JSON for `pkg` in `format_version`, if it can have it
+fn url(pkg: &cargo_metadata::Package, format_version: u32) -> Option<String> {
+    if format_version < OLDEST_FORMAT_VERSION {
+        tracing::debug!(
+            format_version,
+            "docs.rs doesn't serve this rustdoc json format; building locally"
+        );
+        return None;
+    }
+    if !all_features(pkg) {
+        tracing::debug!(
+            package = %pkg.name,
+            "docs.rs doesn't document all features; building locally"
+        );
+        return None;
+    }
+
+    Some(format!(
+        "https://docs.rs/crate/{}/{}/json/{}",
+        pkg.name, pkg.version, format_version
+    ))
+}
+
+/// Whether docs.rs documented `pkg` with all features, from `[package.metadata.docs.rs]`
+fn all_features(pkg: &cargo_metadata::Package) -> bool {
+    pkg.metadata
+        .get("docs")
+        .and_then(|docs| docs.get("rs"))
+        .and_then(|docs_rs| docs_rs.get("all-features"))
+        .and_then(|all_features| all_features.as_bool())
+        .unwrap_or(false)
+}
+
+fn download(url: &str) -> Result<String, eyre::Report> {
+    let response = ureq::get(url)
+        .timeout(std::time::Duration::from_secs(30))
+        .call()?;
+    let mut compressed = Vec::new();
+    std::io::Read::read_to_end(&mut response.into_reader(), &mut compressed)?;
+    let json = zstd::decode_all(&compressed[..])
+        .map_err(|e| eyre::eyre!("Failed when decompressing {}: {}", url, e))?;
+    Ok(String::from_utf8(json)?)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn package(metadata: serde_json::Value) -> cargo_metadata::Package {
+        serde_json::from_value(serde_json::json!({
+            "name": "foo",
+            "version": "1.2.3",
+            "id": "foo 1.2.3 (registry+https://github.com/rust-lang/crates.io-index)",
+            "dependencies": [],
+            "targets": [],
+            "features": {},
+            "manifest_path": "/foo/Cargo.toml",
+            "metadata": metadata,
+        }))
+        .unwrap()
+    }
+
+    fn documented_all_features() -> cargo_metadata::Package {
+        package(serde_json::json!({"docs": {"rs": {"all-features": true}}}))
+    }
+
+    #[test]
+    fn url_all_features() {
+        assert_eq!(
+            url(&documented_all_features(), OLDEST_FORMAT_VERSION).as_deref(),
+            Some("https://docs.rs/crate/foo/1.2.3/json/45")
+        );
+    }
+
+    #[test]
+    fn url_default_features() {
+        assert_eq!(
+            url(&package(serde_json::Value::Null), OLDEST_FORMAT_VERSION),
+            None
+        );
+        let package = package(serde_json::json!({"docs": {"rs": {"all-features": false}}}));
+        assert_eq!(url(&package, OLDEST_FORMAT_VERSION), None);
+    }
+
+    #[test]
+    fn url_unserved_format() {
+        assert_eq!(
+            url(
+                &documented_all_features(),
+                rustdoc_json_types_fork::FORMAT_VERSION
+            ),
+            None
+        );
+    }
+}
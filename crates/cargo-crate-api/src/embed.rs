@@ -0,0 +1,61 @@
+//! API snapshots shipped inside the published package
+//!
+//! Downstream tools can read a crate's API straight from the `.crate` file, without needing a
+//! nightly toolchain to extract it.
+
+/// Snapshot location, relative to the package root
+pub const FILE: &str = "api.json.zst";
+
+pub fn path(pkg: &cargo_metadata::Package) -> std::path::PathBuf {
+    pkg.manifest_path
+        .parent()
+        .expect("manifests are always in a directory")
+        .as_std_path()
+        .join(FILE)
+}
+
+pub fn write(path: &std::path::Path, api: &crate_api::Api) -> Result<(), eyre::Report> {
+    let json = serde_json::to_vec(api)?;
+    let compressed = zstd::encode_all(&json[..], zstd::DEFAULT_COMPRESSION_LEVEL)?;
+    std::fs::write(path, compressed)
+        .map_err(|e| eyre::eyre!("Failed when writing {}: {}", path.display(), e))
+}
+
+/// The snapshot at `path`, if there is one
+pub fn read(path: &std::path::Path) -> Result<Option<crate_api::Api>, eyre::Report> {
+    let compressed = match std::fs::read(path) {
+        Ok(compressed) => compressed,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(None),
+        Err(e) => eyre::bail!("Failed when reading {}: {}", path.display(), e),
+    };
+    let json = zstd::decode_all(&compressed[..])
+        .map_err(|e| eyre::eyre!("Failed when decompressing {}: {}", path.display(), e))?;
+    let api = serde_json::from_slice(&json)
+        .map_err(|e| eyre::eyre!("Failed when parsing {}: {}", path.display(), e))?;
+    Ok(Some(api))
+}
+
+/// Whether the snapshot describes the same API contract as `api`
+///
+/// Docs aren't part of the contract and spans vary with where the source was built from.
+pub fn is_current(embedded: &crate_api::Api, api: &crate_api::Api) -> bool {
+    mismatches(embedded, api).is_empty()
+}
+
+/// Paths the snapshot disagrees with `api` on, including `features` for the feature set
+pub fn mismatches(embedded: &crate_api::Api, api: &crate_api::Api) -> Vec<String> {
+    let mut mismatches = Vec::new();
+    if embedded.features != api.features {
+        mismatches.push("features".to_owned());
+    }
+
+    let embedded = crate::freeze::surface(embedded);
+    let api = crate::freeze::surface(api);
+    let paths: std::collections::BTreeSet<_> = embedded.keys().chain(api.keys()).collect();
+    for key in paths {
+        if embedded.get(key) != api.get(key) && !mismatches.contains(&key.0) {
+            mismatches.push(key.0.clone());
+        }
+    }
+    mismatches
+}
@@ -0,0 +1,144 @@
+//! Stable error codes, so automation can branch on why a run failed
+
+use std::io::Write;
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq, serde::Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum ErrorCode {
+    /// `cargo doc` could not be run or failed
+    DocBuild,
+    /// rustdoc emitted an unsupported JSON format version
+    FormatVersion,
+    /// rustdoc's JSON could not be turned into an API
+    ApiParse,
+    /// The API to compare against could not be found or built
+    BaselineUnavailable,
+    /// Invalid arguments, manifests, or configuration
+    Config,
+    /// The rustup toolchain isn't installed or available
+    Toolchain,
+    /// A check ran and found problems, like changes to a frozen API
+    CheckFailed,
+    Io,
+    Unknown,
+}
+
+/// An error with an explicit [`ErrorCode`], for failures that aren't identifiable by type
+#[derive(Debug)]
+pub struct CodedError {
+    code: ErrorCode,
+    message: String,
+}
+
+impl CodedError {
+    pub fn new(code: ErrorCode, message: impl std::fmt::Display) -> Self {
+        Self {
+            code,
+            message: message.to_string(),
+        }
+    }
+}
+
+/// Shorthand for an [`eyre::Report`] of a [`CodedError`]
+pub fn coded(code: ErrorCode, message: impl std::fmt::Display) -> eyre::Report {
+    eyre::Report::new(CodedError::new(code, message))
+}
+
+/// A [`ErrorCode::Config`] error for a `--format` that `mode` can't render
+pub fn unsupported_format(format: crate::args::Format, mode: &str) -> eyre::Report {
+    coded(
+        ErrorCode::Config,
+        format!("{} format is not supported with `{}`", format, mode),
+    )
+}
+
+impl std::fmt::Display for CodedError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        self.message.fmt(f)
+    }
+}
+
+impl std::error::Error for CodedError {}
+
+pub fn code_of(err: &eyre::Report) -> ErrorCode {
+    if let Some(err) = err.downcast_ref::<CodedError>() {
+        return err.code;
+    }
+    if let Some(err) = err.downcast_ref::<crate_api::Error>() {
+        return match err.kind() {
+            crate_api::ErrorKind::DocBuild => ErrorCode::DocBuild,
+            crate_api::ErrorKind::FormatVersion => ErrorCode::FormatVersion,
+            crate_api::ErrorKind::ApiParse => ErrorCode::ApiParse,
+            crate_api::ErrorKind::Package => ErrorCode::Config,
+            crate_api::ErrorKind::Toolchain => ErrorCode::Toolchain,
+            _ => ErrorCode::Unknown,
+        };
+    }
+    if err.downcast_ref::<cargo_metadata::Error>().is_some() {
+        return ErrorCode::Config;
+    }
+    if err.downcast_ref::<std::io::Error>().is_some() {
+        return ErrorCode::Io;
+    }
+    ErrorCode::Unknown
+}
+
+#[derive(serde::Serialize)]
+#[serde(rename_all = "snake_case")]
+struct ErrorReport<'e> {
+    code: ErrorCode,
+    message: String,
+    package: Option<&'e str>,
+}
+
+/// Report `err` to the user, in `format`
+pub fn report(format: crate::args::ErrorFormat, package: Option<&str>, err: &eyre::Report) {
+    match format {
+        crate::args::ErrorFormat::Human => {
+            ::log::error!("{}", err);
+        }
+        crate::args::ErrorFormat::Json => {
+            let report = ErrorReport {
+                code: code_of(err),
+                message: err.to_string(),
+                package,
+            };
+            let report = serde_json::to_string(&report).expect("always serializable");
+            let _ = writeln!(std::io::stderr(), "{}", report);
+        }
+    }
+}
+
+/// Exit code when the API had differences or violations that were checked for
+pub const FOUND: proc_exit::Code = proc_exit::Code::FAILURE;
+/// Exit code when the tool could not do its job, like with invalid arguments or a failed build
+pub const TOOL_ERROR: proc_exit::Code = proc_exit::Code::UNKNOWN;
+
+/// The exit code for `err`, so CI can tell findings apart from the tool failing
+pub fn exit_code(err: &eyre::Report) -> proc_exit::Code {
+    match code_of(err) {
+        ErrorCode::CheckFailed => FOUND,
+        _ => TOOL_ERROR,
+    }
+}
+
+/// [`report`] `err` and exit with its [`exit_code`]
+pub fn fail(
+    format: crate::args::ErrorFormat,
+    package: Option<&str>,
+    err: &eyre::Report,
+) -> proc_exit::ExitResult {
+    report(format, package, err);
+    exit_code(err).ok()
+}
+
+#[test]
+fn exit_codes() {
+    assert_eq!(exit_code(&coded(ErrorCode::CheckFailed, "")).raw(), 1);
+    assert_eq!(exit_code(&coded(ErrorCode::Config, "")).raw(), 2);
+    assert_eq!(exit_code(&eyre::eyre!("")).raw(), 2);
+    assert_eq!(
+        exit_code(&unsupported_format(crate::args::Format::Csv, "--api")).raw(),
+        2
+    );
+}
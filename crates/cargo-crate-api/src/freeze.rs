@@ -0,0 +1,173 @@
+//! Detect any change to a frozen API, not just breaking ones
+
+use std::collections::BTreeMap;
+use std::collections::HashSet;
+
+/// Describe every difference in the API surface between `before` and `after`
+///
+/// `diffs` only add what the surface doesn't cover, like trait impls and the manifest.
+pub fn surface_changes(
+    before: &crate_api::Api,
+    after: &crate_api::Api,
+    diffs: &[crate_api::diff::Diff],
+    excluded: &HashSet<String>,
+) -> Vec<String> {
+    let mut before_paths = surface(before);
+    before_paths.retain(|(path, _), _| !excluded.contains(path));
+    let mut after_paths = surface(after);
+    after_paths.retain(|(path, _), _| !excluded.contains(path));
+
+    let mut changes = Vec::new();
+    let mut changed_paths = HashSet::new();
+    for (path, kind) in before_paths.keys() {
+        if !after_paths.contains_key(&(path.clone(), *kind)) {
+            changes.push(format!("removed {} `{}`", kind, path));
+            changed_paths.insert(path.as_str());
+        }
+    }
+    for ((path, kind), after_item) in after_paths.iter() {
+        match before_paths.get(&(path.clone(), *kind)) {
+            None => changes.push(format!("added {} `{}`", kind, path)),
+            Some(before_item) if before_item != after_item => {
+                changes.push(format!("changed {} `{}`", kind, path))
+            }
+            Some(_) => continue,
+        }
+        changed_paths.insert(path.as_str());
+    }
+    // Only what the surface can't see, like trait impls and the manifest
+    for diff in diffs {
+        if diff.severity == crate_api::diff::Severity::Allow {
+            continue;
+        }
+        let (api, location) = crate::report::diff_location(before, after, diff);
+        let path = location
+            .path_id
+            .and_then(|path_id| api.paths.get(path_id))
+            .map(|path| path.path.as_str());
+        if path.map_or(false, |path| {
+            changed_paths.contains(path) || excluded.contains(path)
+        }) {
+            continue;
+        }
+        changes.push(format!(
+            "`{}`: {}",
+            crate::report::location_name(api, location),
+            diff.id.explanation
+        ));
+    }
+    changes
+}
+
+pub type Surface = BTreeMap<(String, crate_api::PathKind), serde_json::Value>;
+
+/// Index the API by path, for comparing across `Api`s
+pub fn surface(api: &crate_api::Api) -> Surface {
+    api.paths
+        .iter()
+        .map(|(_, path)| {
+            let item = path
+                .item_id
+                .and_then(|item_id| api.items.get(item_id))
+                .map(comparable)
+                .unwrap_or_default();
+            ((path.path.clone(), path.kind), item)
+        })
+        .collect()
+}
+
+/// Like [`surface`] but with the crate name replaced by `crate`, for comparing different crates
+pub fn normalized_surface(api: &crate_api::Api) -> Surface {
+    let root = api
+        .root_id
+        .and_then(|root_id| api.paths.get(root_id))
+        .map(|root| root.path.as_str())
+        .unwrap_or_default();
+    surface(api)
+        .into_iter()
+        .map(|((path, kind), item)| {
+            let path = match path.strip_prefix(root) {
+                Some(rest) if rest.is_empty() || rest.starts_with("::") => format!("crate{}", rest),
+                _ => path,
+            };
+            ((path, kind), item)
+        })
+        .collect()
+}
+
+/// Drop the parts of an item that don't affect its API
+fn comparable(item: &crate_api::Item) -> serde_json::Value {
+    let mut value = serde_json::to_value(item).unwrap_or_default();
+    if let Some(fields) = value.as_object_mut() {
+        // Ids are only meaningful within one `Api`
+        fields.remove("crate_id");
+        fields.remove("span");
+        fields.remove("docs");
+    }
+    value
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn api(rust_version: &str, paths: &[&str]) -> crate_api::Api {
+        let mut api = crate_api::Api::new();
+        let root = api
+            .paths
+            .push(crate_api::Path::new(crate_api::PathKind::Module, "foo"));
+        api.root_id = Some(root);
+        for path in paths {
+            let mut item = crate_api::Item::new();
+            item.name = path.rsplit("::").next().map(|name| name.to_owned());
+            let mut path = crate_api::Path::new(crate_api::PathKind::Struct, *path);
+            path.item_id = Some(api.items.push(item));
+            let path = api.paths.push(path);
+            api.paths.get_mut(root).unwrap().children.push(path);
+        }
+        api.rust_version = Some(rust_version.to_owned());
+        api
+    }
+
+    fn changes(before: &crate_api::Api, after: &crate_api::Api) -> Vec<String> {
+        let mut diffs = Vec::new();
+        crate_api::diff::diff(before, after, &mut diffs);
+        surface_changes(before, after, &diffs, &HashSet::new())
+    }
+
+    #[test]
+    fn surface_only() {
+        let before = api("1.56", &["foo::Bar"]);
+        let after = api("1.56", &[]);
+        // Also a diff, but already counted
+        let mut diffs = Vec::new();
+        crate_api::diff::diff(&before, &after, &mut diffs);
+        assert!(!diffs.is_empty());
+        assert_eq!(changes(&before, &after), ["removed struct `foo::Bar`"]);
+    }
+
+    #[test]
+    fn manifest() {
+        let before = api("1.56", &[]);
+        let after = api("1.60", &[]);
+        assert_eq!(
+            changes(&before, &after),
+            [format!(
+                "`foo`: {}",
+                crate_api::diff::RUST_VERSION_RAISED.explanation
+            )]
+        );
+    }
+
+    #[test]
+    fn allowed() {
+        let before = api("1.56", &[]);
+        let after = api("1.60", &[]);
+        let mut diffs = Vec::new();
+        crate_api::diff::diff(&before, &after, &mut diffs);
+        for diff in &mut diffs {
+            diff.severity = crate_api::diff::Severity::Allow;
+        }
+        assert!(surface_changes(&before, &after, &diffs, &HashSet::new()).is_empty());
+    }
+}
@@ -0,0 +1,45 @@
+//! API baselines committed alongside the package
+//!
+//! Unlike an embedded snapshot, the baseline is meant to be reviewed, so it is pretty-printed
+//! and leaves out spans, which churn with every unrelated edit.
+
+/// Baseline location, relative to the package root
+pub const FILE: &str = "api.lock";
+
+pub fn path(pkg: &cargo_metadata::Package) -> std::path::PathBuf {
+    pkg.manifest_path
+        .parent()
+        .expect("manifests are always in a directory")
+        .as_std_path()
+        .join(FILE)
+}
+
+pub fn write(path: &std::path::Path, api: &crate_api::Api) -> Result<(), eyre::Report> {
+    let mut api = api.clone();
+    for (_, path) in api.paths.iter_mut() {
+        path.span = None;
+    }
+    for (_, item) in api.items.iter_mut() {
+        item.span = None;
+    }
+    for (_, impl_) in api.impls.iter_mut() {
+        impl_.span = None;
+    }
+
+    let mut json = serde_json::to_string_pretty(&api)?;
+    json.push('\n');
+    std::fs::write(path, json)
+        .map_err(|e| eyre::eyre!("Failed when writing {}: {}", path.display(), e))
+}
+
+/// The baseline at `path`, if there is one
+pub fn read(path: &std::path::Path) -> Result<Option<crate_api::Api>, eyre::Report> {
+    let json = match std::fs::read_to_string(path) {
+        Ok(json) => json,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(None),
+        Err(e) => eyre::bail!("Failed when reading {}: {}", path.display(), e),
+    };
+    let api = serde_json::from_str(&json)
+        .map_err(|e| eyre::eyre!("Failed when parsing {}: {}", path.display(), e))?;
+    Ok(Some(api))
+}
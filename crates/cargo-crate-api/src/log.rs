@@ -2,8 +2,14 @@ use std::io::Write;
 
 pub fn init_logging(
     level: clap_verbosity_flag::Verbosity<clap_verbosity_flag::InfoLevel>,
+    format: crate::args::LogFormat,
     colored: bool,
 ) {
+    if format == crate::args::LogFormat::Json {
+        init_json_logging(level);
+        return;
+    }
+
     if let Some(level) = level.log_level() {
         let palette = if colored {
             Palette::colored()
@@ -56,6 +62,27 @@ pub fn init_logging(
     }
 }
 
+/// Log as JSON lines, reporting when each phase's span closes along with how long it took
+fn init_json_logging(level: clap_verbosity_flag::Verbosity<clap_verbosity_flag::InfoLevel>) {
+    let level = match level.log_level() {
+        Some(log::Level::Error) => tracing_subscriber::filter::LevelFilter::ERROR,
+        Some(log::Level::Warn) => tracing_subscriber::filter::LevelFilter::WARN,
+        Some(log::Level::Info) => tracing_subscriber::filter::LevelFilter::INFO,
+        Some(log::Level::Debug) => tracing_subscriber::filter::LevelFilter::DEBUG,
+        Some(log::Level::Trace) => tracing_subscriber::filter::LevelFilter::TRACE,
+        None => tracing_subscriber::filter::LevelFilter::OFF,
+    };
+    // Also installs a `log` adapter so `log` records are included
+    tracing_subscriber::fmt()
+        .json()
+        .with_max_level(level)
+        .with_span_events(tracing_subscriber::fmt::format::FmtSpan::CLOSE)
+        .with_current_span(true)
+        .with_span_list(true)
+        .with_writer(std::io::stderr)
+        .init();
+}
+
 #[derive(Copy, Clone, Debug)]
 struct Palette {
     error: yansi::Style,
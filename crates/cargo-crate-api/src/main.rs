@@ -1,12 +1,25 @@
 use std::io::Write;
 
 use clap::Parser;
-use proc_exit::WithCodeResultExt;
 
 mod args;
+mod config;
+mod docs_rs;
+mod embed;
+mod error;
+mod freeze;
+mod lock;
 mod log;
+mod output;
+mod owners;
+mod plugin;
 mod report;
+mod serve;
+mod template;
+mod usage;
+mod watch;
 
+#[allow(deprecated)] // HACK: `human-panic` 1.0 predates `PanicHookInfo`
 fn main() {
     human_panic::setup_panic!();
     let result = run();
@@ -15,7 +28,7 @@ fn main() {
 
 fn run() -> proc_exit::ExitResult {
     // clap2's `get_matches` uses Failure rather than Unknown, so bypass it for `get_matches_safe`.
-    let args::Command::CrateApi(args) = match args::Command::try_parse() {
+    let args::Command::CrateApi(mut args) = match args::Command::try_parse() {
         Ok(args) => args,
         Err(e) if e.use_stderr() => {
             return Err(proc_exit::Code::UNKNOWN.with_message(e));
@@ -29,173 +42,2493 @@ fn run() -> proc_exit::ExitResult {
     args.color.apply();
     let colored_stderr = concolor_control::get(concolor_control::Stream::Stderr).ansi_color();
 
-    log::init_logging(args.verbose.clone(), colored_stderr);
+    log::init_logging(args.verbose.clone(), args.log_format, colored_stderr);
 
-    let mut success = true;
+    if let Some(args::Subcommand::Completions { shell }) = args.command {
+        // Works outside of packages, leaving out package names
+        return match completions(shell, &args.manifest) {
+            Ok(()) => proc_exit::Code::SUCCESS.ok(),
+            Err(err) => error::fail(args.error_format, None, &err),
+        };
+    }
+
+    if args.check
+        && !matches!(
+            args.mode(),
+            args::Mode::Api
+                | args::Mode::Embed
+                | args::Mode::Diff
+                | args::Mode::Breaking
+                | args::Mode::DiffManifests
+        )
+    {
+        let err = error::coded(
+            error::ErrorCode::Config,
+            "`--check` is only supported with `--api`, `--embed`, `--diff`, `--breaking`, and `diff`",
+        );
+        return error::fail(args.error_format, None, &err);
+    }
+    if args.all_targets && args.mode() != args::Mode::Api {
+        let err = error::coded(
+            error::ErrorCode::Config,
+            "`--all-targets` is only supported with `--api`",
+        );
+        return error::fail(args.error_format, None, &err);
+    }
+
+    if let Some(args::Subcommand::Diff { new: Some(new), .. }) = &args.command {
+        // The package to diff, or at least where to find the project config
+        if args.manifest.manifest_path.is_none() {
+            args.manifest.manifest_path = Some(new.clone());
+        }
+    }
+    // Snapshots can be merged from anywhere
+    if args.manifest.manifest_path.is_none() && args.mode() != args::Mode::Merge {
+        // Like cargo, work from any directory within the package
+        let cwd = match std::env::current_dir() {
+            Ok(cwd) => cwd,
+            Err(err) => return error::fail(args.error_format, None, &eyre::Report::new(err)),
+        };
+        match crate_api::manifest::discover(&cwd) {
+            Ok(manifest_path) => args.manifest.manifest_path = Some(manifest_path),
+            Err(err) => {
+                return error::fail(args.error_format, None, &eyre::Report::new(err));
+            }
+        }
+    }
+    let config_dir = args
+        .manifest
+        .manifest_path
+        .as_deref()
+        .and_then(|manifest_path| manifest_path.parent())
+        .filter(|dir| !dir.as_os_str().is_empty())
+        .unwrap_or_else(|| std::path::Path::new("."));
+    match config::ProjectConfig::discover(config_dir) {
+        Ok(config) => args.apply_config(&config),
+        Err(err) => {
+            let err = error::coded(error::ErrorCode::Config, err);
+            return error::fail(args.error_format, None, &err);
+        }
+    }
+
+    let path = match args.output.as_deref() {
+        Some(path) => path,
+        None => return run_mode(&args),
+    };
+    // Hold the report back so a failed run leaves the file as it was
+    output::capture();
+    let result = run_mode(&args);
+    let report = output::take();
+    if result.is_ok() {
+        if let Err(err) = report.write_to(path) {
+            let err = error::coded(error::ErrorCode::Io, err);
+            return error::fail(args.error_format, None, &err);
+        }
+    }
+    result
+}
+
+fn run_mode(args: &args::Api) -> proc_exit::ExitResult {
+    let mut rustdoc = args.rustdoc();
+
+    let mut code = proc_exit::Code::SUCCESS;
+
+    let mode = args.mode();
+    if mode == args::Mode::Merge {
+        // Snapshots are already extracted, so there are no packages to work on
+        return match merge(&args.merge, &args.name, args.format, args.with_docs) {
+            Ok(()) => proc_exit::Code::SUCCESS.ok(),
+            Err(err) => error::fail(args.error_format, None, &err),
+        };
+    }
+
+    if let Some(args::Subcommand::Diff {
+        old: Some(old),
+        new: Some(new),
+        ..
+    }) = &args.command
+    {
+        // Neither needs to be in the workspace
+        return match diff_manifests(&rustdoc, old, new, args) {
+            Ok(()) => proc_exit::Code::SUCCESS.ok(),
+            Err(err) => error::fail(args.error_format, None, &err),
+        };
+    }
+
+    let metadata = match args
+        .manifest
+        .metadata()
+        .other_options(args.cargo_options())
+        .exec()
+    {
+        Ok(metadata) => metadata,
+        Err(err) => {
+            return error::fail(args.error_format, None, &eyre::Report::new(err));
+        }
+    };
+
+    if mode == args::Mode::VerifyCrate {
+        // The crate comes from the registry rather than the workspace
+        let spec = args.verify_crate.as_deref().unwrap();
+        return match verify_crate(&rustdoc, &metadata, spec, args.format) {
+            Ok(()) => proc_exit::Code::SUCCESS.ok(),
+            Err(err) => error::fail(args.error_format, None, &err),
+        };
+    }
+
+    if mode == args::Mode::Serve {
+        // Packages are picked per request, out of those selected
+        let (selected, _) = args.workspace.partition_packages(&metadata);
+        return match serve(&rustdoc, &metadata, selected, args.listen, args.docs_rs()) {
+            Ok(()) => proc_exit::Code::SUCCESS.ok(),
+            Err(err) => error::fail(args.error_format, None, &err),
+        };
+    }
+
+    if mode == args::Mode::Watch {
+        let (selected, _) = args.workspace.partition_packages(&metadata);
+        let mut watcher = watch::Watcher::new(
+            &rustdoc,
+            &metadata,
+            selected,
+            args.format,
+            args.error_format,
+        );
+        return match watcher.watch() {
+            Ok(()) => proc_exit::Code::SUCCESS.ok(),
+            Err(err) => error::fail(args.error_format, None, &err),
+        };
+    }
+
+    let base = match mode {
+        args::Mode::DumpRaw => None,
+        args::Mode::Api => None,
+        args::Mode::Metrics => None,
+        args::Mode::UnsafeReport => None,
+        args::Mode::Ffi => None,
+        args::Mode::Embed => None,
+        args::Mode::UpdateBaseline => None,
+        args::Mode::VerifyCrate => unreachable!("handled before selecting packages"),
+        args::Mode::Serve => unreachable!("handled before selecting packages"),
+        args::Mode::Watch => unreachable!("handled before selecting packages"),
+        args::Mode::DiffManifests => unreachable!("handled before loading metadata"),
+        args::Mode::Diff | args::Mode::CheckRelease => {
+            let base = args
+                .base()
+                .map(Ok)
+                .unwrap_or_else(|| find_default_base(metadata.workspace_root.as_std_path()))
+                .map_err(|err| error::coded(error::ErrorCode::BaselineUnavailable, err));
+            match base {
+                Ok(base) => Some(base),
+                Err(err) => {
+                    return error::fail(args.error_format, None, &err);
+                }
+            }
+        }
+        args::Mode::Compare => None,
+        args::Mode::DepCheck => None,
+        args::Mode::Bisect => None,
+        args::Mode::FeaturePowerset => None,
+        args::Mode::FeatureAdditivity => None,
+        args::Mode::FeatureDocs => None,
+        args::Mode::History => None,
+        args::Mode::Since => None,
+        args::Mode::Merge => unreachable!("handled before loading metadata"),
+        args::Mode::Breaking => match find_default_base(metadata.workspace_root.as_std_path()) {
+            Ok(base) => Some(base),
+            Err(err) => {
+                let err = error::coded(error::ErrorCode::BaselineUnavailable, err);
+                return error::fail(args.error_format, None, &err);
+            }
+        },
+    };
+
+    let owners = match args.group_by {
+        Some(args::GroupBy::Owner) => {
+            let path = args.owners.as_deref().expect("clap enforces `--owners`");
+            match owners::Owners::load(path) {
+                Ok(owners) => Some(owners),
+                Err(err) => {
+                    let err = error::coded(error::ErrorCode::Config, err);
+                    return error::fail(args.error_format, None, &err);
+                }
+            }
+        }
+        None => None,
+    };
+
+    if mode == args::Mode::Api && (args.workspace.workspace || args.workspace.all) {
+        // One report covering every member, rather than one per package
+        let (selected, _) = args.workspace.partition_packages(&metadata);
+        let res = if args.scope.is_some() {
+            Err(error::coded(
+                error::ErrorCode::Config,
+                "`--scope` is not supported with `--workspace`",
+            ))
+        } else if args.all_targets {
+            Err(error::coded(
+                error::ErrorCode::Config,
+                "`--all-targets` is not supported with `--workspace`",
+            ))
+        } else if owners.is_some() {
+            Err(error::coded(
+                error::ErrorCode::Config,
+                "`--group-by` is not supported with `--workspace`",
+            ))
+        } else {
+            workspace_api(&rustdoc, &metadata, &selected, args)
+        };
+        return match res {
+            Ok(()) => proc_exit::Code::SUCCESS.ok(),
+            Err(err) => error::fail(args.error_format, None, &err),
+        };
+    }
+
+    let (selected, _) = args.workspace.partition_packages(&metadata);
+    if let Some(path) = args.rustdoc_json.as_deref() {
+        // The JSON only describes one crate, and not any baseline
+        let raw = if !matches!(
+            mode,
+            args::Mode::Api | args::Mode::DumpRaw | args::Mode::UpdateBaseline
+        ) {
+            Err(error::coded(
+                error::ErrorCode::Config,
+                "`--rustdoc-json` is only supported with `--api`, `--dump-raw`, and `--update-baseline`",
+            ))
+        } else if selected.len() != 1 {
+            Err(error::coded(
+                error::ErrorCode::Config,
+                "`--rustdoc-json` requires selecting one package",
+            ))
+        } else {
+            read_rustdoc_json(path)
+        };
+        match raw {
+            Ok(raw) => rustdoc = rustdoc.raw(raw),
+            Err(err) => {
+                return error::fail(args.error_format, None, &err);
+            }
+        }
+    }
+
+    let total = selected.len();
+    let mut failed = Vec::new();
+    for selected in selected {
+        let _span = tracing::debug_span!("package", package = %selected.name).entered();
+        let res = match mode {
+            args::Mode::DumpRaw => dump_raw(&rustdoc, selected, args.format),
+            args::Mode::Api if args.check => check_baseline(&rustdoc, selected),
+            args::Mode::Api if args.all_targets => {
+                target_apis(&rustdoc, selected, owners.as_ref(), args)
+            }
+            args::Mode::Api => api(&rustdoc, selected, owners.as_ref(), args),
+            args::Mode::Diff => diff(
+                &rustdoc,
+                &metadata,
+                selected,
+                base.clone().unwrap(),
+                owners.as_ref(),
+                args,
+            ),
+            args::Mode::Compare => compare(
+                &rustdoc,
+                selected,
+                args.compare.as_deref().unwrap(),
+                args.format,
+            ),
+            args::Mode::DepCheck => dep_check(
+                &rustdoc,
+                &metadata,
+                selected,
+                args.dep_check.as_deref().unwrap(),
+                args.to.as_deref(),
+                args.used,
+                args.format,
+            ),
+            args::Mode::Breaking => breaking(
+                &rustdoc,
+                &metadata,
+                selected,
+                base.clone().unwrap(),
+                args.format,
+                args.check,
+                args.docs_rs(),
+            ),
+            args::Mode::CheckRelease => check_release(
+                &rustdoc,
+                &metadata,
+                selected,
+                base.clone().unwrap(),
+                args.format,
+                args.docs_rs(),
+            ),
+            args::Mode::Bisect => bisect(
+                &rustdoc,
+                &metadata,
+                selected,
+                args.bisect.as_deref().unwrap(),
+                &args.bad,
+                args.change.as_deref(),
+                args.format,
+            ),
+            args::Mode::FeaturePowerset => {
+                feature_powerset(&rustdoc, selected, args.depth, args.format)
+            }
+            args::Mode::FeatureAdditivity => {
+                feature_additivity(&rustdoc, selected, args.depth, args.format)
+            }
+            args::Mode::FeatureDocs => feature_docs(&rustdoc, selected, args.format),
+            args::Mode::History => {
+                history(&rustdoc, &metadata, selected, args.format, args.docs_rs())
+            }
+            args::Mode::Since => since(&rustdoc, &metadata, selected, args.format, args.docs_rs()),
+            args::Mode::Merge => unreachable!("handled before loading metadata"),
+            args::Mode::Metrics => metrics(&rustdoc, selected, args.format, args.append.as_deref()),
+            args::Mode::UnsafeReport => unsafe_report(&rustdoc, selected, args.format),
+            args::Mode::Ffi => ffi(&rustdoc, selected, args.format),
+            args::Mode::Embed => embed(&rustdoc, selected, args.check),
+            args::Mode::UpdateBaseline => update_baseline(&rustdoc, selected),
+            args::Mode::VerifyCrate => unreachable!("handled before selecting packages"),
+            args::Mode::Serve => unreachable!("handled before selecting packages"),
+            args::Mode::Watch => unreachable!("handled before selecting packages"),
+            args::Mode::DiffManifests => unreachable!("handled before loading metadata"),
+        };
+        match res {
+            Ok(()) => {}
+            Err(err) => {
+                error::report(args.error_format, Some(&selected.name), &err);
+                // A tool error hides whether the other packages were fine
+                if code != error::TOOL_ERROR {
+                    code = error::exit_code(&err);
+                }
+                if error::exit_code(&err) == error::TOOL_ERROR {
+                    failed.push((selected.name.as_str(), error::code_of(&err)));
+                }
+                continue;
+            }
+        };
+    }
+
+    if args.keep_going && !failed.is_empty() {
+        error::report(args.error_format, None, &failures(&failed, total));
+    }
+
+    code.ok()
+}
+
+/// Summarize the packages that failed with `--keep-going`
+fn failures(failed: &[(&str, error::ErrorCode)], total: usize) -> eyre::Report {
+    let code = match failed {
+        [(_, first), rest @ ..] if rest.iter().all(|(_, code)| code == first) => *first,
+        _ => error::ErrorCode::Unknown,
+    };
+    let names: Vec<_> = failed.iter().map(|(name, _)| *name).collect();
+    error::coded(
+        code,
+        format!(
+            "{} of {} package(s) failed: {}",
+            failed.len(),
+            total,
+            names.join(", ")
+        ),
+    )
+}
+
+fn completions(
+    shell: clap_complete::Shell,
+    manifest: &clap_cargo::Manifest,
+) -> Result<(), eyre::Report> {
+    use clap::CommandFactory;
+
+    let packages: Vec<String> = match manifest.metadata().no_deps().exec() {
+        Ok(metadata) => metadata.packages.into_iter().map(|pkg| pkg.name).collect(),
+        Err(err) => {
+            tracing::debug!(%err, "completing without package names");
+            Vec::new()
+        }
+    };
+
+    let mut cmd = args::Command::command();
+    if !packages.is_empty() {
+        let crate_api = cmd
+            .find_subcommand_mut("crate-api")
+            .expect("`crate-api` is always a subcommand");
+        *crate_api = std::mem::take(crate_api)
+            .mut_arg("package", |arg| {
+                arg.possible_values(packages.iter().map(String::as_str))
+            })
+            .mut_arg("exclude", |arg| {
+                arg.possible_values(packages.iter().map(String::as_str))
+            });
+    }
+    let stdout = output::stdout();
+    let mut stdout = stdout.lock();
+    clap_complete::generate(shell, &mut cmd, "cargo", &mut stdout);
+    Ok(())
+}
+
+/// Read rustdoc JSON from `path`, or stdin for `-`
+fn read_rustdoc_json(path: &std::path::Path) -> Result<String, eyre::Report> {
+    if path == std::path::Path::new("-") {
+        let mut raw = String::new();
+        std::io::Read::read_to_string(&mut std::io::stdin(), &mut raw)
+            .map_err(|e| eyre::eyre!("Failed when reading stdin: {}", e))?;
+        Ok(raw)
+    } else {
+        std::fs::read_to_string(path)
+            .map_err(|e| eyre::eyre!("Failed when reading {}: {}", path.display(), e))
+    }
+}
+
+fn dump_raw(
+    rustdoc: &crate_api::RustDocBuilder,
+    pkg: &cargo_metadata::Package,
+    format: args::Format,
+) -> Result<(), eyre::Report> {
+    let raw = rustdoc
+        .clone()
+        .dump_raw(pkg.manifest_path.as_path().as_std_path())?;
+    let raw: rustdoc_json_types_fork::Crate = serde_json::from_str(&raw)?;
+
+    let manifest = crate_api::manifest::Manifest::from(pkg);
+
+    let raw = report::Raw {
+        manifest_path: pkg.manifest_path.clone().into_std_path_buf(),
+        rustdoc: Some(raw),
+        manifest: Some(manifest),
+    };
+
+    match format {
+        args::Format::Silent => {}
+        args::Format::Pretty => {
+            let _ = writeln!(output::stdout(), "{}", serde_json::to_string_pretty(&raw)?);
+        }
+        args::Format::Md => {
+            let _ = writeln!(
+                output::stdout(),
+                "```json
+{}
+```",
+                serde_json::to_string_pretty(&raw)?
+            );
+        }
+        args::Format::Json => {
+            let _ = writeln!(output::stdout(), "{}", serde_json::to_string(&raw)?);
+        }
+        unsupported @ (args::Format::Csv
+        | args::Format::Tsv
+        | args::Format::MdChangelog
+        | args::Format::Github
+        | args::Format::Sarif
+        | args::Format::Html) => {
+            return Err(error::unsupported_format(unsupported, "--dump-raw"));
+        }
+    }
+
+    Ok(())
+}
+
+fn workspace_api(
+    rustdoc: &crate_api::RustDocBuilder,
+    metadata: &cargo_metadata::Metadata,
+    selected: &[&cargo_metadata::Package],
+    args: &args::Api,
+) -> Result<(), eyre::Report> {
+    let manifest_path = metadata.workspace_root.join("Cargo.toml");
+    let apis = if args.keep_going {
+        rustdoc
+            .clone()
+            .into_workspace_results(manifest_path.as_std_path())?
+    } else {
+        rustdoc
+            .clone()
+            .into_workspace_apis(manifest_path.as_std_path())?
+            .into_iter()
+            .map(|(id, api)| (id, Ok(api)))
+            .collect()
+    };
+
+    let mut packages = Vec::new();
+    let mut failed = Vec::new();
+    for (id, api) in apis {
+        let pkg = match selected.iter().find(|pkg| pkg.id == id) {
+            Some(pkg) => pkg,
+            // Left out with `--exclude`
+            None => continue,
+        };
+        let mut api = match api {
+            Ok(api) => api,
+            Err(err) => {
+                let err = eyre::Report::new(err);
+                error::report(args.error_format, Some(&pkg.name), &err);
+                failed.push((pkg.name.as_str(), error::code_of(&err)));
+                continue;
+            }
+        };
+        crate_api::manifest::Manifest::from(*pkg).into_api(&mut api);
+        config::PackageConfig::from_package(pkg)?.mark_experimental(&mut api);
+        if !args.with_docs {
+            strip_docs(&mut api);
+        }
+        packages.push(report::PackageApi {
+            name: pkg.name.clone(),
+            version: pkg.version.to_string(),
+            manifest_path: pkg.manifest_path.clone().into_std_path_buf(),
+            api,
+        });
+    }
+    let total = packages.len() + failed.len();
+    let workspace = report::WorkspaceApi { packages };
+
+    render_workspace_api(&workspace, args)?;
+    if !failed.is_empty() {
+        return Err(failures(&failed, total));
+    }
+    Ok(())
+}
+
+fn render_workspace_api(
+    workspace: &report::WorkspaceApi,
+    args: &args::Api,
+) -> Result<(), eyre::Report> {
+    let format = args.format;
+    let _span = tracing::debug_span!("render", ?format).entered();
+    if let Some(plugin) = args.plugin.as_deref() {
+        return render_plugin(plugin, "workspace-api", workspace);
+    }
+    if let Some(template) = args.template.as_deref() {
+        let _ = write!(
+            output::stdout(),
+            "{}",
+            template::render(template, "workspace-api", &workspace)?
+        );
+        return Ok(());
+    }
+
+    match format {
+        args::Format::Silent => {}
+        args::Format::Pretty => {
+            let _ = writeln!(
+                output::stdout(),
+                "{}",
+                serde_json::to_string_pretty(&workspace)?
+            );
+        }
+        args::Format::Md => {
+            let stdout = output::stdout();
+            let mut stdout = stdout.lock();
+            for package in &workspace.packages {
+                report::render_api_markdown(&mut stdout, &package.api)?;
+            }
+        }
+        args::Format::Json => {
+            let _ = writeln!(output::stdout(), "{}", serde_json::to_string(&workspace)?);
+        }
+        unsupported @ (args::Format::Csv
+        | args::Format::Tsv
+        | args::Format::MdChangelog
+        | args::Format::Github
+        | args::Format::Sarif
+        | args::Format::Html) => {
+            return Err(error::unsupported_format(unsupported, "--workspace"));
+        }
+    }
+
+    Ok(())
+}
+
+/// Leave out doc summaries, which are only reported with `--with-docs`
+fn strip_docs(api: &mut crate_api::Api) {
+    for (_, item) in api.items.iter_mut() {
+        item.docs = None;
+    }
+}
+
+fn target_apis(
+    rustdoc: &crate_api::RustDocBuilder,
+    pkg: &cargo_metadata::Package,
+    owners: Option<&owners::Owners>,
+    args: &args::Api,
+) -> Result<(), eyre::Report> {
+    if owners.is_some() {
+        return Err(error::coded(
+            error::ErrorCode::Config,
+            "`--group-by` is not supported with `--all-targets`",
+        ));
+    }
+    let mut builder = rustdoc.clone();
+    if let Some(scope) = args.scope.as_deref() {
+        builder = builder.scope(scope);
+    }
+    let apis = builder.into_target_apis(pkg.manifest_path.as_std_path())?;
+
+    let config = config::PackageConfig::from_package(pkg)?;
+    let mut targets = Vec::new();
+    for (target, mut api) in apis {
+        crate_api::manifest::Manifest::from(pkg).into_api(&mut api);
+        config.mark_experimental(&mut api);
+        if !args.with_docs {
+            strip_docs(&mut api);
+        }
+        targets.push(report::TargetApi { target, api });
+    }
+    let report = report::TargetApis {
+        manifest_path: pkg.manifest_path.clone().into_std_path_buf(),
+        targets,
+    };
+
+    let format = args.format;
+    let _span = tracing::debug_span!("render", ?format).entered();
+    if let Some(plugin) = args.plugin.as_deref() {
+        return render_plugin(plugin, "target-apis", &report);
+    }
+    if let Some(template) = args.template.as_deref() {
+        let _ = write!(
+            output::stdout(),
+            "{}",
+            template::render(template, "target-apis", &report)?
+        );
+        return Ok(());
+    }
+
+    match format {
+        args::Format::Silent => {}
+        args::Format::Pretty => {
+            let _ = writeln!(
+                output::stdout(),
+                "{}",
+                serde_json::to_string_pretty(&report)?
+            );
+        }
+        args::Format::Md => {
+            let stdout = output::stdout();
+            let mut stdout = stdout.lock();
+            for target in &report.targets {
+                report::render_api_markdown(&mut stdout, &target.api)?;
+            }
+        }
+        args::Format::Json => {
+            let _ = writeln!(output::stdout(), "{}", serde_json::to_string(&report)?);
+        }
+        unsupported @ (args::Format::Csv
+        | args::Format::Tsv
+        | args::Format::MdChangelog
+        | args::Format::Github
+        | args::Format::Sarif
+        | args::Format::Html) => {
+            return Err(error::unsupported_format(unsupported, "--all-targets"));
+        }
+    }
+
+    Ok(())
+}
+
+fn api(
+    rustdoc: &crate_api::RustDocBuilder,
+    pkg: &cargo_metadata::Package,
+    owners: Option<&owners::Owners>,
+    args: &args::Api,
+) -> Result<(), eyre::Report> {
+    let mut builder = rustdoc.clone();
+    if let Some(scope) = args.scope.as_deref() {
+        builder = builder.scope(scope);
+    }
+    let mut api = builder.into_api(pkg.manifest_path.as_path().as_std_path())?;
+
+    let manifest = crate_api::manifest::Manifest::from(pkg);
+    manifest.into_api(&mut api);
+    config::PackageConfig::from_package(pkg)?.mark_experimental(&mut api);
+    if !args.with_docs {
+        strip_docs(&mut api);
+    }
+
+    let format = args.format;
+    let _span = tracing::debug_span!("render", ?format).entered();
+    if let Some(plugin) = args.plugin.as_deref() {
+        let raw = report::Api {
+            manifest_path: pkg.manifest_path.clone().into_std_path_buf(),
+            api,
+        };
+        return render_plugin(plugin, "api", &raw);
+    }
+
+    if let Some(template) = args.template.as_deref() {
+        let raw = report::Api {
+            manifest_path: pkg.manifest_path.clone().into_std_path_buf(),
+            api,
+        };
+        let _ = write!(
+            output::stdout(),
+            "{}",
+            template::render(template, "api", &raw)?
+        );
+        return Ok(());
+    }
+
+    if let Some(owners) = owners {
+        let groups = report::group_api_by_owner(&api, owners);
+        match format {
+            args::Format::Silent => {}
+            args::Format::Pretty => {
+                let _ = writeln!(
+                    output::stdout(),
+                    "{}",
+                    serde_json::to_string_pretty(&groups)?
+                );
+            }
+            args::Format::Md => {
+                let stdout = output::stdout();
+                let mut stdout = stdout.lock();
+                report::render_api_by_owner_markdown(&mut stdout, &api, &groups)?;
+            }
+            args::Format::Json => {
+                let _ = writeln!(output::stdout(), "{}", serde_json::to_string(&groups)?);
+            }
+            unsupported @ (args::Format::Csv
+            | args::Format::Tsv
+            | args::Format::MdChangelog
+            | args::Format::Github
+            | args::Format::Sarif
+            | args::Format::Html) => {
+                return Err(error::unsupported_format(unsupported, "--group-by"));
+            }
+        }
+        return Ok(());
+    }
+
+    match format {
+        args::Format::Silent => {}
+        args::Format::Pretty => {
+            // HACK: Real version (using `termtree`) isn't implemented yet
+            let _ = writeln!(output::stdout(), "{}", serde_json::to_string_pretty(&api)?);
+        }
+        args::Format::Md => {
+            let stdout = output::stdout();
+            let mut stdout = stdout.lock();
+            report::render_api_markdown(&mut stdout, &api)?;
+        }
+        args::Format::Json => {
+            let _ = writeln!(output::stdout(), "{}", serde_json::to_string(&api)?);
+        }
+        args::Format::Csv => {
+            let stdout = output::stdout();
+            let mut stdout = stdout.lock();
+            report::render_api_csv(&mut stdout, &api, b',')?;
+        }
+        args::Format::Tsv => {
+            let stdout = output::stdout();
+            let mut stdout = stdout.lock();
+            report::render_api_csv(&mut stdout, &api, b'\t')?;
+        }
+        unsupported @ (args::Format::MdChangelog
+        | args::Format::Github
+        | args::Format::Sarif
+        | args::Format::Html) => {
+            return Err(error::unsupported_format(unsupported, "--api"));
+        }
+    }
+
+    Ok(())
+}
+
+fn feature_powerset(
+    rustdoc: &crate_api::RustDocBuilder,
+    pkg: &cargo_metadata::Package,
+    depth: Option<usize>,
+    format: args::Format,
+) -> Result<(), eyre::Report> {
+    let depth = depth.unwrap_or(usize::MAX);
+    let results = feature_surfaces(rustdoc, pkg, depth)?;
+
+    let report = report::FeaturePowerset::new(&results);
+
+    match format {
+        args::Format::Silent => {}
+        args::Format::Pretty => {
+            let _ = writeln!(
+                output::stdout(),
+                "{}",
+                serde_json::to_string_pretty(&report)?
+            );
+        }
+        args::Format::Md => {
+            let stdout = output::stdout();
+            let mut stdout = stdout.lock();
+            report::render_feature_powerset_markdown(&mut stdout, &report)?;
+        }
+        args::Format::Json => {
+            let _ = writeln!(output::stdout(), "{}", serde_json::to_string(&report)?);
+        }
+        unsupported @ (args::Format::Csv
+        | args::Format::Tsv
+        | args::Format::MdChangelog
+        | args::Format::Github
+        | args::Format::Sarif
+        | args::Format::Html) => {
+            return Err(error::unsupported_format(unsupported, "--feature-powerset"));
+        }
+    }
+
+    Ok(())
+}
+
+/// Extract the API for each combination of up to `depth` features
+fn feature_surfaces(
+    rustdoc: &crate_api::RustDocBuilder,
+    pkg: &cargo_metadata::Package,
+    depth: usize,
+) -> Result<Vec<(Vec<String>, freeze::Surface)>, eyre::Report> {
+    let mut features: Vec<_> = pkg
+        .features
+        .keys()
+        .filter(|f| *f != "default")
+        .cloned()
+        .collect();
+    for dep in &pkg.dependencies {
+        let explicit = pkg
+            .features
+            .values()
+            .flatten()
+            .any(|v| v.strip_prefix("dep:") == Some(&dep.name));
+        if dep.optional && !explicit && !features.contains(&dep.name) {
+            features.push(dep.name.clone());
+        }
+    }
+    features.sort_unstable();
+
+    let mut results = Vec::new();
+    for combination in combinations(&features, depth) {
+        ::log::info!("Extracting API with features: {:?}", combination);
+        let api = rustdoc
+            .clone()
+            .all_features(false)
+            .no_default_features(true)
+            .features(combination.iter().cloned())
+            .into_api(pkg.manifest_path.as_path().as_std_path())?;
+        results.push((combination, freeze::surface(&api)));
+    }
+
+    Ok(results)
+}
+
+fn feature_docs(
+    rustdoc: &crate_api::RustDocBuilder,
+    pkg: &cargo_metadata::Package,
+    format: args::Format,
+) -> Result<(), eyre::Report> {
+    let mut api = rustdoc
+        .clone()
+        .into_api(pkg.manifest_path.as_path().as_std_path())?;
+    crate_api::manifest::Manifest::from(pkg).into_api(&mut api);
+
+    let docs = report::FeatureDocs::new(pkg, &api);
+
+    match format {
+        args::Format::Silent => {}
+        args::Format::Pretty => {
+            let _ = writeln!(output::stdout(), "{}", serde_json::to_string_pretty(&docs)?);
+        }
+        args::Format::Md => {
+            let stdout = output::stdout();
+            let mut stdout = stdout.lock();
+            report::render_feature_docs_markdown(&mut stdout, &docs)?;
+        }
+        args::Format::Json => {
+            let _ = writeln!(output::stdout(), "{}", serde_json::to_string(&docs)?);
+        }
+        unsupported @ (args::Format::Csv
+        | args::Format::Tsv
+        | args::Format::MdChangelog
+        | args::Format::Github
+        | args::Format::Sarif
+        | args::Format::Html) => {
+            return Err(error::unsupported_format(unsupported, "--feature-docs"));
+        }
+    }
+
+    Ok(())
+}
+
+fn feature_additivity(
+    rustdoc: &crate_api::RustDocBuilder,
+    pkg: &cargo_metadata::Package,
+    depth: Option<usize>,
+    format: args::Format,
+) -> Result<(), eyre::Report> {
+    // One more than the combinations being checked, for the features being added
+    let depth = depth.unwrap_or(0).saturating_add(1);
+    let results = feature_surfaces(rustdoc, pkg, depth)?;
+
+    let report = report::FeatureAdditivity::new(&results);
+
+    match format {
+        args::Format::Silent => {}
+        args::Format::Pretty => {
+            let _ = writeln!(
+                output::stdout(),
+                "{}",
+                serde_json::to_string_pretty(&report)?
+            );
+        }
+        args::Format::Md => {
+            let stdout = output::stdout();
+            let mut stdout = stdout.lock();
+            report::render_feature_additivity_markdown(&mut stdout, &report)?;
+        }
+        args::Format::Json => {
+            let _ = writeln!(output::stdout(), "{}", serde_json::to_string(&report)?);
+        }
+        unsupported @ (args::Format::Csv
+        | args::Format::Tsv
+        | args::Format::MdChangelog
+        | args::Format::Github
+        | args::Format::Sarif
+        | args::Format::Html) => {
+            return Err(error::unsupported_format(
+                unsupported,
+                "--feature-additivity",
+            ));
+        }
+    }
+
+    if !report.violations.is_empty() {
+        return Err(error::coded(
+            error::ErrorCode::CheckFailed,
+            format!(
+                "{} has {} non-additive feature change(s)",
+                pkg.name,
+                report.violations.len()
+            ),
+        ));
+    }
+
+    Ok(())
+}
+
+/// All combinations of `features` with at most `depth` entries, smallest first
+fn combinations(features: &[String], depth: usize) -> Vec<Vec<String>> {
+    let mut combinations = vec![Vec::new()];
+    let mut last: Vec<(usize, Vec<String>)> = vec![(0, Vec::new())];
+    for _ in 0..depth.min(features.len()) {
+        let mut next = Vec::new();
+        for (start, combination) in &last {
+            for (i, feature) in features.iter().enumerate().skip(*start) {
+                let mut combination = combination.clone();
+                combination.push(feature.clone());
+                next.push((i + 1, combination));
+            }
+        }
+        combinations.extend(next.iter().map(|(_, combination)| combination.clone()));
+        last = next;
+    }
+    combinations
+}
+
+fn history(
+    rustdoc: &crate_api::RustDocBuilder,
+    metadata: &cargo_metadata::Metadata,
+    pkg: &cargo_metadata::Package,
+    format: args::Format,
+    docs_rs: bool,
+) -> Result<(), eyre::Report> {
+    let apis = release_apis(rustdoc, metadata, pkg, docs_rs)?;
+    let report = report::History::new(&pkg.name, &apis);
+
+    match format {
+        args::Format::Silent => {}
+        args::Format::Pretty => {
+            let _ = writeln!(
+                output::stdout(),
+                "{}",
+                serde_json::to_string_pretty(&report)?
+            );
+        }
+        args::Format::Md => {
+            let stdout = output::stdout();
+            let mut stdout = stdout.lock();
+            report::render_history_markdown(&mut stdout, &report)?;
+        }
+        args::Format::Json => {
+            let _ = writeln!(output::stdout(), "{}", serde_json::to_string(&report)?);
+        }
+        unsupported @ (args::Format::Csv
+        | args::Format::Tsv
+        | args::Format::MdChangelog
+        | args::Format::Github
+        | args::Format::Sarif
+        | args::Format::Html) => {
+            return Err(error::unsupported_format(unsupported, "--history"));
+        }
+    }
+
+    Ok(())
+}
+
+fn since(
+    rustdoc: &crate_api::RustDocBuilder,
+    metadata: &cargo_metadata::Metadata,
+    pkg: &cargo_metadata::Package,
+    format: args::Format,
+    docs_rs: bool,
+) -> Result<(), eyre::Report> {
+    let apis = release_apis(rustdoc, metadata, pkg, docs_rs)?;
+    let history = report::History::new(&pkg.name, &apis);
+
+    let api = rustdoc
+        .clone()
+        .into_api(pkg.manifest_path.as_path().as_std_path())?;
+    let report = report::Since::new(&history, &api);
+
+    match format {
+        args::Format::Silent => {}
+        args::Format::Pretty => {
+            let _ = writeln!(
+                output::stdout(),
+                "{}",
+                serde_json::to_string_pretty(&report)?
+            );
+        }
+        args::Format::Md => {
+            let stdout = output::stdout();
+            let mut stdout = stdout.lock();
+            report::render_since_markdown(&mut stdout, &report)?;
+        }
+        args::Format::Json => {
+            let _ = writeln!(output::stdout(), "{}", serde_json::to_string(&report)?);
+        }
+        unsupported @ (args::Format::Csv
+        | args::Format::Tsv
+        | args::Format::MdChangelog
+        | args::Format::Github
+        | args::Format::Sarif
+        | args::Format::Html) => {
+            return Err(error::unsupported_format(unsupported, "--since"));
+        }
+    }
+
+    Ok(())
+}
+
+/// The API of each release that can still be built, in ascending version order
+///
+/// Releases are listed from crates.io, falling back to release tags for packages that aren't
+/// published there.
+fn release_apis(
+    rustdoc: &crate_api::RustDocBuilder,
+    metadata: &cargo_metadata::Metadata,
+    pkg: &cargo_metadata::Package,
+    docs_rs: bool,
+) -> Result<Vec<(String, crate_api::Api)>, eyre::Report> {
+    let publishable = pkg
+        .publish
+        .as_ref()
+        .map_or(true, |registries| !registries.is_empty());
+    let versions = if publishable {
+        registry_versions(&pkg.name).unwrap_or_else(|err| {
+            ::log::info!(
+                "Could not list releases of {} on crates.io ({}); using release tags",
+                pkg.name,
+                err
+            );
+            Vec::new()
+        })
+    } else {
+        Vec::new()
+    };
+    if !versions.is_empty() {
+        let mut apis = Vec::new();
+        for version in versions {
+            ::log::info!("Extracting API for {} {}", pkg.name, version);
+            match registry_api_at(rustdoc, metadata, pkg, &version, docs_rs) {
+                Ok(api) => apis.push((version.to_string(), api)),
+                // Old releases may no longer build, so work with what we have
+                Err(err) => ::log::warn!("Skipping {} {}: {}", pkg.name, version, err),
+            }
+        }
+        return Ok(apis);
+    }
+
+    let repo = git2::Repository::discover(pkg.manifest_path.as_std_path())?;
+    let releases = find_releases(&repo, &pkg.name)?;
+    if releases.is_empty() {
+        eyre::bail!("Could not find any release tags for {}", pkg.name);
+    }
+
+    let mut apis = Vec::new();
+    for (version, commit) in releases {
+        ::log::info!("Extracting API for {} {}", pkg.name, version);
+        match api_at(rustdoc, metadata, pkg, &repo, commit) {
+            Ok(api) => apis.push((version.to_string(), api)),
+            // Old releases may no longer build, so work with what we have
+            Err(err) => ::log::warn!("Skipping {} {}: {}", pkg.name, version, err),
+        }
+    }
+    Ok(apis)
+}
+
+/// The versions of `name` on crates.io that aren't yanked, in ascending order
+fn registry_versions(name: &str) -> Result<Vec<cargo_metadata::Version>, eyre::Report> {
+    // Crate names are ASCII, so slicing is safe
+    let name = name.to_lowercase();
+    let prefix = match name.len() {
+        1 => "1".to_owned(),
+        2 => "2".to_owned(),
+        3 => format!("3/{}", &name[..1]),
+        _ => format!("{}/{}", &name[..2], &name[2..4]),
+    };
+    let url = format!("https://index.crates.io/{}/{}", prefix, name);
+    let index = ureq::get(&url)
+        .timeout(std::time::Duration::from_secs(30))
+        .call()?
+        .into_string()?;
+
+    #[derive(serde::Deserialize)]
+    struct Release {
+        vers: cargo_metadata::Version,
+        #[serde(default)]
+        yanked: bool,
+    }
+    let mut versions = Vec::new();
+    for line in index.lines().filter(|line| !line.trim().is_empty()) {
+        let release: Release = serde_json::from_str(line)
+            .map_err(|e| eyre::eyre!("Failed when parsing {}: {}", url, e))?;
+        if !release.yanked {
+            versions.push(release.vers);
+        }
+    }
+    versions.sort();
+    versions.dedup();
+    Ok(versions)
+}
+
+/// Release tags, like `v1.0.0` or `name-v1.0.0`, in ascending version order
+fn find_releases(
+    repo: &git2::Repository,
+    name: &str,
+) -> Result<Vec<(cargo_metadata::Version, git2::Oid)>, eyre::Report> {
+    let mut releases = Vec::new();
+    repo.tag_foreach(|oid, tag| {
+        let tag = String::from_utf8_lossy(tag);
+        let tag = tag.strip_prefix("refs/tags/").unwrap_or(&tag);
+        let version = tag
+            .strip_prefix(name)
+            .and_then(|tag| tag.strip_prefix('-'))
+            .unwrap_or(tag);
+        let version = version.strip_prefix('v').unwrap_or(version);
+        if let Ok(version) = cargo_metadata::Version::parse(version) {
+            releases.push((version, oid));
+        }
+        true
+    })?;
+
+    let mut resolved = Vec::new();
+    for (version, oid) in releases {
+        let commit = repo.find_object(oid, None)?.peel_to_commit()?;
+        resolved.push((version, commit.id()));
+    }
+    resolved.sort();
+    resolved.dedup_by(|a, b| a.0 == b.0);
+    Ok(resolved)
+}
+
+fn merge(
+    snapshots: &[std::path::PathBuf],
+    name: &str,
+    format: args::Format,
+    with_docs: bool,
+) -> Result<(), eyre::Report> {
+    let mut loaded = Vec::new();
+    for path in snapshots {
+        let raw = std::fs::read_to_string(path)
+            .map_err(|e| eyre::eyre!("Failed when reading {}: {}", path.display(), e))?;
+        let mut api: crate_api::Api = serde_json::from_str(&raw)
+            .map_err(|e| eyre::eyre!("Failed when parsing {}: {}", path.display(), e))?;
+        if !with_docs {
+            strip_docs(&mut api);
+        }
+        loaded.push((path.clone(), api));
+    }
+    let merged = report::Merged::new(name, &loaded);
+
+    match format {
+        args::Format::Silent => {}
+        args::Format::Pretty => {
+            let _ = writeln!(
+                output::stdout(),
+                "{}",
+                serde_json::to_string_pretty(&merged)?
+            );
+        }
+        args::Format::Md => {
+            let stdout = output::stdout();
+            let mut stdout = stdout.lock();
+            report::render_api_markdown(&mut stdout, &merged.api)?;
+        }
+        args::Format::Json => {
+            let _ = writeln!(output::stdout(), "{}", serde_json::to_string(&merged)?);
+        }
+        args::Format::Csv => {
+            let stdout = output::stdout();
+            let mut stdout = stdout.lock();
+            report::render_api_csv(&mut stdout, &merged.api, b',')?;
+        }
+        args::Format::Tsv => {
+            let stdout = output::stdout();
+            let mut stdout = stdout.lock();
+            report::render_api_csv(&mut stdout, &merged.api, b'\t')?;
+        }
+        unsupported @ (args::Format::MdChangelog
+        | args::Format::Github
+        | args::Format::Sarif
+        | args::Format::Html) => {
+            return Err(error::unsupported_format(unsupported, "--merge"));
+        }
+    }
+
+    Ok(())
+}
+
+fn embed(
+    rustdoc: &crate_api::RustDocBuilder,
+    pkg: &cargo_metadata::Package,
+    check: bool,
+) -> Result<(), eyre::Report> {
+    let mut api = rustdoc
+        .clone()
+        .into_api(pkg.manifest_path.as_path().as_std_path())?;
+
+    let manifest = crate_api::manifest::Manifest::from(pkg);
+    manifest.into_api(&mut api);
+
+    let path = embed::path(pkg);
+    if check {
+        let problem = match embed::read(&path)? {
+            Some(embedded) if embed::is_current(&embedded, &api) => return Ok(()),
+            Some(_) => "out of date",
+            None => "missing",
+        };
+        return Err(error::coded(
+            error::ErrorCode::CheckFailed,
+            format!(
+                "{} is {}; run `cargo crate-api --embed` before packaging",
+                path.display(),
+                problem
+            ),
+        ));
+    }
+
+    embed::write(&path, &api)?;
+    ::log::info!("Wrote {}", path.display());
+
+    Ok(())
+}
+
+fn update_baseline(
+    rustdoc: &crate_api::RustDocBuilder,
+    pkg: &cargo_metadata::Package,
+) -> Result<(), eyre::Report> {
+    let mut api = rustdoc
+        .clone()
+        .into_api(pkg.manifest_path.as_path().as_std_path())?;
+
+    let manifest = crate_api::manifest::Manifest::from(pkg);
+    manifest.into_api(&mut api);
+
+    let path = lock::path(pkg);
+    lock::write(&path, &api)?;
+    ::log::info!("Wrote {}", path.display());
+
+    Ok(())
+}
+
+fn check_baseline(
+    rustdoc: &crate_api::RustDocBuilder,
+    pkg: &cargo_metadata::Package,
+) -> Result<(), eyre::Report> {
+    let mut api = rustdoc
+        .clone()
+        .into_api(pkg.manifest_path.as_path().as_std_path())?;
+
+    let manifest = crate_api::manifest::Manifest::from(pkg);
+    manifest.into_api(&mut api);
+
+    let path = lock::path(pkg);
+    let locked = match lock::read(&path)? {
+        Some(locked) => locked,
+        None => {
+            return Err(error::coded(
+                error::ErrorCode::CheckFailed,
+                format!(
+                    "{} is missing; run `cargo crate-api --update-baseline` to create it",
+                    path.display()
+                ),
+            ));
+        }
+    };
+
+    let mismatches = embed::mismatches(&locked, &api);
+    if mismatches.is_empty() {
+        return Ok(());
+    }
+    let mismatches = mismatches
+        .iter()
+        .map(|path| format!("`{}`", path))
+        .collect::<Vec<_>>()
+        .join(", ");
+    Err(error::coded(
+        error::ErrorCode::CheckFailed,
+        format!(
+            "API differs from {} at {}; run `cargo crate-api --update-baseline` if this is intended",
+            path.display(),
+            mismatches
+        ),
+    ))
+}
+
+fn verify_crate(
+    rustdoc: &crate_api::RustDocBuilder,
+    metadata: &cargo_metadata::Metadata,
+    spec: &str,
+    format: args::Format,
+) -> Result<(), eyre::Report> {
+    let (name, req) = match spec.split_once('@') {
+        Some((name, version)) => (name, format!("={}", version)),
+        None => (spec, "*".to_owned()),
+    };
+    let manifest_path = fetch_registry_package(metadata, name, &req)?;
+    let pkg = resolve_package(&manifest_path)?;
+
+    let target_dir = metadata
+        .target_directory
+        .join("crate-api/verify-crate")
+        .into_std_path_buf();
+    let mut api = rustdoc
+        .clone()
+        .target_directory(&target_dir)
+        .into_api(&manifest_path)?;
+    crate_api::manifest::Manifest::from(&pkg).into_api(&mut api);
+
+    let embedded = embed::read(&embed::path(&pkg))?;
+    let verification = report::Verification::new(&pkg, embedded.as_ref(), &api);
+
+    match format {
+        args::Format::Silent => {}
+        args::Format::Pretty => {
+            let _ = writeln!(
+                output::stdout(),
+                "{}",
+                serde_json::to_string_pretty(&verification)?
+            );
+        }
+        args::Format::Md => {
+            let stdout = output::stdout();
+            let mut stdout = stdout.lock();
+            report::render_verification_markdown(&mut stdout, &verification)?;
+        }
+        args::Format::Json => {
+            let _ = writeln!(
+                output::stdout(),
+                "{}",
+                serde_json::to_string(&verification)?
+            );
+        }
+        unsupported @ (args::Format::Csv
+        | args::Format::Tsv
+        | args::Format::MdChangelog
+        | args::Format::Github
+        | args::Format::Sarif
+        | args::Format::Html) => {
+            return Err(error::unsupported_format(unsupported, "--verify-crate"));
+        }
+    }
+
+    let problem = match verification.status {
+        report::VerificationStatus::Verified => return Ok(()),
+        report::VerificationStatus::Mismatch => "does not match its embedded API snapshot",
+        report::VerificationStatus::NotEmbedded => "has no embedded API snapshot",
+    };
+    Err(error::coded(
+        error::ErrorCode::CheckFailed,
+        format!("{} {} {}", pkg.name, pkg.version, problem),
+    ))
+}
+
+fn metrics(
+    rustdoc: &crate_api::RustDocBuilder,
+    pkg: &cargo_metadata::Package,
+    format: args::Format,
+    append: Option<&std::path::Path>,
+) -> Result<(), eyre::Report> {
+    let mut api = rustdoc
+        .clone()
+        .into_api(pkg.manifest_path.as_path().as_std_path())?;
+
+    let manifest = crate_api::manifest::Manifest::from(pkg);
+    manifest.into_api(&mut api);
+
+    let metrics = report::Metrics::new(pkg, &api, std::time::SystemTime::now());
+
+    if let Some(append) = append {
+        let mut file = std::fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(append)
+            .map_err(|e| eyre::eyre!("Failed when opening {}: {}", append.display(), e))?;
+        writeln!(file, "{}", serde_json::to_string(&metrics)?)?;
+        return Ok(());
+    }
+
+    match format {
+        args::Format::Silent => {}
+        args::Format::Pretty => {
+            let _ = writeln!(
+                output::stdout(),
+                "{}",
+                serde_json::to_string_pretty(&metrics)?
+            );
+        }
+        args::Format::Md => {
+            let stdout = output::stdout();
+            let mut stdout = stdout.lock();
+            report::render_metrics_markdown(&mut stdout, &metrics)?;
+        }
+        args::Format::Json => {
+            let _ = writeln!(output::stdout(), "{}", serde_json::to_string(&metrics)?);
+        }
+        unsupported @ (args::Format::Csv
+        | args::Format::Tsv
+        | args::Format::MdChangelog
+        | args::Format::Github
+        | args::Format::Sarif
+        | args::Format::Html) => {
+            return Err(error::unsupported_format(unsupported, "--metrics"));
+        }
+    }
+
+    Ok(())
+}
+
+fn unsafe_report(
+    rustdoc: &crate_api::RustDocBuilder,
+    pkg: &cargo_metadata::Package,
+    format: args::Format,
+) -> Result<(), eyre::Report> {
+    let api = rustdoc
+        .clone()
+        .into_api(pkg.manifest_path.as_path().as_std_path())?;
+
+    let report = report::UnsafeReport::new(&api);
+
+    match format {
+        args::Format::Silent => {}
+        args::Format::Pretty => {
+            let _ = writeln!(
+                output::stdout(),
+                "{}",
+                serde_json::to_string_pretty(&report)?
+            );
+        }
+        args::Format::Md => {
+            let stdout = output::stdout();
+            let mut stdout = stdout.lock();
+            report::render_unsafe_markdown(&mut stdout, &api, &report)?;
+        }
+        args::Format::Json => {
+            let _ = writeln!(output::stdout(), "{}", serde_json::to_string(&report)?);
+        }
+        unsupported @ (args::Format::Csv
+        | args::Format::Tsv
+        | args::Format::MdChangelog
+        | args::Format::Github
+        | args::Format::Sarif
+        | args::Format::Html) => {
+            return Err(error::unsupported_format(unsupported, "--unsafe-report"));
+        }
+    }
+
+    Ok(())
+}
+
+fn ffi(
+    rustdoc: &crate_api::RustDocBuilder,
+    pkg: &cargo_metadata::Package,
+    format: args::Format,
+) -> Result<(), eyre::Report> {
+    let api = rustdoc
+        .clone()
+        .into_api(pkg.manifest_path.as_path().as_std_path())?;
+
+    let report = report::FfiReport::new(&api);
+
+    match format {
+        args::Format::Silent => {}
+        args::Format::Pretty => {
+            let _ = writeln!(
+                output::stdout(),
+                "{}",
+                serde_json::to_string_pretty(&report)?
+            );
+        }
+        args::Format::Md => {
+            let stdout = output::stdout();
+            let mut stdout = stdout.lock();
+            report::render_ffi_markdown(&mut stdout, &report)?;
+        }
+        args::Format::Json => {
+            let _ = writeln!(output::stdout(), "{}", serde_json::to_string(&report)?);
+        }
+        unsupported @ (args::Format::Csv
+        | args::Format::Tsv
+        | args::Format::MdChangelog
+        | args::Format::Github
+        | args::Format::Sarif
+        | args::Format::Html) => {
+            return Err(error::unsupported_format(unsupported, "--ffi"));
+        }
+    }
+
+    Ok(())
+}
+
+fn diff(
+    rustdoc: &crate_api::RustDocBuilder,
+    metadata: &cargo_metadata::Metadata,
+    pkg: &cargo_metadata::Package,
+    base: report::Source,
+    owners: Option<&owners::Owners>,
+    args: &args::Api,
+) -> Result<(), eyre::Report> {
+    let (before, after, diffs) = load_diff(rustdoc, metadata, pkg, &base, args.docs_rs())?;
+
+    let config = config::PackageConfig::from_package(pkg)?;
+    let mut excluded = config.excluded_paths(&before);
+    excluded.extend(config.excluded_paths(&after));
+    if config.frozen && !args.unfreeze {
+        let changes = freeze::surface_changes(&before, &after, &diffs, &excluded);
+        if !changes.is_empty() {
+            for change in &changes {
+                ::log::error!("{}: {}", pkg.name, change);
+            }
+            return Err(error::coded(
+                error::ErrorCode::CheckFailed,
+                format!(
+                    "{} API is frozen; pass `--unfreeze` to acknowledge {} change(s)",
+                    pkg.name,
+                    changes.len()
+                ),
+            ));
+        }
+    }
+
+    let changes = diffs
+        .iter()
+        .filter(|diff| diff.severity != crate_api::diff::Severity::Allow)
+        .count();
+    render_diff(pkg, base, (before, after, diffs), &excluded, owners, args)?;
+    check_changes(pkg, args.check, changes)
+}
+
+/// Compare `old` and `new`, rather than a workspace member against a baseline
+fn diff_manifests(
+    rustdoc: &crate_api::RustDocBuilder,
+    old: &std::path::Path,
+    new: &std::path::Path,
+    args: &args::Api,
+) -> Result<(), eyre::Report> {
+    let old_pkg = resolve_package(old)?;
+    let new_pkg = resolve_package(new)?;
+
+    let mut before = rustdoc
+        .clone()
+        .into_api(old_pkg.manifest_path.as_std_path())?;
+    crate_api::manifest::Manifest::from(&old_pkg).into_api(&mut before);
+    let mut after = rustdoc
+        .clone()
+        .into_api(new_pkg.manifest_path.as_std_path())?;
+    crate_api::manifest::Manifest::from(&new_pkg).into_api(&mut after);
+
+    let config = config::PackageConfig::from_package(&new_pkg)?;
+    config.mark_experimental(&mut before);
+    config.mark_experimental(&mut after);
+    let mut diffs = Vec::new();
+    crate_api::diff::diff(&before, &after, &mut diffs);
+    config.filter_diffs(&before, &after, &mut diffs);
+    let mut excluded = config.excluded_paths(&before);
+    excluded.extend(config.excluded_paths(&after));
+
+    let changes = diffs
+        .iter()
+        .filter(|diff| diff.severity != crate_api::diff::Severity::Allow)
+        .count();
+    let base = report::Source::Path(old_pkg.manifest_path.clone().into_std_path_buf());
+    render_diff(
+        &new_pkg,
+        base,
+        (before, after, diffs),
+        &excluded,
+        None,
+        args,
+    )?;
+    check_changes(&new_pkg, args.check, changes)
+}
+
+/// With `--check`, fail when any change was found
+fn check_changes(
+    pkg: &cargo_metadata::Package,
+    check: bool,
+    changes: usize,
+) -> Result<(), eyre::Report> {
+    if check && changes != 0 {
+        return Err(error::coded(
+            error::ErrorCode::CheckFailed,
+            format!("{} API has {} change(s)", pkg.name, changes),
+        ));
+    }
+    Ok(())
+}
+
+fn render_diff(
+    pkg: &cargo_metadata::Package,
+    base: report::Source,
+    (before, after, diffs): (crate_api::Api, crate_api::Api, Vec<crate_api::diff::Diff>),
+    excluded: &std::collections::HashSet<String>,
+    owners: Option<&owners::Owners>,
+    args: &args::Api,
+) -> Result<(), eyre::Report> {
+    let format = args.format;
+    let _span = tracing::debug_span!("render", ?format).entered();
+    if args.upgrade_guide {
+        let guide = report::UpgradeGuide::new(pkg, base, &before, &after, &diffs, excluded);
+        let stdout = output::stdout();
+        let mut stdout = stdout.lock();
+        return report::render_upgrade_guide_markdown(&mut stdout, &guide);
+    }
+
+    if let Some(plugin) = args.plugin.as_deref() {
+        let raw = report::Diff {
+            manifest_path: pkg.manifest_path.clone().into_std_path_buf(),
+            against: base,
+            before,
+            after,
+            diffs,
+        };
+        return render_plugin(plugin, "diff", &raw);
+    }
+
+    if let Some(template) = args.template.as_deref() {
+        let raw = report::Diff {
+            manifest_path: pkg.manifest_path.clone().into_std_path_buf(),
+            against: base,
+            before,
+            after,
+            diffs,
+        };
+        let _ = write!(
+            output::stdout(),
+            "{}",
+            template::render(template, "diff", &raw)?
+        );
+        return Ok(());
+    }
+
+    if let Some(owners) = owners {
+        let groups = report::group_diffs_by_owner(&before, &after, &diffs, owners);
+        match format {
+            args::Format::Silent => {}
+            args::Format::Pretty => {
+                let _ = writeln!(
+                    output::stdout(),
+                    "{}",
+                    serde_json::to_string_pretty(&groups)?
+                );
+            }
+            args::Format::Md => {
+                let stdout = output::stdout();
+                let mut stdout = stdout.lock();
+                report::render_diff_by_owner_markdown(&mut stdout, &before, &after, &groups)?;
+            }
+            args::Format::Json => {
+                let _ = writeln!(output::stdout(), "{}", serde_json::to_string(&groups)?);
+            }
+            unsupported @ (args::Format::Csv
+            | args::Format::Tsv
+            | args::Format::MdChangelog
+            | args::Format::Github
+            | args::Format::Sarif
+            | args::Format::Html) => {
+                return Err(error::unsupported_format(unsupported, "--group-by"));
+            }
+        }
+        return Ok(());
+    }
+
+    match format {
+        args::Format::Silent => {}
+        args::Format::Pretty => {
+            // HACK: Real version (using `termtree`) isn't implemented yet
+            let raw = report::Diff {
+                manifest_path: pkg.manifest_path.clone().into_std_path_buf(),
+                against: base,
+                before,
+                after,
+                diffs,
+            };
+            let _ = writeln!(output::stdout(), "{}", serde_json::to_string_pretty(&raw)?);
+        }
+        args::Format::Md => {
+            let stdout = output::stdout();
+            let mut stdout = stdout.lock();
+            report::render_diff_markdown(&mut stdout, &before, &after, &diffs)?;
+        }
+        args::Format::Json => {
+            let findings = report::Findings::new(
+                pkg.manifest_path.clone().into_std_path_buf(),
+                base,
+                &before,
+                &after,
+                &diffs,
+            );
+            let _ = writeln!(output::stdout(), "{}", serde_json::to_string(&findings)?);
+        }
+        args::Format::MdChangelog => {
+            let stdout = output::stdout();
+            let mut stdout = stdout.lock();
+            report::render_diff_changelog(&mut stdout, &before, &after, &diffs, excluded)?;
+        }
+        args::Format::Github => {
+            let stdout = output::stdout();
+            let mut stdout = stdout.lock();
+            report::render_diff_github(&mut stdout, &before, &after, &diffs)?;
+        }
+        args::Format::Sarif => {
+            let sarif = report::render_diff_sarif(&before, &after, &diffs);
+            let _ = writeln!(output::stdout(), "{}", serde_json::to_string(&sarif)?);
+        }
+        args::Format::Html => {
+            let stdout = output::stdout();
+            let mut stdout = stdout.lock();
+            report::render_diff_html(&mut stdout, &pkg.name, &before, &after, &diffs)?;
+        }
+        unsupported @ (args::Format::Csv | args::Format::Tsv) => {
+            return Err(error::unsupported_format(unsupported, "--diff"));
+        }
+    }
+
+    Ok(())
+}
+
+fn serve(
+    rustdoc: &crate_api::RustDocBuilder,
+    metadata: &cargo_metadata::Metadata,
+    selected: Vec<&cargo_metadata::Package>,
+    listen: Option<std::net::SocketAddr>,
+    docs_rs: bool,
+) -> Result<(), eyre::Report> {
+    let mut server = serve::Server::new(rustdoc, metadata, selected, docs_rs);
+    match listen {
+        Some(addr) => {
+            let listener = std::net::TcpListener::bind(addr)
+                .map_err(|e| eyre::eyre!("Failed when listening on {}: {}", addr, e))?;
+            ::log::info!("Listening on {}", listener.local_addr()?);
+            // One client at a time, all sharing what has been extracted so far
+            for stream in listener.incoming() {
+                let stream = stream?;
+                let input = std::io::BufReader::new(stream.try_clone()?);
+                if server.serve(input, stream)? {
+                    break;
+                }
+            }
+        }
+        None => {
+            let stdin = std::io::stdin();
+            let stdout = std::io::stdout();
+            server.serve(stdin.lock(), stdout.lock())?;
+        }
+    }
+    Ok(())
+}
+
+/// A baseline's API, the current API, and the changes between them
+type Loaded = (crate_api::Api, crate_api::Api, Vec<crate_api::diff::Diff>);
+
+fn load_diff(
+    rustdoc: &crate_api::RustDocBuilder,
+    metadata: &cargo_metadata::Metadata,
+    pkg: &cargo_metadata::Package,
+    base: &report::Source,
+    docs_rs: bool,
+) -> Result<Loaded, eyre::Report> {
+    load_diff_with_base(rustdoc, metadata, pkg, base, docs_rs).map(|(_, loaded)| loaded)
+}
+
+/// Like [`load_diff`], along with the baseline's package
+fn load_diff_with_base(
+    rustdoc: &crate_api::RustDocBuilder,
+    metadata: &cargo_metadata::Metadata,
+    pkg: &cargo_metadata::Package,
+    base: &report::Source,
+    docs_rs: bool,
+) -> Result<(cargo_metadata::Package, Loaded), eyre::Report> {
+    // Before the slow part, so a missing baseline fails fast
+    let base_path = resolve_source_path(metadata, pkg, base)
+        .map_err(|err| error::coded(error::ErrorCode::BaselineUnavailable, err))?;
+
+    let mut after = rustdoc
+        .clone()
+        .into_api(pkg.manifest_path.as_path().as_std_path())?;
+    let manifest = crate_api::manifest::Manifest::from(pkg);
+    manifest.into_api(&mut after);
+
+    let old_pkg = resolve_package(&base_path)?;
+    let mut before = match base {
+        report::Source::Registry(_) => {
+            registry_api(rustdoc, metadata, &old_pkg, &base_path, docs_rs)?
+        }
+        report::Source::Git(_) | report::Source::Path(_) => rustdoc.clone().into_api(&base_path)?,
+    };
+    let manifest = crate_api::manifest::Manifest::from(&old_pkg);
+    manifest.into_api(&mut before);
+
+    let config = config::PackageConfig::from_package(pkg)?;
+    config.mark_experimental(&mut before);
+    config.mark_experimental(&mut after);
+
+    let mut diffs = Vec::new();
+    crate_api::diff::diff(&before, &after, &mut diffs);
+    config.filter_diffs(&before, &after, &mut diffs);
+
+    Ok((old_pkg, (before, after, diffs)))
+}
 
-    let metadata = args
-        .manifest
-        .metadata()
-        .exec()
-        .with_code(proc_exit::Code::CONFIG_ERR)?;
+/// The API of a release downloaded from crates.io, preferring the rustdoc JSON docs.rs built
+fn registry_api(
+    rustdoc: &crate_api::RustDocBuilder,
+    metadata: &cargo_metadata::Metadata,
+    pkg: &cargo_metadata::Package,
+    manifest_path: &std::path::Path,
+    docs_rs: bool,
+) -> Result<crate_api::Api, eyre::Report> {
+    let prebuilt = if docs_rs { docs_rs::fetch(pkg) } else { None };
+    let prebuilt = prebuilt.and_then(|raw| {
+        rustdoc
+            .clone()
+            .raw(raw)
+            .into_api(manifest_path)
+            .map_err(|err| {
+                tracing::info!(
+                    "docs.rs rustdoc json is unusable ({}); building locally",
+                    err
+                )
+            })
+            .ok()
+    });
+    let api = match prebuilt {
+        Some(api) => api,
+        // Keep the build out of cargo's registry cache
+        None => rustdoc
+            .clone()
+            .target_directory(metadata.target_directory.join("crate-api/registry-base"))
+            .into_api(manifest_path)?,
+    };
+    Ok(api)
+}
 
-    let mode = args.mode();
-    let base = match mode {
-        args::Mode::DumpRaw => None,
-        args::Mode::Api => None,
-        args::Mode::Diff => {
-            let base = args
-                .base()
-                .map(Ok)
-                .unwrap_or_else(|| find_default_base(metadata.workspace_root.as_std_path()))
-                .with_code(proc_exit::Code::FAILURE)?;
-            Some(base)
+fn compare(
+    rustdoc: &crate_api::RustDocBuilder,
+    pkg: &cargo_metadata::Package,
+    other: &std::path::Path,
+    format: args::Format,
+) -> Result<(), eyre::Report> {
+    let api = rustdoc
+        .clone()
+        .into_api(pkg.manifest_path.as_path().as_std_path())?;
+    let other_api = rustdoc.clone().into_api(other)?;
+    let other_pkg = resolve_package(other)?;
+
+    let report = report::Compatibility::new(
+        &pkg.name,
+        &freeze::normalized_surface(&api),
+        &other_pkg.name,
+        &freeze::normalized_surface(&other_api),
+    );
+
+    match format {
+        args::Format::Silent => {}
+        args::Format::Pretty => {
+            let _ = writeln!(
+                output::stdout(),
+                "{}",
+                serde_json::to_string_pretty(&report)?
+            );
+        }
+        args::Format::Md => {
+            let stdout = output::stdout();
+            let mut stdout = stdout.lock();
+            report::render_compatibility_markdown(&mut stdout, &report)?;
+        }
+        args::Format::Json => {
+            let _ = writeln!(output::stdout(), "{}", serde_json::to_string(&report)?);
         }
+        unsupported @ (args::Format::Csv
+        | args::Format::Tsv
+        | args::Format::MdChangelog
+        | args::Format::Github
+        | args::Format::Sarif
+        | args::Format::Html) => {
+            return Err(error::unsupported_format(unsupported, "--compare"));
+        }
+    }
+
+    Ok(())
+}
+
+/// Diff the locked version of a dependency against a candidate upgrade
+fn dep_check(
+    rustdoc: &crate_api::RustDocBuilder,
+    metadata: &cargo_metadata::Metadata,
+    pkg: &cargo_metadata::Package,
+    dep: &str,
+    to: Option<&str>,
+    used: bool,
+    format: args::Format,
+) -> Result<(), eyre::Report> {
+    let (locked, lib_name) = locked_dependency(metadata, pkg, dep)?;
+    // A bare version means exactly that version, rather than cargo's default of `^`
+    let req = match to {
+        Some(to) if to.starts_with(|c: char| c.is_ascii_digit()) => format!("={}", to),
+        Some(to) => to.to_owned(),
+        None => "*".to_owned(),
     };
+    let candidate_path = fetch_registry_package(metadata, &locked.name, &req)?;
+    let candidate = resolve_package(&candidate_path)?;
+    if candidate.version == locked.version {
+        ::log::info!("{} is already at {}", locked.name, locked.version);
+    }
 
-    let (selected, _) = args.workspace.partition_packages(&metadata);
-    for selected in selected {
-        let res = match mode {
-            args::Mode::DumpRaw => dump_raw(selected, args.format),
-            args::Mode::Api => api(selected, args.format),
-            args::Mode::Diff => diff(&metadata, selected, base.clone().unwrap(), args.format),
-        };
-        match res {
-            Ok(()) => {}
-            Err(err) => {
-                ::log::error!("{}", err);
-                success = false;
-                continue;
-            }
-        };
+    let target_dir = metadata
+        .target_directory
+        .join("crate-api/dep-check")
+        .into_std_path_buf();
+    let mut before = rustdoc
+        .clone()
+        .target_directory(&target_dir)
+        .into_api(locked.manifest_path.as_std_path())?;
+    crate_api::manifest::Manifest::from(locked).into_api(&mut before);
+    let mut after = rustdoc
+        .clone()
+        .target_directory(&target_dir)
+        .into_api(&candidate_path)?;
+    crate_api::manifest::Manifest::from(&candidate).into_api(&mut after);
+
+    let mut diffs = Vec::new();
+    crate_api::diff::diff(&before, &after, &mut diffs);
+
+    if used {
+        let root = pkg
+            .manifest_path
+            .parent()
+            .expect("manifests are in a directory")
+            .as_std_path();
+        // Normalize `use` renames of the dependency to the name rustdoc reports
+        let api_name = before
+            .root_id
+            .and_then(|root_id| before.paths.get(root_id))
+            .map(|root| root.path.clone())
+            .unwrap_or_else(|| lib_name.to_owned());
+        let used_paths: std::collections::BTreeSet<_> = usage::used_paths(root, lib_name)?
+            .into_iter()
+            .map(|path| format!("{}{}", api_name, &path[lib_name.len()..]))
+            .collect();
+        ::log::debug!("{} uses {:?}", pkg.name, used_paths);
+        diffs.retain(|diff| {
+            let before_path = diff
+                .before
+                .and_then(|location| location.path_id)
+                .and_then(|path_id| before.paths.get(path_id));
+            let after_path = diff
+                .after
+                .and_then(|location| location.path_id)
+                .and_then(|path_id| after.paths.get(path_id));
+            before_path
+                .into_iter()
+                .chain(after_path)
+                .any(|path| usage::affects(&used_paths, &path.path))
+        });
     }
 
-    if success {
-        proc_exit::Code::SUCCESS.ok()
-    } else {
-        proc_exit::Code::FAILURE.ok()
+    match format {
+        args::Format::Silent => {}
+        args::Format::Pretty | args::Format::Json => {
+            let raw = report::Diff {
+                manifest_path: candidate_path,
+                against: report::Source::Registry(format!("{}@{}", locked.name, locked.version)),
+                before,
+                after,
+                diffs,
+            };
+            let raw = if format == args::Format::Pretty {
+                serde_json::to_string_pretty(&raw)?
+            } else {
+                serde_json::to_string(&raw)?
+            };
+            let _ = writeln!(output::stdout(), "{}", raw);
+        }
+        args::Format::Md => {
+            let stdout = output::stdout();
+            let mut stdout = stdout.lock();
+            let _ = writeln!(
+                stdout,
+                "# `{}` {} to {}",
+                locked.name, locked.version, candidate.version
+            );
+            let _ = writeln!(stdout);
+            report::render_diff_markdown(&mut stdout, &before, &after, &diffs)?;
+        }
+        unsupported @ (args::Format::Csv
+        | args::Format::Tsv
+        | args::Format::MdChangelog
+        | args::Format::Github
+        | args::Format::Sarif
+        | args::Format::Html) => {
+            return Err(error::unsupported_format(unsupported, "--dep-check"));
+        }
     }
+
+    Ok(())
 }
 
-fn dump_raw(pkg: &cargo_metadata::Package, format: args::Format) -> Result<(), eyre::Report> {
-    let colored_stderr = concolor_control::get(concolor_control::Stream::Stderr).color();
-    let raw = crate_api::RustDocBuilder::new()
-        .color(colored_stderr)
-        .dump_raw(pkg.manifest_path.as_path().as_std_path())?;
-    let raw: rustdoc_json_types_fork::Crate = serde_json::from_str(&raw)?;
+/// The version of `dep` that `pkg` currently builds against, with the name `pkg` uses for it
+fn locked_dependency<'m>(
+    metadata: &'m cargo_metadata::Metadata,
+    pkg: &cargo_metadata::Package,
+    dep: &str,
+) -> Result<(&'m cargo_metadata::Package, &'m str), eyre::Report> {
+    let resolve = metadata
+        .resolve
+        .as_ref()
+        .expect("present because called with deps");
+    let node = resolve
+        .nodes
+        .iter()
+        .find(|node| node.id == pkg.id)
+        .expect("selected packages are resolved");
+    node.deps
+        .iter()
+        .filter_map(|node_dep| {
+            let package = metadata.packages.iter().find(|p| p.id == node_dep.pkg)?;
+            Some((package, node_dep.name.as_str()))
+        })
+        .find(|(p, _)| p.name == dep)
+        .ok_or_else(|| eyre::eyre!("{} does not depend on {}", pkg.name, dep))
+}
 
-    let manifest = crate_api::manifest::Manifest::from(pkg);
+/// Download the latest `name` matching `req` from crates.io, returning its manifest
+fn fetch_registry_package(
+    metadata: &cargo_metadata::Metadata,
+    name: &str,
+    req: &str,
+) -> Result<std::path::PathBuf, eyre::Report> {
+    let root = metadata
+        .target_directory
+        .join(format!("crate-api/registry/{}", name))
+        .into_std_path_buf();
+    std::fs::create_dir_all(root.join("src"))?;
+    std::fs::write(root.join("src/lib.rs"), "")?;
+    // `[workspace]` keeps it out of the workspace whose target directory it lives in
+    std::fs::write(
+        root.join("Cargo.toml"),
+        format!(
+            "[package]
+name = \"crate-api-fetch\"
+version = \"0.0.0\"
 
-    let raw = report::Raw {
+[workspace]
+
+[dependencies]
+{} = \"{}\"
+",
+            name, req
+        ),
+    )?;
+    // Resolve fresh rather than reusing the last fetch
+    let _ = std::fs::remove_file(root.join("Cargo.lock"));
+
+    let fetched = cargo_metadata::MetadataCommand::new()
+        .manifest_path(root.join("Cargo.toml"))
+        .exec()?;
+    fetched
+        .packages
+        .into_iter()
+        .find(|p| p.name == name)
+        .map(|p| p.manifest_path.into_std_path_buf())
+        .ok_or_else(|| eyre::eyre!("Could not find {} {} on crates.io", name, req))
+}
+
+fn breaking(
+    rustdoc: &crate_api::RustDocBuilder,
+    metadata: &cargo_metadata::Metadata,
+    pkg: &cargo_metadata::Package,
+    base: report::Source,
+    format: args::Format,
+    check: bool,
+    docs_rs: bool,
+) -> Result<(), eyre::Report> {
+    let (before, after, diffs) = load_diff(rustdoc, metadata, pkg, &base, docs_rs)?;
+
+    let repo = git2::Repository::discover(pkg.manifest_path.as_std_path())?;
+    let oldest = match &base {
+        report::Source::Git(rev) => Some(repo.revparse_single(rev)?.peel_to_commit()?.id()),
+        report::Source::Path(_) | report::Source::Registry(_) => None,
+    };
+
+    let changes: Vec<_> = diffs
+        .into_iter()
+        .filter(|diff| diff.severity == crate_api::diff::Severity::Warn)
+        .map(|diff| {
+            let (api, location) = report::diff_location(&before, &after, &diff);
+            let path = report::location_name(api, location).into_owned();
+            let span = report::location_span(api, location).cloned();
+            // Removed items can't be blamed in the current tree
+            let blamed = span
+                .as_ref()
+                .filter(|_| diff.id.category != crate_api::diff::Category::Removed);
+            let introduced_by = blamed.and_then(|span| {
+                blame(&repo, oldest, metadata.workspace_root.as_std_path(), span).unwrap_or_else(
+                    |err| {
+                        ::log::warn!("Could not attribute `{}`: {}", path, err);
+                        None
+                    },
+                )
+            });
+            report::BreakingChange {
+                path,
+                diff,
+                span,
+                introduced_by,
+            }
+        })
+        .collect();
+
+    let found = changes.len();
+    let report = report::Breaking {
         manifest_path: pkg.manifest_path.clone().into_std_path_buf(),
-        rustdoc: Some(raw),
-        manifest: Some(manifest),
+        against: base,
+        changes,
     };
 
     match format {
         args::Format::Silent => {}
         args::Format::Pretty => {
-            let _ = writeln!(std::io::stdout(), "{}", serde_json::to_string_pretty(&raw)?);
-        }
-        args::Format::Md => {
             let _ = writeln!(
-                std::io::stdout(),
-                "```json
-{}
-```",
-                serde_json::to_string_pretty(&raw)?
+                output::stdout(),
+                "{}",
+                serde_json::to_string_pretty(&report)?
             );
         }
+        args::Format::Md => {
+            let stdout = output::stdout();
+            let mut stdout = stdout.lock();
+            report::render_breaking_markdown(&mut stdout, &report)?;
+        }
         args::Format::Json => {
-            let _ = writeln!(std::io::stdout(), "{}", serde_json::to_string(&raw)?);
+            let _ = writeln!(output::stdout(), "{}", serde_json::to_string(&report)?);
+        }
+        unsupported @ (args::Format::Csv
+        | args::Format::Tsv
+        | args::Format::MdChangelog
+        | args::Format::Github
+        | args::Format::Sarif
+        | args::Format::Html) => {
+            return Err(error::unsupported_format(unsupported, "--breaking"));
         }
     }
 
-    Ok(())
+    check_changes(pkg, check, found)
 }
 
-fn api(pkg: &cargo_metadata::Package, format: args::Format) -> Result<(), eyre::Report> {
-    let colored_stderr = concolor_control::get(concolor_control::Stream::Stderr).color();
-    let mut api = crate_api::RustDocBuilder::new()
-        .color(colored_stderr)
-        .into_api(pkg.manifest_path.as_path().as_std_path())?;
-
-    let manifest = crate_api::manifest::Manifest::from(pkg);
-    manifest.into_api(&mut api);
+/// Fail unless the version in `Cargo.toml` is bumped enough for the API changes since `base`
+fn check_release(
+    rustdoc: &crate_api::RustDocBuilder,
+    metadata: &cargo_metadata::Metadata,
+    pkg: &cargo_metadata::Package,
+    base: report::Source,
+    format: args::Format,
+    docs_rs: bool,
+) -> Result<(), eyre::Report> {
+    let (old_pkg, (before, after, diffs)) =
+        load_diff_with_base(rustdoc, metadata, pkg, &base, docs_rs)?;
+    let report = report::Release::new(pkg, &old_pkg, base, &before, &after, diffs);
 
     match format {
         args::Format::Silent => {}
         args::Format::Pretty => {
-            // HACK: Real version (using `termtree`) isn't implemented yet
-            let _ = writeln!(std::io::stdout(), "{}", serde_json::to_string_pretty(&api)?);
+            let _ = writeln!(
+                output::stdout(),
+                "{}",
+                serde_json::to_string_pretty(&report)?
+            );
         }
         args::Format::Md => {
-            let stdout = std::io::stdout();
+            let stdout = output::stdout();
             let mut stdout = stdout.lock();
-            report::render_api_markdown(&mut stdout, &api)?;
+            report::render_release_markdown(&mut stdout, &report)?;
         }
         args::Format::Json => {
-            let _ = writeln!(std::io::stdout(), "{}", serde_json::to_string(&api)?);
+            let _ = writeln!(output::stdout(), "{}", serde_json::to_string(&report)?);
+        }
+        unsupported @ (args::Format::Csv
+        | args::Format::Tsv
+        | args::Format::MdChangelog
+        | args::Format::Github
+        | args::Format::Sarif
+        | args::Format::Html) => {
+            return Err(error::unsupported_format(unsupported, "--check-release"));
         }
     }
 
-    Ok(())
+    if report.is_sufficient() {
+        return Ok(());
+    }
+    for change in &report.changes {
+        ::log::error!(
+            "{}: `{}` needs a {} bump: {} ({})",
+            pkg.name,
+            change.path,
+            change.bump,
+            change.diff.id.explanation,
+            change.reference
+        );
+    }
+    let problem = match report.bumped {
+        Some(bumped) => format!(
+            "is a {} bump from {} but the API changes require a {} bump, like {}",
+            bumped,
+            report.before,
+            report.required,
+            crate_api::diff::rules::next_version(&report.before, report.required)
+        ),
+        None => format!("is older than {}", report.before),
+    };
+    Err(error::coded(
+        error::ErrorCode::CheckFailed,
+        format!("{} {} {}", pkg.name, report.after, problem),
+    ))
 }
 
-fn diff(
+/// Binary search `good..bad`, along first parents, for the commit introducing a breaking change
+fn bisect(
+    rustdoc: &crate_api::RustDocBuilder,
     metadata: &cargo_metadata::Metadata,
     pkg: &cargo_metadata::Package,
-    base: report::Source,
+    good: &str,
+    bad: &str,
+    change: Option<&str>,
     format: args::Format,
 ) -> Result<(), eyre::Report> {
-    let colored_stderr = concolor_control::get(concolor_control::Stream::Stderr).color();
-    let mut after = crate_api::RustDocBuilder::new()
-        .color(colored_stderr)
-        .into_api(pkg.manifest_path.as_path().as_std_path())?;
-    let manifest = crate_api::manifest::Manifest::from(pkg);
-    manifest.into_api(&mut after);
+    let repo = git2::Repository::discover(pkg.manifest_path.as_std_path())?;
+    let good_id = repo.revparse_single(good)?.peel_to_commit()?.id();
+    let bad_id = repo.revparse_single(bad)?.peel_to_commit()?.id();
 
-    let base_path = resolve_source_path(metadata, pkg, &base)?;
-    let mut before = crate_api::RustDocBuilder::new()
-        .color(colored_stderr)
-        .into_api(&base_path)?;
-    let old_pkg = resolve_package(&base_path)?;
-    let manifest = crate_api::manifest::Manifest::from(&old_pkg);
-    manifest.into_api(&mut before);
+    let mut revwalk = repo.revwalk()?;
+    revwalk.push(bad_id)?;
+    revwalk.hide(good_id)?;
+    revwalk.simplify_first_parent()?;
+    revwalk.set_sorting(git2::Sort::TOPOLOGICAL | git2::Sort::REVERSE)?;
+    let candidates = revwalk.collect::<Result<Vec<_>, _>>()?;
+    if candidates.is_empty() {
+        eyre::bail!("`{}` is not an ancestor of `{}`", good, bad);
+    }
 
-    let mut diffs = Vec::new();
-    crate_api::diff::diff(&before, &after, &mut diffs);
+    let config = config::PackageConfig::from_package(pkg)?;
+    let mut baseline = api_at(rustdoc, metadata, pkg, &repo, good_id)?;
+    config.mark_experimental(&mut baseline);
+    let mut tested = 1;
+    let mut is_bad = |commit: git2::Oid| -> Result<bool, eyre::Report> {
+        tested += 1;
+        let mut candidate = api_at(rustdoc, metadata, pkg, &repo, commit)?;
+        config.mark_experimental(&mut candidate);
+        let mut diffs = Vec::new();
+        crate_api::diff::diff(&baseline, &candidate, &mut diffs);
+        config.filter_diffs(&baseline, &candidate, &mut diffs);
+        let found = diffs.iter().any(|diff| match change {
+            Some(change) => diff.id.name == change,
+            None => diff.severity == crate_api::diff::Severity::Warn,
+        });
+        ::log::info!("{}: {}", commit, if found { "bad" } else { "good" });
+        Ok(found)
+    };
+
+    // Invariant: `candidates[hi]` is bad while everything before `lo` is good
+    let mut lo = 0;
+    let mut hi = candidates.len() - 1;
+    if !is_bad(candidates[hi])? {
+        eyre::bail!(
+            "`{}` has no {} compared to `{}`",
+            bad,
+            change.unwrap_or("breaking change"),
+            good
+        );
+    }
+    while lo < hi {
+        let mid = lo + (hi - lo) / 2;
+        if is_bad(candidates[mid])? {
+            hi = mid;
+        } else {
+            lo = mid + 1;
+        }
+    }
+
+    let report = report::Bisect {
+        manifest_path: pkg.manifest_path.clone().into_std_path_buf(),
+        good: good.to_owned(),
+        bad: bad.to_owned(),
+        change: change.map(|change| change.to_owned()),
+        first_bad: attribute(&repo.find_commit(candidates[hi])?),
+        tested,
+    };
 
     match format {
         args::Format::Silent => {}
         args::Format::Pretty => {
-            // HACK: Real version (using `termtree`) isn't implemented yet
-            let raw = report::Diff {
-                manifest_path: pkg.manifest_path.clone().into_std_path_buf(),
-                against: base,
-                before,
-                after,
-                diffs,
-            };
-            let _ = writeln!(std::io::stdout(), "{}", serde_json::to_string_pretty(&raw)?);
+            let _ = writeln!(
+                output::stdout(),
+                "{}",
+                serde_json::to_string_pretty(&report)?
+            );
         }
         args::Format::Md => {
-            let stdout = std::io::stdout();
+            let stdout = output::stdout();
             let mut stdout = stdout.lock();
-            report::render_diff_markdown(&mut stdout, &before, &after, &diffs)?;
+            report::render_bisect_markdown(&mut stdout, &report)?;
         }
         args::Format::Json => {
-            let raw = report::Diff {
-                manifest_path: pkg.manifest_path.clone().into_std_path_buf(),
-                against: base,
-                before,
-                after,
-                diffs,
-            };
-            let _ = writeln!(std::io::stdout(), "{}", serde_json::to_string(&raw)?);
+            let _ = writeln!(output::stdout(), "{}", serde_json::to_string(&report)?);
+        }
+        unsupported @ (args::Format::Csv
+        | args::Format::Tsv
+        | args::Format::MdChangelog
+        | args::Format::Github
+        | args::Format::Sarif
+        | args::Format::Html) => {
+            return Err(error::unsupported_format(unsupported, "--bisect"));
+        }
+    }
+
+    Ok(())
+}
+
+/// Extract the API of `pkg` as of `commit`, caching it in the target directory
+fn api_at(
+    rustdoc: &crate_api::RustDocBuilder,
+    metadata: &cargo_metadata::Metadata,
+    pkg: &cargo_metadata::Package,
+    repo: &git2::Repository,
+    commit: git2::Oid,
+) -> Result<crate_api::Api, eyre::Report> {
+    cached_api(rustdoc, metadata, pkg, &commit.to_string(), || {
+        let target = metadata
+            .target_directory
+            .join(format!("crate-api/{}-bisect", pkg.name))
+            .into_std_path_buf();
+        let commit = repo.find_commit(commit)?;
+        checkout_object(repo, commit.as_object(), &target)?;
+        let manifest_path = find_in_checkout(repo, pkg, &target)?;
+
+        let mut api = rustdoc.clone().into_api(&manifest_path)?;
+        let old_pkg = resolve_package(&manifest_path)?;
+        let manifest = crate_api::manifest::Manifest::from(&old_pkg);
+        manifest.into_api(&mut api);
+        Ok(api)
+    })
+}
+
+/// Extract the API of `version` of `pkg` from crates.io, caching it like [`api_at`]
+fn registry_api_at(
+    rustdoc: &crate_api::RustDocBuilder,
+    metadata: &cargo_metadata::Metadata,
+    pkg: &cargo_metadata::Package,
+    version: &cargo_metadata::Version,
+    docs_rs: bool,
+) -> Result<crate_api::Api, eyre::Report> {
+    cached_api(rustdoc, metadata, pkg, &version.to_string(), || {
+        let manifest_path = fetch_registry_package(metadata, &pkg.name, &format!("={}", version))?;
+        let old_pkg = resolve_package(&manifest_path)?;
+        let mut api = registry_api(rustdoc, metadata, &old_pkg, &manifest_path, docs_rs)?;
+        let manifest = crate_api::manifest::Manifest::from(&old_pkg);
+        manifest.into_api(&mut api);
+        Ok(api)
+    })
+}
+
+/// The API of `pkg` at `revision`, from the target directory's cache or else `extract`
+fn cached_api(
+    rustdoc: &crate_api::RustDocBuilder,
+    metadata: &cargo_metadata::Metadata,
+    pkg: &cargo_metadata::Package,
+    revision: &str,
+    extract: impl FnOnce() -> Result<crate_api::Api, eyre::Report>,
+) -> Result<crate_api::Api, eyre::Report> {
+    let cache_path = metadata
+        .target_directory
+        .join(format!(
+            "crate-api/cache/{}-{}-{}.json",
+            pkg.name,
+            revision,
+            builder_hash(rustdoc)
+        ))
+        .into_std_path_buf();
+    if let Ok(cached) = std::fs::read_to_string(&cache_path) {
+        match serde_json::from_str(&cached) {
+            Ok(api) => {
+                ::log::debug!("Using cached API from {}", cache_path.display());
+                return Ok(api);
+            }
+            Err(err) => {
+                ::log::warn!("Ignoring cached API at {}: {}", cache_path.display(), err);
+            }
         }
     }
 
+    let api = extract()?;
+
+    if let Some(parent) = cache_path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    std::fs::write(&cache_path, serde_json::to_string(&api)?)?;
+
+    Ok(api)
+}
+
+/// Identify the features, toolchain, target, etc `rustdoc` documents with
+fn builder_hash(rustdoc: &crate_api::RustDocBuilder) -> String {
+    use std::hash::Hash;
+    use std::hash::Hasher;
+
+    let mut hasher = std::collections::hash_map::DefaultHasher::new();
+    format!("{:?}", rustdoc).hash(&mut hasher);
+    format!("{:016x}", hasher.finish())
+}
+
+fn render_plugin(
+    plugin: &std::path::Path,
+    mode: &'static str,
+    data: &impl serde::Serialize,
+) -> Result<(), eyre::Report> {
+    let response = plugin::render(plugin, mode, data)?;
+    let _ = write!(output::stdout(), "{}", response.output);
+    for finding in &response.findings {
+        ::log::error!("{}", finding);
+    }
+    if !response.findings.is_empty() {
+        return Err(error::coded(
+            error::ErrorCode::CheckFailed,
+            format!(
+                "{} reported {} finding(s)",
+                plugin.display(),
+                response.findings.len()
+            ),
+        ));
+    }
     Ok(())
 }
 
@@ -222,6 +2555,62 @@ fn find_default_base(path: &std::path::Path) -> Result<report::Source, eyre::Rep
     eyre::bail!("Could not find a tag for {} for base", path.display());
 }
 
+/// Find the most recent commit, newer than `oldest`, to touch `span`
+fn blame(
+    repo: &git2::Repository,
+    oldest: Option<git2::Oid>,
+    workspace_root: &std::path::Path,
+    span: &crate_api::Span,
+) -> Result<Option<report::Attribution>, eyre::Report> {
+    let workdir = repo
+        .workdir()
+        .ok_or_else(|| eyre::eyre!("Cannot blame in a bare repository"))?
+        .canonicalize()?;
+    let path = workspace_root.join(&span.filename).canonicalize()?;
+    let path = path.strip_prefix(&workdir).map_err(|_| {
+        eyre::eyre!(
+            "{} is outside of {}",
+            span.filename.display(),
+            workdir.display()
+        )
+    })?;
+
+    let mut options = git2::BlameOptions::new();
+    options.min_line(span.begin.0).max_line(span.end.0);
+    if let Some(oldest) = oldest {
+        options.oldest_commit(oldest);
+    }
+    let blame = repo.blame_file(path, Some(&mut options))?;
+
+    let mut newest: Option<git2::Commit<'_>> = None;
+    for hunk in blame.iter() {
+        // Boundary hunks are unchanged since `oldest`
+        if hunk.is_boundary() {
+            continue;
+        }
+        let commit = repo.find_commit(hunk.final_commit_id())?;
+        if newest
+            .as_ref()
+            .map(|newest| newest.time() < commit.time())
+            .unwrap_or(true)
+        {
+            newest = Some(commit);
+        }
+    }
+
+    Ok(newest.as_ref().map(attribute))
+}
+
+fn attribute(commit: &git2::Commit<'_>) -> report::Attribution {
+    let author = commit.author();
+    report::Attribution {
+        commit: commit.id().to_string(),
+        author: String::from_utf8_lossy(author.name_bytes()).into_owned(),
+        email: author.email().map(|email| email.to_owned()),
+        summary: commit.summary().map(|summary| summary.to_owned()),
+    }
+}
+
 fn resolve_package(path: &std::path::Path) -> Result<cargo_metadata::Package, eyre::Report> {
     let metadata = cargo_metadata::MetadataCommand::new()
         .manifest_path(path)
@@ -255,12 +2644,21 @@ fn resolve_source_path(
                 .target_directory
                 .join(format!("crate-api/{}-base", pkg.name))
                 .into_std_path_buf();
-            checkout_ref(pkg.manifest_path.as_std_path(), &target, rev)?;
-            find_by_package_name(&pkg.name, &target)
+            let repo = git2::Repository::discover(pkg.manifest_path.as_std_path())?;
+            let rev = repo.revparse_single(rev)?;
+            checkout_object(&repo, &rev, &target)?;
+            find_in_checkout(&repo, pkg, &target)
         }
         report::Source::Path(path) => Ok(path.to_owned()),
-        report::Source::Registry(_) => {
-            todo!()
+        report::Source::Registry(spec) => {
+            let (name, version) = spec
+                .split_once('@')
+                .unwrap_or((pkg.name.as_str(), spec.as_str()));
+            let req = match version {
+                "" | "latest" => "*".to_owned(),
+                version => format!("={}", version),
+            };
+            fetch_registry_package(metadata, name, &req)
         }
     }
 }
@@ -283,22 +2681,151 @@ fn find_by_package_name(
         .ok_or_else(|| eyre::eyre!("Could no find {} at {}", name, target.display()))
 }
 
-fn checkout_ref(
-    source: &std::path::Path,
+/// Check out `object` into `target`, leaving the repo's working tree and index as they are
+///
+/// Uncommitted changes stay out of the checkout.  `target` is emptied first, so files from an
+/// earlier checkout don't linger, except for its `target` directory to speed up rebuilds.
+fn checkout_object(
+    repo: &git2::Repository,
+    object: &git2::Object<'_>,
     target: &std::path::Path,
-    rev: &str,
 ) -> Result<(), eyre::Report> {
-    let repo = git2::Repository::discover(source)?;
-
-    let rev = repo.revparse_single(rev)?;
+    if target.exists() {
+        for entry in std::fs::read_dir(target)? {
+            let entry = entry?;
+            if entry.file_name() == "target" {
+                continue;
+            }
+            if entry.file_type()?.is_dir() {
+                std::fs::remove_dir_all(entry.path())?;
+            } else {
+                std::fs::remove_file(entry.path())?;
+            }
+        }
+    }
 
     let mut co = git2::build::CheckoutBuilder::new();
-    co.target_dir(target)
-        .remove_untracked(true)
-        .remove_ignored(true)
-        .use_ours(true)
-        .force();
-    repo.checkout_tree(&rev, Some(&mut co))?;
+    co.target_dir(target).update_index(false).force();
+    repo.checkout_tree(object, Some(&mut co))?;
 
     Ok(())
 }
+
+/// Find `pkg` in a checkout of its repo at `target`
+fn find_in_checkout(
+    repo: &git2::Repository,
+    pkg: &cargo_metadata::Package,
+    target: &std::path::Path,
+) -> Result<std::path::PathBuf, eyre::Report> {
+    // The same place relative to the repo, even when the repo root isn't the workspace root
+    if let Some(workdir) = repo.workdir() {
+        let workdir = std::fs::canonicalize(workdir)?;
+        let manifest_path = std::fs::canonicalize(pkg.manifest_path.as_std_path())?;
+        if let Ok(relative) = manifest_path.strip_prefix(&workdir) {
+            let candidate = target.join(relative);
+            if candidate.is_file() {
+                return Ok(candidate);
+            }
+        }
+    }
+    // Moved since
+    find_by_package_name(&pkg.name, target)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn features(features: &[&str]) -> Vec<String> {
+        features
+            .iter()
+            .map(|feature| (*feature).to_owned())
+            .collect()
+    }
+
+    #[test]
+    fn combinations_smallest_first() {
+        assert_eq!(
+            combinations(&features(&["a", "b", "c"]), 2),
+            [
+                features(&[]),
+                features(&["a"]),
+                features(&["b"]),
+                features(&["c"]),
+                features(&["a", "b"]),
+                features(&["a", "c"]),
+                features(&["b", "c"]),
+            ]
+        );
+    }
+
+    #[test]
+    fn combinations_depth() {
+        assert_eq!(combinations(&features(&["a", "b"]), 0), [features(&[])]);
+        // Deeper than there are features is the full powerset
+        let all = combinations(&features(&["a", "b", "c"]), 5);
+        assert_eq!(all.len(), 8);
+        assert_eq!(all.last(), Some(&features(&["a", "b", "c"])));
+    }
+
+    #[test]
+    fn combinations_empty() {
+        assert_eq!(combinations(&[], 3), [features(&[])]);
+    }
+
+    fn commit(repo: &git2::Repository, content: &str, message: &str) -> git2::Oid {
+        let workdir = repo.workdir().unwrap();
+        std::fs::write(workdir.join("lib.rs"), content).unwrap();
+        let mut index = repo.index().unwrap();
+        index.add_path(std::path::Path::new("lib.rs")).unwrap();
+        index.write().unwrap();
+        let tree = repo.find_tree(index.write_tree().unwrap()).unwrap();
+        let signature = git2::Signature::now("Author", "author@example.com").unwrap();
+        let parents = repo
+            .head()
+            .ok()
+            .map(|head| head.peel_to_commit().unwrap())
+            .into_iter()
+            .collect::<Vec<_>>();
+        let parents = parents.iter().collect::<Vec<_>>();
+        repo.commit(
+            Some("HEAD"),
+            &signature,
+            &signature,
+            message,
+            &tree,
+            &parents,
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn blame_line() {
+        let temp = tempfile::tempdir().unwrap();
+        let repo = git2::Repository::init(temp.path()).unwrap();
+        // Hunks from the root commit are boundaries
+        commit(&repo, "", "Initial");
+        let first = commit(&repo, "pub const A: u8 = 0;\npub const B: u8 = 0;\n", "Add");
+        let second = commit(
+            &repo,
+            "pub const A: u8 = 0;\npub const B: u8 = 1;\n",
+            "Change B",
+        );
+
+        let span = |line| crate_api::Span {
+            filename: "lib.rs".into(),
+            begin: (line, 0),
+            end: (line, 20),
+        };
+        let a = blame(&repo, None, temp.path(), &span(1)).unwrap().unwrap();
+        assert_eq!(a.commit, first.to_string());
+        let b = blame(&repo, None, temp.path(), &span(2)).unwrap().unwrap();
+        assert_eq!(b.commit, second.to_string());
+        assert_eq!(b.summary.as_deref(), Some("Change B"));
+
+        // Unchanged since `oldest`
+        assert!(blame(&repo, Some(second), temp.path(), &span(1))
+            .unwrap()
+            .is_none());
+    }
+}
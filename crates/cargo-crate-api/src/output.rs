@@ -0,0 +1,53 @@
+//! Where reports are written, so `--output` only replaces its file once a run succeeds
+
+use std::io::Write;
+
+thread_local! {
+    static CAPTURED: std::cell::RefCell<Option<Vec<u8>>> = std::cell::RefCell::new(None);
+}
+
+/// The report destination: stdout, unless [`capture`] was called
+pub fn stdout() -> Stdout {
+    Stdout
+}
+
+/// Buffer everything written to [`stdout`] until [`take`]
+pub fn capture() {
+    CAPTURED.with(|captured| *captured.borrow_mut() = Some(Vec::new()));
+}
+
+/// End [`capture`], returning what was written
+pub fn take() -> crate::report::Report {
+    let content = CAPTURED.with(|captured| captured.borrow_mut().take());
+    crate::report::Report::new(content.unwrap_or_default())
+}
+
+pub struct Stdout;
+
+impl Stdout {
+    /// Mirrors [`std::io::Stdout::lock`]
+    pub fn lock(&self) -> Self {
+        Stdout
+    }
+}
+
+impl Write for Stdout {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        let captured = CAPTURED.with(|captured| {
+            captured
+                .borrow_mut()
+                .as_mut()
+                .map(|content| content.extend_from_slice(buf))
+                .is_some()
+        });
+        if captured {
+            Ok(buf.len())
+        } else {
+            std::io::stdout().write(buf)
+        }
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        std::io::stdout().flush()
+    }
+}
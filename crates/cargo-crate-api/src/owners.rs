@@ -0,0 +1,48 @@
+/// Map paths to the teams responsible for them, like `CODEOWNERS`
+///
+/// Each line is a path glob followed by one or more owners.  When multiple lines match, the
+/// last one wins.
+#[derive(Clone, Debug, Default)]
+pub struct Owners {
+    rules: Vec<(crate_api::PathGlob, Vec<String>)>,
+}
+
+pub const UNOWNED: &str = "(unowned)";
+
+impl Owners {
+    pub fn load(path: &std::path::Path) -> Result<Self, eyre::Report> {
+        let content = std::fs::read_to_string(path)
+            .map_err(|e| eyre::eyre!("Failed when reading {}: {}", path.display(), e))?;
+        Ok(Self::parse(&content))
+    }
+
+    pub fn parse(content: &str) -> Self {
+        let rules = content
+            .lines()
+            .map(|line| line.split_once('#').map(|(line, _)| line).unwrap_or(line))
+            .filter_map(|line| {
+                let mut fields = line.split_whitespace();
+                let pattern = fields.next()?;
+                let owners: Vec<_> = fields.map(|owner| owner.to_owned()).collect();
+                Some((crate_api::PathGlob::new(pattern), owners))
+            })
+            .collect();
+        Self { rules }
+    }
+
+    pub fn owners_of(&self, path: &str) -> Option<&[String]> {
+        self.rules
+            .iter()
+            .rev()
+            .find(|(pattern, _)| pattern.matches(path))
+            .map(|(_, owners)| owners.as_slice())
+            .filter(|owners| !owners.is_empty())
+    }
+
+    /// The group name for reports
+    pub fn group_of(&self, path: Option<&str>) -> String {
+        path.and_then(|path| self.owners_of(path))
+            .map(|owners| owners.join(" "))
+            .unwrap_or_else(|| UNOWNED.to_owned())
+    }
+}
@@ -0,0 +1,78 @@
+//! Host for user-provided WASM report generators
+//!
+//! A plugin is a WASM module exporting:
+//! - `memory`
+//! - `cargo_crate_api_alloc(len: i32) -> i32`: reserve `len` bytes for the input
+//! - `cargo_crate_api_render(ptr: i32, len: i32) -> i64`: render the input, returning the
+//!   location of the result as `ptr << 32 | len`
+//!
+//! The input is a JSON [`Request`] and the result must be a JSON [`Response`].
+
+#[derive(serde::Serialize)]
+#[serde(rename_all = "snake_case")]
+pub struct Request<'d, D: serde::Serialize> {
+    pub mode: &'static str,
+    pub data: &'d D,
+}
+
+#[derive(Default, serde::Deserialize)]
+#[serde(rename_all = "snake_case")]
+#[serde(default)]
+pub struct Response {
+    /// Written verbatim to stdout
+    pub output: String,
+    /// Reported as errors, failing the run
+    pub findings: Vec<String>,
+}
+
+pub fn render<D: serde::Serialize>(
+    plugin: &std::path::Path,
+    mode: &'static str,
+    data: &D,
+) -> Result<Response, eyre::Report> {
+    let wasm = std::fs::read(plugin)
+        .map_err(|e| eyre::eyre!("Failed when reading {}: {}", plugin.display(), e))?;
+    let input = serde_json::to_vec(&Request { mode, data })?;
+    let input_len = i32::try_from(input.len())
+        .map_err(|_| eyre::eyre!("Input is too large for {}", plugin.display()))?;
+
+    let engine = wasmi::Engine::default();
+    let module = wasmi::Module::new(&engine, &wasm[..])
+        .map_err(|e| eyre::eyre!("Failed when loading {}: {}", plugin.display(), e))?;
+    let mut store = wasmi::Store::new(&engine, ());
+    let linker = wasmi::Linker::<()>::new(&engine);
+    let instance = linker
+        .instantiate(&mut store, &module)
+        .and_then(|instance| instance.start(&mut store))
+        .map_err(|e| eyre::eyre!("Failed when instantiating {}: {}", plugin.display(), e))?;
+
+    let memory = instance
+        .get_memory(&store, "memory")
+        .ok_or_else(|| eyre::eyre!("{} does not export `memory`", plugin.display()))?;
+    let alloc = instance
+        .get_typed_func::<i32, i32>(&store, "cargo_crate_api_alloc")
+        .map_err(|e| eyre::eyre!("{}: `cargo_crate_api_alloc`: {}", plugin.display(), e))?;
+    let render = instance
+        .get_typed_func::<(i32, i32), i64>(&store, "cargo_crate_api_render")
+        .map_err(|e| eyre::eyre!("{}: `cargo_crate_api_render`: {}", plugin.display(), e))?;
+
+    let input_ptr = alloc
+        .call(&mut store, input_len)
+        .map_err(|e| eyre::eyre!("{} failed to allocate: {}", plugin.display(), e))?;
+    memory
+        .write(&mut store, input_ptr as u32 as usize, &input)
+        .map_err(|e| eyre::eyre!("{} allocated invalid memory: {}", plugin.display(), e))?;
+
+    let packed = render
+        .call(&mut store, (input_ptr, input_len))
+        .map_err(|e| eyre::eyre!("{} failed to render: {}", plugin.display(), e))?;
+    let output_ptr = (packed as u64 >> 32) as usize;
+    let output_len = (packed as u64 & u64::from(u32::MAX)) as usize;
+    let mut output = vec![0; output_len];
+    memory
+        .read(&store, output_ptr, &mut output)
+        .map_err(|e| eyre::eyre!("{} returned invalid memory: {}", plugin.display(), e))?;
+
+    serde_json::from_slice(&output)
+        .map_err(|e| eyre::eyre!("{} returned an invalid response: {}", plugin.display(), e))
+}
@@ -1,3 +1,40 @@
+/// A rendered report, ready to be saved
+#[derive(Clone, Debug, Default)]
+pub struct Report {
+    content: Vec<u8>,
+}
+
+impl Report {
+    pub fn new(content: Vec<u8>) -> Self {
+        Self { content }
+    }
+
+    /// Replace the file at `path` with the report
+    ///
+    /// The report is written to a temporary file next to `path` and renamed over it, so a failed
+    /// write never leaves `path` truncated.
+    pub fn write_to(&self, path: &std::path::Path) -> Result<(), eyre::Report> {
+        let file_name = path
+            .file_name()
+            .ok_or_else(|| eyre::eyre!("{} is not a file path", path.display()))?;
+        let mut tmp_name = std::ffi::OsString::from(".");
+        tmp_name.push(file_name);
+        tmp_name.push(format!(".{}.tmp", std::process::id()));
+        let tmp_path = path.with_file_name(tmp_name);
+
+        let written = std::fs::File::create(&tmp_path).and_then(|mut file| {
+            std::io::Write::write_all(&mut file, &self.content)?;
+            file.sync_all()
+        });
+        let written = written.and_then(|()| std::fs::rename(&tmp_path, path));
+        if let Err(e) = written {
+            let _ = std::fs::remove_file(&tmp_path);
+            eyre::bail!("Failed when writing {}: {}", path.display(), e);
+        }
+        Ok(())
+    }
+}
+
 #[derive(serde::Serialize)]
 #[serde(rename_all = "snake_case")]
 pub struct Raw {
@@ -23,7 +60,111 @@ pub struct Diff {
     pub diffs: Vec<crate_api::diff::Diff>,
 }
 
-#[derive(Clone, Debug, PartialEq, Eq, serde::Serialize)]
+/// A diff's changes in a stable shape, for tools post-processing them
+///
+/// Unlike [`Diff`], this doesn't carry the APIs, so ids are resolved to paths and spans.
+#[derive(serde::Serialize)]
+#[serde(rename_all = "snake_case")]
+pub struct Findings {
+    /// Bumped when a field is removed or changes meaning
+    pub schema_version: u32,
+    pub manifest_path: std::path::PathBuf,
+    pub against: Source,
+    pub summary: FindingsSummary,
+    pub findings: Vec<Finding>,
+}
+
+#[derive(serde::Serialize)]
+#[serde(rename_all = "snake_case")]
+pub struct FindingsSummary {
+    /// The bump the changes require
+    pub required_bump: crate_api::diff::rules::Bump,
+    pub breaking: usize,
+    pub reported: usize,
+    pub allowed: usize,
+}
+
+#[derive(serde::Serialize)]
+#[serde(rename_all = "snake_case")]
+pub struct Finding {
+    pub id: &'static str,
+    pub severity: crate_api::diff::Severity,
+    pub category: crate_api::diff::Category,
+    pub bump: crate_api::diff::rules::Bump,
+    pub old_path: Option<String>,
+    pub new_path: Option<String>,
+    pub old_span: Option<crate_api::Span>,
+    pub new_span: Option<crate_api::Span>,
+    pub message: String,
+    /// Where the semver reference covers this change
+    pub reference: &'static str,
+}
+
+impl Findings {
+    pub const SCHEMA_VERSION: u32 = 1;
+
+    pub fn new(
+        manifest_path: std::path::PathBuf,
+        against: Source,
+        before: &crate_api::Api,
+        after: &crate_api::Api,
+        diffs: &[crate_api::diff::Diff],
+    ) -> Self {
+        let count = |severity| {
+            diffs
+                .iter()
+                .filter(|diff| diff.severity == severity)
+                .count()
+        };
+        let summary = FindingsSummary {
+            required_bump: crate_api::diff::rules::required_bump(diffs),
+            breaking: count(crate_api::diff::Severity::Warn),
+            reported: count(crate_api::diff::Severity::Report),
+            allowed: count(crate_api::diff::Severity::Allow),
+        };
+        let findings = diffs
+            .iter()
+            .map(|diff| {
+                let (api, location) = diff_location(before, after, diff);
+                Finding {
+                    id: diff.id.name,
+                    severity: diff.severity,
+                    category: diff.id.category,
+                    bump: crate_api::diff::rules::classify(diff),
+                    old_path: diff
+                        .before
+                        .map(|location| location_name(before, location).into_owned()),
+                    new_path: diff
+                        .after
+                        .map(|location| location_name(after, location).into_owned()),
+                    old_span: diff
+                        .before
+                        .and_then(|location| location_span(before, location))
+                        .cloned(),
+                    new_span: diff
+                        .after
+                        .and_then(|location| location_span(after, location))
+                        .cloned(),
+                    message: format!(
+                        "`{}`: {}",
+                        location_name(api, location),
+                        diff.id.explanation
+                    ),
+                    reference: crate_api::diff::rules::rule(diff.id).reference,
+                }
+            })
+            .collect();
+        Self {
+            schema_version: Self::SCHEMA_VERSION,
+            manifest_path,
+            against,
+            summary,
+            findings,
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Hash, serde::Serialize, serde::Deserialize)]
 #[serde(rename_all = "snake_case")]
 pub enum Source {
     Git(String),
@@ -31,205 +172,3405 @@ pub enum Source {
     Registry(String),
 }
 
-pub fn render_api_markdown(
-    writer: &mut dyn std::io::Write,
-    api: &crate_api::Api,
-) -> Result<(), eyre::Report> {
-    let root_id = *match api.root_id.as_ref() {
-        Some(root_id) => root_id,
-        None => return Ok(()),
-    };
+/// Breaking changes since the last release, attributed with `git blame`
+#[derive(serde::Serialize)]
+#[serde(rename_all = "snake_case")]
+pub struct Breaking {
+    pub manifest_path: std::path::PathBuf,
+    pub against: Source,
+    pub changes: Vec<BreakingChange>,
+}
 
-    let root = api.paths.get(root_id).unwrap();
-    let _ = writeln!(writer, "# `{}`", root.path);
-    let _ = writeln!(writer);
+#[derive(serde::Serialize)]
+#[serde(rename_all = "snake_case")]
+pub struct BreakingChange {
+    pub path: String,
+    pub diff: crate_api::diff::Diff,
+    pub span: Option<crate_api::Span>,
+    /// The most recent commit since the release to touch the item
+    pub introduced_by: Option<Attribution>,
+}
 
-    let mut paths: std::collections::VecDeque<crate_api::PathId> = Default::default();
-    paths.extend(root.children.iter().copied().rev());
-    while let Some(next_path_id) = paths.pop_back() {
-        let next_path = api.paths.get(next_path_id).unwrap();
+/// Whether the version is bumped enough for the API changes since the last release
+#[derive(serde::Serialize)]
+#[serde(rename_all = "snake_case")]
+pub struct Release {
+    pub manifest_path: std::path::PathBuf,
+    pub against: Source,
+    pub before: cargo_metadata::Version,
+    pub after: cargo_metadata::Version,
+    /// The bump the API changes require
+    pub required: crate_api::diff::rules::Bump,
+    /// The largest bump `before` to `after` allows, or `None` if `after` is older
+    pub bumped: Option<crate_api::diff::rules::Bump>,
+    /// Changes needing more than `bumped`
+    pub changes: Vec<ReleaseChange>,
+}
 
-        match next_path.kind {
-            crate_api::PathKind::Module => {
-                for _ in 0..(next_path.path.matches("::").count() + 1) {
-                    let _ = write!(writer, "#");
-                }
-                let _ = writeln!(writer, " `{}`", next_path.path);
-                let _ = writeln!(writer);
+#[derive(serde::Serialize)]
+#[serde(rename_all = "snake_case")]
+pub struct ReleaseChange {
+    pub path: String,
+    pub bump: crate_api::diff::rules::Bump,
+    pub diff: crate_api::diff::Diff,
+    pub reference: &'static str,
+}
 
-                if let Some(crate_id) = next_path.crate_id {
-                    let crate_ = api.crates.get(crate_id).unwrap();
-                    let _ = writeln!(writer, "*from crate `{}`*", crate_.name);
-                    let _ = writeln!(writer);
+impl Release {
+    pub fn new(
+        pkg: &cargo_metadata::Package,
+        old_pkg: &cargo_metadata::Package,
+        against: Source,
+        before: &crate_api::Api,
+        after: &crate_api::Api,
+        diffs: Vec<crate_api::diff::Diff>,
+    ) -> Self {
+        let required = crate_api::diff::rules::required_bump(&diffs);
+        // An unchanged version is fine for changes users can't observe
+        let bumped = if pkg.version == old_pkg.version {
+            Some(crate_api::diff::rules::Bump::Patch)
+        } else {
+            crate_api::diff::rules::bump_between(&old_pkg.version, &pkg.version)
+        };
+        let changes = diffs
+            .into_iter()
+            .filter_map(|diff| {
+                let bump = crate_api::diff::rules::classify(&diff);
+                if Some(bump) <= bumped {
+                    return None;
                 }
+                let (api, location) = diff_location(before, after, &diff);
+                Some(ReleaseChange {
+                    path: location_name(api, location).into_owned(),
+                    bump,
+                    reference: crate_api::diff::rules::rule(diff.id).reference,
+                    diff,
+                })
+            })
+            .collect();
+        Self {
+            manifest_path: pkg.manifest_path.clone().into_std_path_buf(),
+            against,
+            before: old_pkg.version.clone(),
+            after: pkg.version.clone(),
+            required,
+            bumped,
+            changes,
+        }
+    }
 
-                let (mut modules, mut other): (Vec<_>, Vec<_>) = next_path
-                    .children
-                    .iter()
-                    .copied()
-                    .partition(|next_path_id| {
-                        let next_path = api.paths.get(*next_path_id).unwrap();
-                        next_path.kind == crate_api::PathKind::Module
-                    });
-                modules.sort_unstable_by_key(|next_path_id| {
-                    let next_path = api.paths.get(*next_path_id).unwrap();
-                    (next_path.kind, next_path.path.as_str())
-                });
-                other.sort_unstable_by_key(|next_path_id| {
-                    let next_path = api.paths.get(*next_path_id).unwrap();
-                    (next_path.kind, next_path.path.as_str())
-                });
-                paths.extend(modules.into_iter().rev());
-                paths.extend(other.into_iter().rev());
-            }
-            _ => {
-                let _ = writeln!(writer, "**`{}`** *({:?})*", next_path.path, next_path.kind);
-                let _ = writeln!(writer);
+    pub fn is_sufficient(&self) -> bool {
+        Some(self.required) <= self.bumped
+    }
+}
 
-                if let Some(crate_id) = next_path.crate_id {
-                    let crate_ = api.crates.get(crate_id).unwrap();
-                    let _ = writeln!(writer, "*from crate `{}`*", crate_.name);
-                    let _ = writeln!(writer);
-                }
+#[derive(Clone, Debug, serde::Serialize)]
+#[serde(rename_all = "snake_case")]
+pub struct Attribution {
+    pub commit: String,
+    pub author: String,
+    pub email: Option<String>,
+    pub summary: Option<String>,
+}
 
-                let mut other = next_path.children.clone();
-                other.sort_unstable_by_key(|next_path_id| {
-                    let next_path = api.paths.get(*next_path_id).unwrap();
-                    (next_path.kind, next_path.path.as_str())
-                });
-                paths.extend(other.into_iter().rev());
+#[derive(serde::Serialize)]
+#[serde(rename_all = "snake_case")]
+pub struct Bisect {
+    pub manifest_path: std::path::PathBuf,
+    pub good: String,
+    pub bad: String,
+    /// Diff id searched for, or any breaking change
+    pub change: Option<String>,
+    pub first_bad: Attribution,
+    /// Number of commits whose API was checked
+    pub tested: usize,
+}
+
+#[derive(serde::Serialize)]
+#[serde(rename_all = "snake_case")]
+pub struct FeaturePowerset {
+    pub combinations: usize,
+    /// Feature combinations, grouped by those producing identical APIs
+    pub unique: Vec<Vec<Vec<String>>>,
+    /// Paths missing from some combinations
+    pub conditional: Vec<ConditionalPath>,
+}
+
+#[derive(serde::Serialize)]
+#[serde(rename_all = "snake_case")]
+pub struct ConditionalPath {
+    pub path: String,
+    pub kind: crate_api::PathKind,
+    /// The smallest feature combinations that include this path
+    pub requires: Vec<Vec<String>>,
+}
+
+impl FeaturePowerset {
+    pub fn new(results: &[(Vec<String>, crate::freeze::Surface)]) -> Self {
+        let mut unique: Vec<(&crate::freeze::Surface, Vec<Vec<String>>)> = Vec::new();
+        for (features, surface) in results {
+            match unique.iter_mut().find(|(s, _)| *s == surface) {
+                Some((_, combinations)) => combinations.push(features.clone()),
+                None => unique.push((surface, vec![features.clone()])),
             }
         }
-    }
 
-    if !api.features.is_empty() {
-        let _ = writeln!(writer, "## Feature Flags");
-        let _ = writeln!(writer);
-        for details in api.features.values() {
-            match details {
-                crate_api::AnyFeature::Feature(feature) => {
-                    let _ = writeln!(writer, "`{}`", feature.name);
-                    for dep in &feature.dependencies {
-                        let _ = writeln!(writer, "- `{}`", dep);
-                    }
-                    let _ = writeln!(writer);
-                }
-                crate_api::AnyFeature::OptionalDependency(dep) => {
-                    if let Some(package) = dep.package.as_deref() {
-                        let _ = writeln!(writer, "`{}` *(dependency `{}`)*", dep.name, package);
-                    } else {
-                        let _ = writeln!(writer, "`{}` *(dependency)*", dep.name);
-                    }
-                    let _ = writeln!(writer);
-                }
+        let mut present: std::collections::BTreeMap<
+            &(String, crate_api::PathKind),
+            Vec<&Vec<String>>,
+        > = std::collections::BTreeMap::new();
+        for (features, surface) in results {
+            for key in surface.keys() {
+                present.entry(key).or_default().push(features);
             }
         }
-    }
-
-    if !api.crates.is_empty() {
-        let _ = writeln!(writer, "## Public Dependencies");
-        let _ = writeln!(writer);
+        let conditional = present
+            .into_iter()
+            .filter(|(_, combinations)| combinations.len() < results.len())
+            .map(|((path, kind), combinations)| {
+                let requires = combinations
+                    .iter()
+                    .filter(|combination| {
+                        !combinations.iter().any(|other| {
+                            other.len() < combination.len()
+                                && other.iter().all(|f| combination.contains(f))
+                        })
+                    })
+                    .map(|combination| (*combination).clone())
+                    .collect();
+                ConditionalPath {
+                    path: path.clone(),
+                    kind: *kind,
+                    requires,
+                }
+            })
+            .collect();
 
-        for (_, crate_) in api.crates.iter() {
-            let _ = writeln!(
-                writer,
-                "- `{}` (version {})",
-                crate_.name,
-                crate_
-                    .version
-                    .as_ref()
-                    .map(|v| v.to_string())
-                    .unwrap_or_else(|| "unknown".into())
-            );
+        Self {
+            combinations: results.len(),
+            unique: unique
+                .into_iter()
+                .map(|(_, combinations)| combinations)
+                .collect(),
+            conditional,
         }
-        let _ = writeln!(writer);
     }
+}
 
-    Ok(())
+/// Enabling a feature must only add to the API, see cargo's feature unification
+#[derive(serde::Serialize)]
+#[serde(rename_all = "snake_case")]
+pub struct FeatureAdditivity {
+    /// Number of feature additions checked
+    pub checked: usize,
+    pub violations: Vec<AdditivityViolation>,
 }
 
-pub fn render_diff_markdown(
-    writer: &mut dyn std::io::Write,
-    before: &crate_api::Api,
-    after: &crate_api::Api,
-    diffs: &[crate_api::diff::Diff],
-) -> Result<(), eyre::Report> {
-    let mut diffs = diffs.to_vec();
-    diffs.sort_by_key(|diff| (diff.severity, diff.id.category, diff.id.name));
+#[derive(serde::Serialize)]
+#[serde(rename_all = "snake_case")]
+pub struct AdditivityViolation {
+    pub feature: String,
+    /// Features already enabled
+    pub base: Vec<String>,
+    pub path: String,
+    pub kind: crate_api::PathKind,
+    pub change: AdditivityChange,
+}
 
-    let mut last_severity = crate_api::diff::Severity::Allow;
-    let mut last_category = None;
-    for diff in diffs {
-        if diff.severity != last_severity {
-            match diff.severity {
-                crate_api::diff::Severity::Allow => unreachable!(),
-                crate_api::diff::Severity::Report => {
-                    let _ = writeln!(writer, "## Changes");
-                    let _ = writeln!(writer);
+#[derive(Copy, Clone, Debug, PartialEq, Eq, serde::Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AdditivityChange {
+    Removed,
+    Changed,
+}
+
+impl FeatureAdditivity {
+    pub fn new(results: &[(Vec<String>, crate::freeze::Surface)]) -> Self {
+        let mut checked = 0;
+        let mut violations = Vec::new();
+        for (base, before) in results {
+            for (features, after) in results {
+                // Only compare against `base` plus one more feature
+                if features.len() != base.len() + 1 || !base.iter().all(|f| features.contains(f)) {
+                    continue;
                 }
-                crate_api::diff::Severity::Warn => {
-                    let _ = writeln!(writer, "## Breaking Changes");
-                    let _ = writeln!(writer);
+                let feature = features.iter().find(|f| !base.contains(f)).unwrap();
+                checked += 1;
+                for (key, before_item) in before {
+                    let change = match after.get(key) {
+                        None => AdditivityChange::Removed,
+                        Some(after_item) if after_item != before_item => AdditivityChange::Changed,
+                        Some(_) => continue,
+                    };
+                    violations.push(AdditivityViolation {
+                        feature: feature.clone(),
+                        base: base.clone(),
+                        path: key.0.clone(),
+                        kind: key.1,
+                        change,
+                    });
                 }
             }
-            last_severity = diff.severity;
         }
-        if Some(diff.id.category) != last_category {
-            match diff.id.category {
-                crate_api::diff::Category::Unknown => {}
-                crate_api::diff::Category::Added => {
-                    let _ = writeln!(writer, "**Added**");
-                }
-                crate_api::diff::Category::Removed => {
-                    let _ = writeln!(writer, "**Removed**");
-                }
-                crate_api::diff::Category::Changed => {
-                    let _ = writeln!(writer, "**Changed**");
-                }
-            }
-            last_category = Some(diff.id.category);
+        Self {
+            checked,
+            violations,
         }
+    }
+}
 
-        match diff.id {
-            crate_api::diff::DEPENDENCY_REQUIREMENT => {
-                let before_crate = before
-                    .crates
-                    .get(diff.before.unwrap().crate_id.unwrap())
-                    .unwrap();
-                let after_crate = after
-                    .crates
-                    .get(diff.after.unwrap().crate_id.unwrap())
-                    .unwrap();
-                let _ = writeln!(
-                    writer,
-                    "- `{}` (public dependency): changed version requirement from {} to {}",
-                    after_crate.name,
-                    before_crate.version.as_ref().unwrap(),
-                    after_crate.version.as_ref().unwrap()
-                );
-            }
-            _ => {
-                let name = diff
-                    .after
-                    .map(|loc| location_name(after, loc))
-                    .or_else(|| diff.before.map(|loc| location_name(before, loc)))
-                    .expect("at least before or after exists");
-                let _ = writeln!(writer, "- `{}`: {}", name, diff.id.explanation);
+/// How well one crate can stand in for another, like a fork for its upstream
+#[derive(serde::Serialize)]
+#[serde(rename_all = "snake_case")]
+pub struct Compatibility {
+    pub name: String,
+    pub other: String,
+    pub shared: Vec<ComparedPath>,
+    /// In `other` but not `name`
+    pub missing: Vec<ComparedPath>,
+    /// In `name` but not `other`
+    pub extra: Vec<ComparedPath>,
+    pub differing: Vec<ComparedPath>,
+}
+
+#[derive(serde::Serialize)]
+#[serde(rename_all = "snake_case")]
+pub struct ComparedPath {
+    /// Relative to the crate root, as `crate::...`
+    pub path: String,
+    pub kind: crate_api::PathKind,
+}
+
+impl Compatibility {
+    pub fn new(
+        name: &str,
+        surface: &crate::freeze::Surface,
+        other: &str,
+        other_surface: &crate::freeze::Surface,
+    ) -> Self {
+        let compared = |(path, kind): &(String, crate_api::PathKind)| ComparedPath {
+            path: path.clone(),
+            kind: *kind,
+        };
+        let mut shared = Vec::new();
+        let mut extra = Vec::new();
+        let mut differing = Vec::new();
+        for (key, item) in surface {
+            match other_surface.get(key) {
+                Some(other_item) if other_item == item => shared.push(compared(key)),
+                Some(_) => differing.push(compared(key)),
+                None => extra.push(compared(key)),
             }
         }
+        let missing = other_surface
+            .keys()
+            .filter(|key| !surface.contains_key(*key))
+            .map(compared)
+            .collect();
+        Self {
+            name: name.to_owned(),
+            other: other.to_owned(),
+            shared,
+            missing,
+            extra,
+            differing,
+        }
     }
+}
 
-    Ok(())
+/// Outline of a migration guide for maintainers to fill in
+#[derive(serde::Serialize)]
+#[serde(rename_all = "snake_case")]
+pub struct UpgradeGuide {
+    pub name: String,
+    pub against: Source,
+    pub version: Option<String>,
+    pub removed: Vec<RemovedItem>,
+    pub changed: Vec<ChangedItem>,
+    pub dependencies: Vec<String>,
 }
 
-fn location_name(api: &crate_api::Api, location: crate_api::diff::Location) -> &str {
-    if let Some(path_id) = location.path_id {
-        api.paths.get(path_id).unwrap().path.as_str()
-    } else if let Some(item_id) = location.item_id {
-        api.items.get(item_id).unwrap().name.as_deref().unwrap()
-    } else if let Some(crate_id) = location.crate_id {
-        api.crates.get(crate_id).unwrap().name.as_str()
-    } else {
-        unimplemented!("{:?} had no location", location)
+#[derive(serde::Serialize)]
+#[serde(rename_all = "snake_case")]
+pub struct RemovedItem {
+    pub path: String,
+    pub kind: crate_api::PathKind,
+    pub replacement: Option<String>,
+}
+
+#[derive(serde::Serialize)]
+#[serde(rename_all = "snake_case")]
+pub struct ChangedItem {
+    pub path: String,
+    pub kind: crate_api::PathKind,
+    pub before: Option<String>,
+    pub after: Option<String>,
+}
+
+impl UpgradeGuide {
+    pub fn new(
+        pkg: &cargo_metadata::Package,
+        against: Source,
+        before: &crate_api::Api,
+        after: &crate_api::Api,
+        diffs: &[crate_api::diff::Diff],
+        excluded: &std::collections::HashSet<String>,
+    ) -> Self {
+        let mut before_surface = crate::freeze::surface(before);
+        before_surface.retain(|(path, _), _| !excluded.contains(path));
+        let mut after_surface = crate::freeze::surface(after);
+        after_surface.retain(|(path, _), _| !excluded.contains(path));
+        let before_paths = paths_by_key(before);
+        let after_paths = paths_by_key(after);
+
+        let renames: std::collections::HashMap<_, _> = diffs
+            .iter()
+            .filter(|diff| {
+                diff.id == crate_api::diff::ITEM_RENAMED || diff.id == crate_api::diff::PATH_REMOVED
+            })
+            .map(|diff| {
+                (
+                    location_name(before, diff.before.unwrap()),
+                    location_name(after, diff.after.unwrap()),
+                )
+            })
+            .collect();
+        let removed = before_surface
+            .keys()
+            .filter(|key| !after_surface.contains_key(*key))
+            .map(|(path, kind)| RemovedItem {
+                path: path.clone(),
+                kind: *kind,
+                replacement: renames
+                    .get(path.as_str())
+                    .map(|path| path.clone().into_owned()),
+            })
+            .collect();
+        let changed = before_surface
+            .iter()
+            .filter_map(|(key, before_item)| {
+                let after_item = after_surface.get(key)?;
+                (before_item != after_item).then(|| ChangedItem {
+                    path: key.0.clone(),
+                    kind: key.1,
+                    before: declaration(before, before_paths[key]),
+                    after: declaration(after, after_paths[key]),
+                })
+            })
+            .collect();
+        let dependencies = diffs
+            .iter()
+            .filter(|diff| diff.severity == crate_api::diff::Severity::Warn)
+            // Covered by `removed`
+            .filter(|diff| {
+                diff.id != crate_api::diff::ITEM_RENAMED
+                    && diff.id != crate_api::diff::ITEM_REMOVED
+                    && diff.id != crate_api::diff::PATH_REMOVED
+                    && diff.id != crate_api::diff::DEPRECATED_ITEM_REMOVED
+            })
+            .map(|diff| {
+                let (api, location) = diff_location(before, after, diff);
+                format!(
+                    "`{}`: {}",
+                    location_name(api, location),
+                    diff.id.explanation
+                )
+            })
+            .collect();
+
+        Self {
+            name: pkg.name.clone(),
+            against,
+            version: Some(pkg.version.to_string()),
+            removed,
+            changed,
+            dependencies,
+        }
     }
 }
+
+fn paths_by_key(
+    api: &crate_api::Api,
+) -> std::collections::BTreeMap<(String, crate_api::PathKind), &crate_api::Path> {
+    api.paths
+        .iter()
+        .map(|(_, path)| ((path.path.clone(), path.kind), path))
+        .collect()
+}
+
+/// Render enough of an item to show how it changed
+fn declaration(api: &crate_api::Api, path: &crate_api::Path) -> Option<String> {
+    let item = api.items.get(path.item_id?)?;
+    let name = item.name.as_deref().unwrap_or_default();
+    if let Some(signature) = &item.signature {
+        Some(fn_declaration(item, signature))
+    } else if path.kind == crate_api::PathKind::Macro && !item.matchers.is_empty() {
+        let rules: Vec<_> = item
+            .matchers
+            .iter()
+            .map(|matcher| format!("{} => {{ ... }};", matcher))
+            .collect();
+        Some(format!("macro_rules! {} {{ {} }}", name, rules.join(" ")))
+    } else if let Some(usage) = macro_usage(path.kind, item) {
+        Some(usage)
+    } else if let (crate_api::PathKind::Typedef, Some(type_)) = (path.kind, &item.type_) {
+        Some(format!("type {}{} = {}", name, item.generics, type_))
+    } else if let Some(type_) = &item.type_ {
+        let keyword = match path.kind {
+            crate_api::PathKind::Static if item.is_mutable => "static mut",
+            crate_api::PathKind::Static => "static",
+            _ => "const",
+        };
+        let value = item
+            .value
+            .as_deref()
+            .map(|value| format!(" = {}", value))
+            .unwrap_or_default();
+        Some(format!("{} {}: {}{}", keyword, name, type_, value))
+    } else if item.variant_kind.is_some() {
+        Some(variant_declaration(item))
+    } else if !item.fields.is_empty() {
+        let fields: Vec<_> = item.fields.iter().map(field_declaration).collect();
+        Some(format!(
+            "{} {}{} {{ {} }}",
+            path.kind,
+            name,
+            item.generics,
+            fields.join(", ")
+        ))
+    } else {
+        None
+    }
+}
+
+impl std::fmt::Display for Source {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Git(rev) => rev.fmt(f),
+            Self::Path(path) => path.display().fmt(f),
+            Self::Registry(pkg) => pkg.fmt(f),
+        }
+    }
+}
+
+/// When each path was added, deprecated, and removed across releases
+#[derive(serde::Serialize)]
+#[serde(rename_all = "snake_case")]
+pub struct History {
+    pub name: String,
+    pub versions: Vec<String>,
+    pub paths: Vec<PathHistory>,
+}
+
+#[derive(Clone, serde::Serialize)]
+#[serde(rename_all = "snake_case")]
+pub struct PathHistory {
+    pub path: String,
+    pub kind: crate_api::PathKind,
+    pub added: String,
+    pub deprecated: Option<String>,
+    pub removed: Option<String>,
+}
+
+impl History {
+    /// `releases` must be in ascending version order
+    pub fn new(name: &str, releases: &[(String, crate_api::Api)]) -> Self {
+        let mut paths: std::collections::BTreeMap<(String, crate_api::PathKind), PathHistory> =
+            Default::default();
+        for (version, api) in releases {
+            let mut present = std::collections::BTreeSet::new();
+            for (_, path) in api.paths.iter() {
+                let key = (path.path.clone(), path.kind);
+                present.insert(key.clone());
+                let history = paths.entry(key).or_insert_with(|| PathHistory {
+                    path: path.path.clone(),
+                    kind: path.kind,
+                    added: version.clone(),
+                    deprecated: None,
+                    removed: None,
+                });
+                if history.removed.is_some() {
+                    // Re-added, so the earlier history no longer applies
+                    history.added = version.clone();
+                    history.deprecated = None;
+                    history.removed = None;
+                }
+                let is_deprecated = path
+                    .item_id
+                    .and_then(|item_id| api.items.get(item_id))
+                    .map(|item| item.deprecated.is_some())
+                    .unwrap_or(false);
+                if is_deprecated && history.deprecated.is_none() {
+                    history.deprecated = Some(version.clone());
+                }
+            }
+            for (key, history) in paths.iter_mut() {
+                if !present.contains(key) && history.removed.is_none() {
+                    history.removed = Some(version.clone());
+                }
+            }
+        }
+
+        Self {
+            name: name.to_owned(),
+            versions: releases
+                .iter()
+                .map(|(version, _)| version.clone())
+                .collect(),
+            paths: paths.into_values().collect(),
+        }
+    }
+}
+
+/// The release each current path first appeared in
+#[derive(serde::Serialize)]
+#[serde(rename_all = "snake_case")]
+pub struct Since {
+    pub paths: Vec<PathSince>,
+}
+
+#[derive(serde::Serialize)]
+#[serde(rename_all = "snake_case")]
+pub struct PathSince {
+    pub path: String,
+    pub kind: crate_api::PathKind,
+    /// `None` when not yet released
+    pub since: Option<String>,
+}
+
+impl Since {
+    pub fn new(history: &History, api: &crate_api::Api) -> Self {
+        let added: std::collections::BTreeMap<_, _> = history
+            .paths
+            .iter()
+            .filter(|path| path.removed.is_none())
+            .map(|path| ((path.path.as_str(), path.kind), path.added.as_str()))
+            .collect();
+        let mut paths: Vec<_> = api
+            .paths
+            .iter()
+            .filter(|(path_id, _)| Some(*path_id) != api.root_id)
+            .map(|(_, path)| PathSince {
+                path: path.path.clone(),
+                kind: path.kind,
+                since: added
+                    .get(&(path.path.as_str(), path.kind))
+                    .map(|version| (*version).to_owned()),
+            })
+            .collect();
+        paths.sort_unstable_by(|a, b| (a.path.as_str(), a.kind).cmp(&(b.path.as_str(), b.kind)));
+        Self { paths }
+    }
+}
+
+/// The API of each workspace member, from `--api --workspace`
+#[derive(serde::Serialize)]
+#[serde(rename_all = "snake_case")]
+pub struct WorkspaceApi {
+    pub packages: Vec<PackageApi>,
+}
+
+#[derive(serde::Serialize)]
+#[serde(rename_all = "snake_case")]
+pub struct PackageApi {
+    pub name: String,
+    pub version: String,
+    pub manifest_path: std::path::PathBuf,
+    pub api: crate_api::Api,
+}
+
+/// The API of each of a package's crates, from `--api --all-targets`
+#[derive(serde::Serialize)]
+#[serde(rename_all = "snake_case")]
+pub struct TargetApis {
+    pub manifest_path: std::path::PathBuf,
+    pub targets: Vec<TargetApi>,
+}
+
+#[derive(serde::Serialize)]
+#[serde(rename_all = "snake_case")]
+pub struct TargetApi {
+    pub target: crate_api::rustdoc::DocTarget,
+    pub api: crate_api::Api,
+}
+
+/// Several snapshots combined into one, with where each path came from
+#[derive(serde::Serialize)]
+#[serde(rename_all = "snake_case")]
+pub struct Merged {
+    pub sources: Vec<std::path::PathBuf>,
+    pub api: crate_api::Api,
+    pub provenance: Vec<Provenance>,
+}
+
+#[derive(serde::Serialize)]
+#[serde(rename_all = "snake_case")]
+pub struct Provenance {
+    pub path: String,
+    pub kind: crate_api::PathKind,
+    /// Indexes into `sources`
+    pub sources: Vec<usize>,
+}
+
+impl Merged {
+    /// When the snapshots have different roots, they are put under a new `name` module
+    pub fn new(name: &str, snapshots: &[(std::path::PathBuf, crate_api::Api)]) -> Self {
+        let mut api = crate_api::Api::new();
+        let mut sources: std::collections::HashMap<crate_api::PathId, Vec<usize>> =
+            Default::default();
+        let mut roots = Vec::new();
+        for (i, (_, snapshot)) in snapshots.iter().enumerate() {
+            let path_ids = api.merge(snapshot);
+            if let Some(root_id) = snapshot.root_id {
+                let root_id = path_ids[&root_id];
+                if !roots.contains(&root_id) {
+                    roots.push(root_id);
+                }
+            }
+            for path_id in path_ids.into_values() {
+                let path_sources = sources.entry(path_id).or_default();
+                if !path_sources.contains(&i) {
+                    path_sources.push(i);
+                }
+            }
+        }
+        if 1 < roots.len() {
+            let mut root = crate_api::Path::new(crate_api::PathKind::Module, name);
+            root.children = roots;
+            api.root_id = Some(api.paths.push(root));
+        }
+
+        let mut provenance: Vec<_> = sources
+            .into_iter()
+            .map(|(path_id, sources)| {
+                let path = api.paths.get(path_id).unwrap();
+                Provenance {
+                    path: path.path.clone(),
+                    kind: path.kind,
+                    sources,
+                }
+            })
+            .collect();
+        provenance
+            .sort_unstable_by(|a, b| (a.path.as_str(), a.kind).cmp(&(b.path.as_str(), b.kind)));
+        Self {
+            sources: snapshots.iter().map(|(path, _)| path.clone()).collect(),
+            api,
+            provenance,
+        }
+    }
+}
+
+/// What each of a package's features does, for a "Feature flags" section
+#[derive(serde::Serialize)]
+#[serde(rename_all = "snake_case")]
+pub struct FeatureDocs {
+    pub name: String,
+    pub features: Vec<FeatureDoc>,
+}
+
+#[derive(serde::Serialize)]
+#[serde(rename_all = "snake_case")]
+pub struct FeatureDoc {
+    pub name: String,
+    pub is_default: bool,
+    /// Other features this turns on
+    pub enables: Vec<String>,
+    /// For optional dependencies, the package being depended on
+    pub dependency: Option<String>,
+    /// Paths only available with this feature
+    pub gates: Vec<String>,
+}
+
+impl FeatureDocs {
+    pub fn new(pkg: &cargo_metadata::Package, api: &crate_api::Api) -> Self {
+        let defaults: &[String] = match api.features.get("default") {
+            Some(crate_api::AnyFeature::Feature(default)) => &default.dependencies,
+            _ => &[],
+        };
+
+        let mut gates: std::collections::BTreeMap<&str, std::collections::BTreeSet<&str>> =
+            Default::default();
+        for (_, path) in api.paths.iter() {
+            // Re-exports are gated by their target's feature, so only list the declaration
+            if path.kind == crate_api::PathKind::Import {
+                continue;
+            }
+            let item = match path.item_id.and_then(|item_id| api.items.get(item_id)) {
+                Some(item) => item,
+                None => continue,
+            };
+            for feature in &item.features {
+                gates.entry(feature).or_default().insert(&path.path);
+            }
+        }
+
+        let features = api
+            .features
+            .iter()
+            .filter(|(name, _)| *name != "default")
+            .map(|(name, feature)| {
+                let (enables, dependency) = match feature {
+                    crate_api::AnyFeature::Feature(feature) => (feature.dependencies.clone(), None),
+                    crate_api::AnyFeature::OptionalDependency(dependency) => (
+                        Vec::new(),
+                        Some(dependency.package.clone().unwrap_or_else(|| name.clone())),
+                    ),
+                };
+                FeatureDoc {
+                    name: name.clone(),
+                    is_default: defaults.contains(name),
+                    enables,
+                    dependency,
+                    gates: gates
+                        .get(name.as_str())
+                        .map(|paths| paths.iter().map(|path| (*path).to_owned()).collect())
+                        .unwrap_or_default(),
+                }
+            })
+            .collect();
+
+        Self {
+            name: pkg.name.clone(),
+            features,
+        }
+    }
+}
+
+/// A downloaded crate's embedded snapshot checked against its source
+#[derive(serde::Serialize)]
+#[serde(rename_all = "snake_case")]
+pub struct Verification {
+    pub name: String,
+    pub version: cargo_metadata::Version,
+    pub status: VerificationStatus,
+    /// Paths the snapshot misrepresents
+    pub mismatches: Vec<String>,
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq, serde::Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum VerificationStatus {
+    Verified,
+    Mismatch,
+    NotEmbedded,
+}
+
+impl Verification {
+    pub fn new(
+        pkg: &cargo_metadata::Package,
+        embedded: Option<&crate_api::Api>,
+        api: &crate_api::Api,
+    ) -> Self {
+        let mismatches = embedded
+            .map(|embedded| crate::embed::mismatches(embedded, api))
+            .unwrap_or_default();
+        let status = match embedded {
+            None => VerificationStatus::NotEmbedded,
+            Some(_) if mismatches.is_empty() => VerificationStatus::Verified,
+            Some(_) => VerificationStatus::Mismatch,
+        };
+        Self {
+            name: pkg.name.clone(),
+            version: pkg.version.clone(),
+            status,
+            mismatches,
+        }
+    }
+}
+
+#[derive(serde::Serialize)]
+#[serde(rename_all = "snake_case")]
+pub struct Metrics {
+    pub timestamp: String,
+    pub name: String,
+    pub version: cargo_metadata::Version,
+    pub paths: usize,
+    pub paths_by_kind: std::collections::BTreeMap<crate_api::PathKind, usize>,
+    pub items: usize,
+    pub documented_items: usize,
+    pub doc_coverage: f64,
+    pub public_dependencies: usize,
+    pub features: usize,
+}
+
+impl Metrics {
+    pub fn new(
+        pkg: &cargo_metadata::Package,
+        api: &crate_api::Api,
+        now: std::time::SystemTime,
+    ) -> Self {
+        let mut paths_by_kind = std::collections::BTreeMap::new();
+        for (_, path) in api.paths.iter() {
+            *paths_by_kind.entry(path.kind).or_insert(0) += 1;
+        }
+
+        let items = api.items.len();
+        let documented_items = api
+            .items
+            .iter()
+            .filter(|(_, item)| item.docs.is_some())
+            .count();
+        let doc_coverage = if items == 0 {
+            1.0
+        } else {
+            documented_items as f64 / items as f64
+        };
+
+        Self {
+            timestamp: humantime::format_rfc3339_seconds(now).to_string(),
+            name: pkg.name.clone(),
+            version: pkg.version.clone(),
+            paths: api.paths.len(),
+            paths_by_kind,
+            items,
+            documented_items,
+            doc_coverage,
+            public_dependencies: api.crates.len(),
+            features: api.features.len(),
+        }
+    }
+}
+
+#[derive(serde::Serialize)]
+#[serde(rename_all = "snake_case")]
+pub struct UnsafeReport {
+    pub modules: Vec<UnsafeModule>,
+}
+
+#[derive(serde::Serialize)]
+#[serde(rename_all = "snake_case")]
+pub struct UnsafeModule {
+    pub path: String,
+    pub entries: Vec<UnsafeEntry>,
+}
+
+#[derive(serde::Serialize)]
+#[serde(rename_all = "snake_case")]
+pub struct UnsafeEntry {
+    pub path: String,
+    pub kind: crate_api::PathKind,
+    pub hazards: Vec<Hazard>,
+    pub span: Option<crate_api::Span>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, serde::Serialize)]
+#[serde(rename_all = "snake_case")]
+#[serde(tag = "kind")]
+pub enum Hazard {
+    UnsafeFn,
+    UnsafeTrait,
+    ExternFn { abi: String },
+    RawPointerField { field: String },
+}
+
+impl std::fmt::Display for Hazard {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::UnsafeFn => "unsafe fn".fmt(f),
+            Self::UnsafeTrait => "unsafe trait".fmt(f),
+            Self::ExternFn { abi } => write!(f, "extern \"{}\" fn", abi),
+            Self::RawPointerField { field } => write!(f, "raw pointer in field `{}`", field),
+        }
+    }
+}
+
+impl UnsafeReport {
+    pub fn new(api: &crate_api::Api) -> Self {
+        let mut modules: std::collections::BTreeMap<String, Vec<UnsafeEntry>> = Default::default();
+
+        let root_id = match api.root_id {
+            Some(root_id) => root_id,
+            None => {
+                return Self {
+                    modules: Vec::new(),
+                }
+            }
+        };
+        let mut visited = std::collections::HashSet::new();
+        let mut pending = vec![(root_id, api.paths.get(root_id).unwrap().path.clone())];
+        while let Some((path_id, module)) = pending.pop() {
+            if !visited.insert(path_id) {
+                continue;
+            }
+            let path = api.paths.get(path_id).unwrap();
+            let module = if path.kind == crate_api::PathKind::Module {
+                path.path.clone()
+            } else {
+                module
+            };
+            pending.extend(
+                path.children
+                    .iter()
+                    .rev()
+                    .map(|child_id| (*child_id, module.clone())),
+            );
+
+            // Re-exports share the item of what they point to which is reported at its definition
+            if path.kind == crate_api::PathKind::Import {
+                continue;
+            }
+            let item = match path.item_id {
+                Some(item_id) => api.items.get(item_id).unwrap(),
+                None => continue,
+            };
+            let hazards = hazards(path.kind, item);
+            if !hazards.is_empty() {
+                modules.entry(module).or_default().push(UnsafeEntry {
+                    path: path.path.clone(),
+                    kind: path.kind,
+                    hazards,
+                    span: path.span.clone(),
+                });
+            }
+        }
+
+        let modules = modules
+            .into_iter()
+            .map(|(path, mut entries)| {
+                entries.sort_unstable_by(|a, b| a.path.cmp(&b.path));
+                UnsafeModule { path, entries }
+            })
+            .collect();
+        Self { modules }
+    }
+}
+
+fn hazards(kind: crate_api::PathKind, item: &crate_api::Item) -> Vec<Hazard> {
+    let mut hazards = Vec::new();
+    match kind {
+        crate_api::PathKind::Function | crate_api::PathKind::Method => {
+            if item.is_unsafe {
+                hazards.push(Hazard::UnsafeFn);
+            }
+            if let Some(abi) = item.abi.as_ref() {
+                hazards.push(Hazard::ExternFn { abi: abi.clone() });
+            }
+        }
+        crate_api::PathKind::Trait if item.is_unsafe => {
+            hazards.push(Hazard::UnsafeTrait);
+        }
+        crate_api::PathKind::Struct | crate_api::PathKind::Union => {
+            for field in &item.fields {
+                if field.type_.contains("*const ") || field.type_.contains("*mut ") {
+                    hazards.push(Hazard::RawPointerField {
+                        field: field.name.clone(),
+                    });
+                }
+            }
+        }
+        _ => {}
+    }
+    hazards
+}
+
+#[derive(serde::Serialize)]
+#[serde(rename_all = "snake_case")]
+pub struct FfiReport {
+    pub symbols: Vec<FfiSymbol>,
+    /// Types with a `#[repr]`, whose layout FFI may rely on
+    pub layouts: Vec<FfiLayout>,
+}
+
+#[derive(serde::Serialize)]
+#[serde(rename_all = "snake_case")]
+pub struct FfiSymbol {
+    /// Unmangled, C-linkable name, if exported
+    pub symbol: Option<String>,
+    pub path: String,
+    pub kind: crate_api::PathKind,
+    pub abi: Option<String>,
+    pub declaration: String,
+    pub span: Option<crate_api::Span>,
+}
+
+#[derive(serde::Serialize)]
+#[serde(rename_all = "snake_case")]
+pub struct FfiLayout {
+    pub path: String,
+    pub kind: crate_api::PathKind,
+    /// Hints from `#[repr(...)]`, like `["C", "packed(2)"]`
+    pub repr: Vec<String>,
+    pub span: Option<crate_api::Span>,
+}
+
+impl FfiReport {
+    pub fn new(api: &crate_api::Api) -> Self {
+        let mut symbols: Vec<_> = api
+            .paths
+            .iter()
+            .filter(|(_, path)| path.kind != crate_api::PathKind::Import)
+            .filter_map(|(_, path)| {
+                let item = api.items.get(path.item_id?).unwrap();
+                if item.symbol.is_none() && item.abi.is_none() {
+                    return None;
+                }
+
+                let declaration = match item.signature.as_ref() {
+                    Some(signature) => fn_declaration(item, signature),
+                    None => format!("{} {}", path.kind, item.name.as_deref().unwrap_or_default()),
+                };
+
+                Some(FfiSymbol {
+                    symbol: item.symbol.clone(),
+                    path: path.path.clone(),
+                    kind: path.kind,
+                    abi: item.abi.clone(),
+                    declaration,
+                    span: path.span.clone(),
+                })
+            })
+            .collect();
+        symbols.sort_unstable_by(|a, b| {
+            (a.symbol.as_deref(), a.path.as_str()).cmp(&(b.symbol.as_deref(), b.path.as_str()))
+        });
+
+        let mut layouts: Vec<_> = api
+            .paths
+            .iter()
+            .filter(|(_, path)| {
+                matches!(
+                    path.kind,
+                    crate_api::PathKind::Struct
+                        | crate_api::PathKind::Enum
+                        | crate_api::PathKind::Union
+                )
+            })
+            .filter_map(|(_, path)| {
+                let item = api.items.get(path.item_id?).unwrap();
+                if item.repr.is_empty() {
+                    return None;
+                }
+                Some(FfiLayout {
+                    path: path.path.clone(),
+                    kind: path.kind,
+                    repr: item.repr.clone(),
+                    span: path.span.clone(),
+                })
+            })
+            .collect();
+        layouts.sort_unstable_by(|a, b| a.path.cmp(&b.path));
+
+        Self { symbols, layouts }
+    }
+}
+
+#[derive(serde::Serialize)]
+#[serde(rename_all = "snake_case")]
+pub struct OwnedPath {
+    pub path: String,
+    pub kind: crate_api::PathKind,
+}
+
+pub fn group_api_by_owner(
+    api: &crate_api::Api,
+    owners: &crate::owners::Owners,
+) -> std::collections::BTreeMap<String, Vec<OwnedPath>> {
+    let mut groups: std::collections::BTreeMap<String, Vec<OwnedPath>> = Default::default();
+    for (path_id, path) in api.paths.iter() {
+        if Some(path_id) == api.root_id {
+            continue;
+        }
+        groups
+            .entry(owners.group_of(Some(&path.path)))
+            .or_default()
+            .push(OwnedPath {
+                path: path.path.clone(),
+                kind: path.kind,
+            });
+    }
+    for paths in groups.values_mut() {
+        paths.sort_unstable_by(|a, b| (a.path.as_str(), a.kind).cmp(&(b.path.as_str(), b.kind)));
+    }
+    groups
+}
+
+pub fn group_diffs_by_owner(
+    before: &crate_api::Api,
+    after: &crate_api::Api,
+    diffs: &[crate_api::diff::Diff],
+    owners: &crate::owners::Owners,
+) -> std::collections::BTreeMap<String, Vec<crate_api::diff::Diff>> {
+    let mut groups: std::collections::BTreeMap<String, Vec<crate_api::diff::Diff>> =
+        Default::default();
+    for diff in diffs {
+        let (api, location) = diff_location(before, after, diff);
+        let path = location_path(api, location);
+        groups
+            .entry(owners.group_of(path))
+            .or_default()
+            .push(diff.clone());
+    }
+    groups
+}
+
+pub fn render_api_markdown(
+    writer: &mut dyn std::io::Write,
+    api: &crate_api::Api,
+) -> Result<(), eyre::Report> {
+    let root_id = *match api.root_id.as_ref() {
+        Some(root_id) => root_id,
+        None => return Ok(()),
+    };
+
+    let root = api.paths.get(root_id).unwrap();
+    let _ = writeln!(writer, "# `{}`", root.path);
+    let _ = writeln!(writer);
+    match (api.edition.as_deref(), api.rust_version.as_deref()) {
+        (Some(edition), Some(rust_version)) => {
+            let _ = writeln!(writer, "*Edition {}, Rust {}+*", edition, rust_version);
+            let _ = writeln!(writer);
+        }
+        (Some(edition), None) => {
+            let _ = writeln!(writer, "*Edition {}*", edition);
+            let _ = writeln!(writer);
+        }
+        (None, Some(rust_version)) => {
+            let _ = writeln!(writer, "*Rust {}+*", rust_version);
+            let _ = writeln!(writer);
+        }
+        (None, None) => {}
+    }
+
+    let mut paths: std::collections::VecDeque<crate_api::PathId> = Default::default();
+    paths.extend(root.children.iter().copied().rev());
+    while let Some(next_path_id) = paths.pop_back() {
+        let next_path = api.paths.get(next_path_id).unwrap();
+
+        match next_path.kind {
+            crate_api::PathKind::Module => {
+                for _ in 0..(next_path.path.matches("::").count() + 1) {
+                    let _ = write!(writer, "#");
+                }
+                let _ = writeln!(
+                    writer,
+                    " `{}`{}",
+                    next_path.path,
+                    experimental_badge(next_path)
+                );
+                let _ = writeln!(writer);
+
+                if let Some(crate_id) = next_path.crate_id {
+                    let crate_ = api.crates.get(crate_id).unwrap();
+                    let _ = writeln!(writer, "*from crate `{}`*", crate_.name);
+                    let _ = writeln!(writer);
+                }
+
+                let (mut modules, mut other): (Vec<_>, Vec<_>) = next_path
+                    .children
+                    .iter()
+                    .copied()
+                    .partition(|next_path_id| {
+                        let next_path = api.paths.get(*next_path_id).unwrap();
+                        next_path.kind == crate_api::PathKind::Module
+                    });
+                modules.sort_unstable_by_key(|next_path_id| {
+                    let next_path = api.paths.get(*next_path_id).unwrap();
+                    (next_path.kind, next_path.path.as_str())
+                });
+                other.sort_unstable_by_key(|next_path_id| {
+                    let next_path = api.paths.get(*next_path_id).unwrap();
+                    (next_path.kind, next_path.path.as_str())
+                });
+                paths.extend(modules.into_iter().rev());
+                paths.extend(other.into_iter().rev());
+            }
+            _ => {
+                let item = next_path.item_id.and_then(|item_id| api.items.get(item_id));
+                let _ = writeln!(
+                    writer,
+                    "{} *({}{:?})*{}{}{}{}",
+                    strike_deprecated(item, format!("**`{}`**", next_path.path)),
+                    item.map(qualifiers).unwrap_or_default(),
+                    next_path.kind,
+                    item.map(attributes_badge).unwrap_or_default(),
+                    item.map(|item| features_badge(&item.features))
+                        .unwrap_or_default(),
+                    experimental_badge(next_path),
+                    item.map(deprecation_badge).unwrap_or_default()
+                );
+                let _ = writeln!(writer);
+
+                if let Some(docs) = item.and_then(|item| item.docs.as_deref()) {
+                    let _ = writeln!(writer, "{}", docs);
+                    let _ = writeln!(writer);
+                }
+
+                if let Some(crate_id) = next_path.crate_id {
+                    let crate_ = api.crates.get(crate_id).unwrap();
+                    let _ = writeln!(writer, "*from crate `{}`*", crate_.name);
+                    let _ = writeln!(writer);
+                }
+
+                if let Some(item) = next_path.item_id.and_then(|item_id| api.items.get(item_id)) {
+                    if !item.supertraits.is_empty() {
+                        let _ =
+                            writeln!(writer, "*Supertraits: `{}`*", item.supertraits.join(" + "));
+                        let _ = writeln!(writer);
+                    }
+                    let members: Vec<_> = item
+                        .trait_items
+                        .iter()
+                        .filter_map(|item_id| api.items.get(*item_id))
+                        .collect();
+                    if !members.is_empty() {
+                        for member in members {
+                            let provided = if member.signature.is_some() && member.has_default {
+                                " *(provided)*"
+                            } else {
+                                ""
+                            };
+                            let _ = writeln!(
+                                writer,
+                                "- {}{}{}{}{}{}",
+                                strike_deprecated(
+                                    Some(member),
+                                    format!("`{}`", associated_item(member))
+                                ),
+                                provided,
+                                attributes_badge(member),
+                                features_badge(&member.features),
+                                deprecation_badge(member),
+                                member_docs(member)
+                            );
+                        }
+                        let _ = writeln!(writer);
+                    }
+                    if let Some(item_id) = next_path.item_id {
+                        let mut traits: Vec<_> = api
+                            .trait_impls(item_id)
+                            .map(|(_, impl_)| {
+                                let negative = if impl_.is_negative { "!" } else { "" };
+                                format!("`{}{}`", negative, impl_.trait_)
+                            })
+                            .collect();
+                        traits.sort_unstable();
+                        traits.dedup();
+                        if !traits.is_empty() {
+                            let _ = writeln!(writer, "*Implements {}*", traits.join(", "));
+                            let _ = writeln!(writer);
+                        }
+                        // Kept apart, as their items come from the trait rather than the type
+                        let blanket_impls: Vec<_> = item
+                            .blanket_impls
+                            .iter()
+                            .filter_map(|impl_id| api.impls.get(*impl_id))
+                            .map(|impl_| format!("`{}`", impl_))
+                            .collect();
+                        if !blanket_impls.is_empty() {
+                            let _ = writeln!(
+                                writer,
+                                "*Through blanket impls {}*",
+                                blanket_impls.join(", ")
+                            );
+                            let _ = writeln!(writer);
+                        }
+                    }
+                    if let Some(variant_kind) = item.variant_kind {
+                        if variant_kind != crate_api::VariantKind::Unit {
+                            let _ = writeln!(writer, "`{}`", variant_declaration(item));
+                            let _ = writeln!(writer);
+                        }
+                    } else if !item.fields.is_empty() || item.has_private_fields {
+                        for field in &item.fields {
+                            let _ = writeln!(writer, "- `{}`", field_declaration(field));
+                        }
+                        // Stripped fields aren't listed, so call out that there are some
+                        if item.has_private_fields
+                            && item
+                                .fields
+                                .iter()
+                                .all(|field| field.visibility == crate_api::Visibility::Public)
+                        {
+                            let _ = writeln!(writer, "- `/* private fields */`");
+                        }
+                        let _ = writeln!(writer);
+                    } else if !item.matchers.is_empty() {
+                        let name = item.name.as_deref().unwrap_or_default();
+                        for matcher in &item.matchers {
+                            let _ = writeln!(writer, "- `{}!{}`", name, matcher);
+                        }
+                        let _ = writeln!(writer);
+                    } else if let Some(usage) = macro_usage(next_path.kind, item) {
+                        let _ = writeln!(writer, "`{}`", usage);
+                        let _ = writeln!(writer);
+                        if !item.helpers.is_empty() {
+                            let helpers: Vec<_> = item
+                                .helpers
+                                .iter()
+                                .map(|helper| format!("`#[{}]`", helper))
+                                .collect();
+                            let _ = writeln!(writer, "*Helper attributes {}*", helpers.join(", "));
+                            let _ = writeln!(writer);
+                        }
+                    } else if matches!(
+                        next_path.kind,
+                        crate_api::PathKind::Constant
+                            | crate_api::PathKind::Static
+                            | crate_api::PathKind::Typedef
+                    ) {
+                        if let Some(declaration) = declaration(api, next_path) {
+                            let _ = writeln!(writer, "`{}`", declaration);
+                            let _ = writeln!(writer);
+                        }
+                    }
+                    for inherent_impl in &item.inherent_impls {
+                        let _ = writeln!(writer, "`{}`", inherent_impl);
+                        for member in inherent_impl
+                            .items
+                            .iter()
+                            .filter_map(|item_id| api.items.get(*item_id))
+                        {
+                            let _ = writeln!(
+                                writer,
+                                "- {}{}{}{}{}",
+                                strike_deprecated(
+                                    Some(member),
+                                    format!("`{}`", associated_item(member))
+                                ),
+                                attributes_badge(member),
+                                features_badge(&member.features),
+                                deprecation_badge(member),
+                                member_docs(member)
+                            );
+                        }
+                        let _ = writeln!(writer);
+                    }
+                }
+
+                let mut other = next_path.children.clone();
+                other.sort_unstable_by_key(|next_path_id| {
+                    let next_path = api.paths.get(*next_path_id).unwrap();
+                    (next_path.kind, next_path.path.as_str())
+                });
+                paths.extend(other.into_iter().rev());
+            }
+        }
+    }
+
+    if !api.impls.is_empty() {
+        let _ = writeln!(writer, "## Trait Impls");
+        let _ = writeln!(writer);
+        let mut impls: Vec<_> = api
+            .impls
+            .iter()
+            .map(|(_, impl_)| (impl_.to_string(), features_badge(&impl_.features)))
+            .collect();
+        impls.sort_unstable();
+        for (impl_, features) in impls {
+            let _ = writeln!(writer, "- `{}`{}", impl_, features);
+        }
+        let _ = writeln!(writer);
+    }
+
+    if !api.features.is_empty() {
+        let _ = writeln!(writer, "## Feature Flags");
+        let _ = writeln!(writer);
+        for details in api.features.values() {
+            match details {
+                crate_api::AnyFeature::Feature(feature) => {
+                    let _ = writeln!(writer, "`{}`", feature.name);
+                    for dep in &feature.dependencies {
+                        let _ = writeln!(writer, "- `{}`", dep);
+                    }
+                    let _ = writeln!(writer);
+                }
+                crate_api::AnyFeature::OptionalDependency(dep) => {
+                    if let Some(package) = dep.package.as_deref() {
+                        let _ = writeln!(writer, "`{}` *(dependency `{}`)*", dep.name, package);
+                    } else {
+                        let _ = writeln!(writer, "`{}` *(dependency)*", dep.name);
+                    }
+                    let _ = writeln!(writer);
+                }
+            }
+        }
+    }
+
+    if !api.crates.is_empty() {
+        let _ = writeln!(writer, "## Public Dependencies");
+        let _ = writeln!(writer);
+
+        for (_, crate_) in api.crates.iter() {
+            let _ = writeln!(
+                writer,
+                "- `{}` (version {})",
+                crate_.name,
+                crate_
+                    .version
+                    .as_ref()
+                    .map(|v| v.to_string())
+                    .unwrap_or_else(|| "unknown".into())
+            );
+        }
+        let _ = writeln!(writer);
+    }
+
+    Ok(())
+}
+
+/// The impl block of the type at `location` that defines its item
+fn inherent_impl(
+    api: &crate_api::Api,
+    location: crate_api::diff::Location,
+) -> Option<&crate_api::InherentImpl> {
+    let item_id = location.item_id?;
+    let type_ = api.items.get(api.paths.get(location.path_id?)?.item_id?)?;
+    type_
+        .inherent_impls
+        .iter()
+        .find(|inherent_impl| inherent_impl.items.contains(&item_id))
+}
+
+/// Declare a field like its struct, union, or variant does
+fn field_declaration(field: &crate_api::Field) -> String {
+    match &field.visibility {
+        crate_api::Visibility::Inherited => format!("{}: {}", field.name, field.type_),
+        visibility => format!("{} {}: {}", visibility, field.name, field.type_),
+    }
+}
+
+/// Declare a variant like its enum does, like `Abc(u8)`
+fn variant_declaration(variant: &crate_api::Item) -> String {
+    let name = variant.name.as_deref().unwrap_or_default();
+    match variant.variant_kind {
+        Some(crate_api::VariantKind::Tuple) => {
+            let types: Vec<_> = variant
+                .fields
+                .iter()
+                .map(|field| field.type_.as_str())
+                .collect();
+            format!("{}({})", name, types.join(", "))
+        }
+        Some(crate_api::VariantKind::Struct) if variant.fields.is_empty() => {
+            format!("{} {{}}", name)
+        }
+        Some(crate_api::VariantKind::Struct) => {
+            let fields: Vec<_> = variant.fields.iter().map(field_declaration).collect();
+            format!("{} {{ {} }}", name, fields.join(", "))
+        }
+        Some(crate_api::VariantKind::Unit) | None => name.to_owned(),
+    }
+}
+
+/// How a proc-macro is used, like `#[derive(Serialize)]`, since it isn't declared like one
+fn macro_usage(kind: crate_api::PathKind, item: &crate_api::Item) -> Option<String> {
+    let name = item.name.as_deref().unwrap_or_default();
+    match kind {
+        crate_api::PathKind::ProcDerive => Some(format!("#[derive({})]", name)),
+        crate_api::PathKind::ProcAttribute => Some(format!("#[{}]", name)),
+        // Function-like proc-macros take any tokens, unlike `macro_rules!`
+        crate_api::PathKind::Macro if item.matchers.is_empty() => Some(format!("{}!(...)", name)),
+        _ => None,
+    }
+}
+
+/// Declare a function with its qualifiers, like `unsafe fn get_unchecked(index: usize)`
+fn fn_declaration(item: &crate_api::Item, signature: &crate_api::Signature) -> String {
+    let name = item.name.as_deref().unwrap_or_default();
+    format!(
+        "{}fn {}{}",
+        qualifiers(item),
+        name,
+        signature.with_generics(&item.generics)
+    )
+}
+
+/// The keywords ahead of a function's or trait's declaration, like `unsafe extern "C" `
+fn qualifiers(item: &crate_api::Item) -> String {
+    let mut qualifiers = String::new();
+    if let Some(signature) = &item.signature {
+        if signature.is_const {
+            qualifiers.push_str("const ");
+        }
+        if signature.is_async {
+            qualifiers.push_str("async ");
+        }
+    }
+    if item.is_unsafe {
+        qualifiers.push_str("unsafe ");
+    }
+    if let Some(abi) = &item.abi {
+        qualifiers.push_str(&format!("extern \"{}\" ", abi));
+    }
+    qualifiers
+}
+
+/// Declare an associated function, type, or const like its trait or impl block does
+fn associated_item(member: &crate_api::Item) -> String {
+    let name = member.name.as_deref().unwrap_or_default();
+    let mut rendered = if let Some(signature) = &member.signature {
+        fn_declaration(member, signature)
+    } else if let Some(type_) = &member.type_ {
+        format!("const {}: {}", name, type_)
+    } else if member.bounds.is_empty() {
+        format!("type {}", name)
+    } else {
+        format!("type {}: {}", name, member.bounds.join(" + "))
+    };
+    if member.signature.is_none() {
+        if let Some(default) = &member.default {
+            rendered.push_str(" = ");
+            rendered.push_str(default);
+        }
+    }
+    rendered
+}
+
+/// Attributes that change what users may rely on or are warned about, like `#[repr(C)]`
+fn attributes(item: &crate_api::Item) -> Vec<String> {
+    let mut attributes = Vec::new();
+    if !item.repr.is_empty() {
+        attributes.push(format!("#[repr({})]", item.repr.join(", ")));
+    }
+    if item.is_non_exhaustive {
+        attributes.push("#[non_exhaustive]".to_owned());
+    }
+    if let Some(must_use) = &item.must_use {
+        attributes.push(must_use.to_string());
+    }
+    attributes
+}
+
+/// [`attributes`], like `` `#[repr(C)]` ``, for after an item's name
+fn attributes_badge(item: &crate_api::Item) -> String {
+    attributes(item)
+        .into_iter()
+        .map(|attribute| format!(" `{}`", attribute))
+        .collect()
+}
+
+/// The features something is `cfg`-gated behind, like `` *(only with feature `serde`)*``
+fn features_badge(features: &[String]) -> String {
+    if features.is_empty() {
+        String::new()
+    } else {
+        format!(" *(only with {})*", feature_list(features))
+    }
+}
+
+/// Like `` feature `serde` `` or `` features `serde`, `std` ``
+fn feature_list(features: &[String]) -> String {
+    let names: Vec<_> = features
+        .iter()
+        .map(|feature| format!("`{}`", feature))
+        .collect();
+    if names.len() == 1 {
+        format!("feature {}", names[0])
+    } else {
+        format!("features {}", names.join(", "))
+    }
+}
+
+/// The features the impl or item at `location` is `cfg`-gated behind
+fn location_features(api: &crate_api::Api, location: crate_api::diff::Location) -> &[String] {
+    if let Some(impl_id) = location.impl_id {
+        &api.impls.get(impl_id).unwrap().features
+    } else {
+        location
+            .item_id
+            .and_then(|item_id| api.items.get(item_id))
+            .map(|item| item.features.as_slice())
+            .unwrap_or_default()
+    }
+}
+
+/// The `#[cfg]` for `features`, like `#[cfg(feature = "serde")]`
+fn cfg_attribute(features: &[String]) -> Option<String> {
+    let predicates: Vec<_> = features
+        .iter()
+        .map(|feature| format!("feature = {:?}", feature))
+        .collect();
+    match predicates.as_slice() {
+        [] => None,
+        [predicate] => Some(format!("#[cfg({})]", predicate)),
+        predicates => Some(format!("#[cfg(all({}))]", predicates.join(", "))),
+    }
+}
+
+/// A member's doc summary, like `: Returns the length`, kept to its list item's line
+fn member_docs(member: &crate_api::Item) -> String {
+    member
+        .docs
+        .as_deref()
+        .map(|docs| {
+            format!(
+                ": {}",
+                docs.split_whitespace().collect::<Vec<_>>().join(" ")
+            )
+        })
+        .unwrap_or_default()
+}
+
+/// Strike through `rendered` when `item` is deprecated
+fn strike_deprecated(item: Option<&crate_api::Item>, rendered: String) -> String {
+    match item {
+        Some(item) if item.deprecated.is_some() => format!("~~{}~~", rendered),
+        _ => rendered,
+    }
+}
+
+/// Why and since when `item` is deprecated, like ` *(deprecated since 1.2.0: use `bar`)*`
+fn deprecation_badge(item: &crate_api::Item) -> String {
+    let deprecation = match &item.deprecated {
+        Some(deprecation) => deprecation,
+        None => return String::new(),
+    };
+    let since = deprecation
+        .since
+        .as_deref()
+        .map(|since| format!(" since {}", since))
+        .unwrap_or_default();
+    let note = deprecation
+        .note
+        .as_deref()
+        .map(|note| format!(": {}", note))
+        .unwrap_or_default();
+    format!(" *(deprecated{}{})*", since, note)
+}
+
+/// When the item at `location` was deprecated, like ` since 1.2.0`, if it says
+fn deprecation_since(api: &crate_api::Api, location: crate_api::diff::Location) -> String {
+    location
+        .item_id
+        .and_then(|item_id| api.items.get(item_id))
+        .and_then(|item| item.deprecated.as_ref())
+        .and_then(|deprecation| deprecation.since.as_deref())
+        .map(|since| format!(" since {}", since))
+        .unwrap_or_default()
+}
+
+pub fn render_diff_markdown(
+    writer: &mut dyn std::io::Write,
+    before: &crate_api::Api,
+    after: &crate_api::Api,
+    diffs: &[crate_api::diff::Diff],
+) -> Result<(), eyre::Report> {
+    let mut diffs = diffs.to_vec();
+    diffs.sort_by_key(|diff| (diff.severity, diff.id.category, diff.id.name));
+
+    let mut last_severity = None;
+    let mut last_category = None;
+    for diff in diffs {
+        if Some(diff.severity) != last_severity {
+            match diff.severity {
+                crate_api::diff::Severity::Allow => {
+                    let _ = writeln!(writer, "## Allowed Changes");
+                    let _ = writeln!(writer);
+                }
+                crate_api::diff::Severity::Report => {
+                    let _ = writeln!(writer, "## Changes");
+                    let _ = writeln!(writer);
+                }
+                crate_api::diff::Severity::Warn => {
+                    let _ = writeln!(writer, "## Breaking Changes");
+                    let _ = writeln!(writer);
+                }
+            }
+            last_severity = Some(diff.severity);
+        }
+        if Some(diff.id.category) != last_category {
+            match diff.id.category {
+                crate_api::diff::Category::Unknown => {}
+                crate_api::diff::Category::Added => {
+                    let _ = writeln!(writer, "**Added**");
+                }
+                crate_api::diff::Category::Removed => {
+                    let _ = writeln!(writer, "**Removed**");
+                }
+                crate_api::diff::Category::Changed => {
+                    let _ = writeln!(writer, "**Changed**");
+                }
+            }
+            last_category = Some(diff.id.category);
+        }
+
+        match diff.id {
+            crate_api::diff::DEPENDENCY_REQUIREMENT => {
+                let before_crate = before
+                    .crates
+                    .get(diff.before.unwrap().crate_id.unwrap())
+                    .unwrap();
+                let after_crate = after
+                    .crates
+                    .get(diff.after.unwrap().crate_id.unwrap())
+                    .unwrap();
+                let _ = writeln!(
+                    writer,
+                    "- `{}` (public dependency): changed version requirement from {} to {}",
+                    after_crate.name,
+                    before_crate.version.as_ref().unwrap(),
+                    after_crate.version.as_ref().unwrap()
+                );
+            }
+            crate_api::diff::RUST_VERSION_RAISED => {
+                let _ = writeln!(
+                    writer,
+                    "- raised `rust-version` from {} to {}",
+                    before.rust_version.as_deref().unwrap_or_default(),
+                    after.rust_version.as_deref().unwrap_or_default()
+                );
+            }
+            crate_api::diff::ITEM_RENAMED => {
+                let _ = writeln!(
+                    writer,
+                    "- `{}`: renamed to `{}` ({}% confidence){}",
+                    location_name(before, diff.before.unwrap()),
+                    location_name(after, diff.after.unwrap()),
+                    diff.confidence.unwrap_or_default(),
+                    location_badge(after, diff.after)
+                );
+            }
+            crate_api::diff::ITEM_REMOVED => {
+                let path = before
+                    .paths
+                    .get(diff.before.unwrap().path_id.unwrap())
+                    .unwrap();
+                let _ = writeln!(
+                    writer,
+                    "- `{}`: removed {}{}",
+                    path.path,
+                    path.kind,
+                    location_badge(before, diff.before)
+                );
+            }
+            crate_api::diff::PATH_REMOVED => {
+                let path = before
+                    .paths
+                    .get(diff.before.unwrap().path_id.unwrap())
+                    .unwrap();
+                let _ = writeln!(
+                    writer,
+                    "- `{}`: removed {}, still reachable as `{}`{}",
+                    path.path,
+                    path.kind,
+                    location_name(after, diff.after.unwrap()),
+                    location_badge(before, diff.before)
+                );
+            }
+            crate_api::diff::DEPRECATED_ITEM_REMOVED => {
+                let location = diff.before.unwrap();
+                let path = before.paths.get(location.path_id.unwrap()).unwrap();
+                if location.item_id != path.item_id {
+                    let _ = writeln!(
+                        writer,
+                        "- `{}`: removed from `{}`, deprecated{}{}",
+                        location_name(before, location),
+                        inherent_impl(before, location)
+                            .map(|inherent_impl| inherent_impl.to_string())
+                            .unwrap_or_default(),
+                        deprecation_since(before, location),
+                        location_badge(before, diff.before)
+                    );
+                } else {
+                    let _ = writeln!(
+                        writer,
+                        "- `{}`: removed deprecated {}{}{}",
+                        path.path,
+                        path.kind,
+                        deprecation_since(before, location),
+                        location_badge(before, diff.before)
+                    );
+                }
+            }
+            crate_api::diff::DEPRECATION_ADDED => {
+                let note = diff
+                    .after
+                    .unwrap()
+                    .item_id
+                    .and_then(|item_id| after.items.get(item_id))
+                    .and_then(|item| item.deprecated.as_ref())
+                    .and_then(|deprecation| deprecation.note.as_deref())
+                    .map(|note| format!(": {}", note))
+                    .unwrap_or_default();
+                let _ = writeln!(
+                    writer,
+                    "- `{}`: deprecated{}{}{}",
+                    location_name(after, diff.after.unwrap()),
+                    deprecation_since(after, diff.after.unwrap()),
+                    note,
+                    location_badge(after, diff.after)
+                );
+            }
+            crate_api::diff::VISIBILITY_REDUCED => {
+                let visibility = |api: &crate_api::Api, location: crate_api::diff::Location| {
+                    location
+                        .item_id
+                        .and_then(|item_id| api.items.get(item_id))
+                        .map(|item| item.visibility.to_string())
+                        .unwrap_or_default()
+                };
+                let _ = writeln!(
+                    writer,
+                    "- `{}`: changed from `{}` to `{}`{}",
+                    location_name(after, diff.after.unwrap()),
+                    visibility(before, diff.before.unwrap()),
+                    visibility(after, diff.after.unwrap()),
+                    location_badge(after, diff.after)
+                );
+            }
+            crate_api::diff::FEATURE_GATE_ADDED | crate_api::diff::FEATURE_GATE_REMOVED => {
+                let before_features = location_features(before, diff.before.unwrap());
+                let after_features = location_features(after, diff.after.unwrap());
+                let (change, features): (_, Vec<_>) =
+                    if diff.id == crate_api::diff::FEATURE_GATE_ADDED {
+                        let added = after_features
+                            .iter()
+                            .filter(|feature| !before_features.contains(feature));
+                        ("now needs", added.cloned().collect())
+                    } else {
+                        let removed = before_features
+                            .iter()
+                            .filter(|feature| !after_features.contains(feature));
+                        ("no longer needs", removed.cloned().collect())
+                    };
+                let _ = writeln!(
+                    writer,
+                    "- `{}`: {} {}{}",
+                    location_name(after, diff.after.unwrap()),
+                    change,
+                    feature_list(&features),
+                    location_badge(after, diff.after)
+                );
+            }
+            crate_api::diff::REPR_ADDED | crate_api::diff::REPR_CHANGED => {
+                let repr = |api: &crate_api::Api, location: crate_api::diff::Location| {
+                    let repr = location
+                        .item_id
+                        .and_then(|item_id| api.items.get(item_id))
+                        .map(|item| item.repr.join(", "))
+                        .unwrap_or_default();
+                    if repr.is_empty() {
+                        "the default representation".to_owned()
+                    } else {
+                        format!("`#[repr({})]`", repr)
+                    }
+                };
+                let _ = writeln!(
+                    writer,
+                    "- `{}`: changed from {} to {}{}",
+                    location_name(after, diff.after.unwrap()),
+                    repr(before, diff.before.unwrap()),
+                    repr(after, diff.after.unwrap()),
+                    location_badge(after, diff.after)
+                );
+            }
+            crate_api::diff::AUTO_TRAIT_REMOVED => {
+                let auto_traits = |api: &crate_api::Api, location: crate_api::diff::Location| {
+                    location
+                        .item_id
+                        .and_then(|item_id| api.items.get(item_id))
+                        .map(|item| item.auto_traits.clone())
+                        .unwrap_or_default()
+                };
+                let after_traits = auto_traits(after, diff.after.unwrap());
+                let removed: Vec<_> = auto_traits(before, diff.before.unwrap())
+                    .into_iter()
+                    .filter(|auto_trait| !after_traits.contains(auto_trait))
+                    .map(|auto_trait| format!("`{}`", auto_trait))
+                    .collect();
+                let _ = writeln!(
+                    writer,
+                    "- `{}`: no longer implements {}{}",
+                    location_name(after, diff.after.unwrap()),
+                    removed.join(", "),
+                    location_badge(after, diff.after)
+                );
+            }
+            crate_api::diff::BLANKET_IMPL_REMOVED => {
+                let location = diff.before.unwrap();
+                let type_ = location_path(before, location).unwrap_or_default();
+                let impl_ = location
+                    .impl_id
+                    .and_then(|impl_id| before.impls.get(impl_id))
+                    .map(|impl_| impl_.to_string())
+                    .unwrap_or_default();
+                let _ = writeln!(
+                    writer,
+                    "- `{}`: no longer covered by `{}`{}",
+                    type_,
+                    impl_,
+                    location_badge(before, diff.before)
+                );
+            }
+            crate_api::diff::INHERENT_ITEM_ADDED | crate_api::diff::INHERENT_ITEM_REMOVED => {
+                let (api, location) = diff_location(before, after, &diff);
+                let change = if diff.id == crate_api::diff::INHERENT_ITEM_ADDED {
+                    "added to"
+                } else {
+                    "removed from"
+                };
+                let _ = writeln!(
+                    writer,
+                    "- `{}`: {} `{}`{}",
+                    location_name(api, location),
+                    change,
+                    inherent_impl(api, location)
+                        .map(|inherent_impl| inherent_impl.to_string())
+                        .unwrap_or_default(),
+                    location_badge(api, Some(location))
+                );
+            }
+            crate_api::diff::VARIANT_FIELDS_CHANGED => {
+                let variant = |api: &crate_api::Api, location: crate_api::diff::Location| {
+                    location
+                        .item_id
+                        .and_then(|item_id| api.items.get(item_id))
+                        .map(variant_declaration)
+                        .unwrap_or_default()
+                };
+                let _ = writeln!(
+                    writer,
+                    "- `{}`: changed from `{}` to `{}`{}",
+                    location_name(after, diff.after.unwrap()),
+                    variant(before, diff.before.unwrap()),
+                    variant(after, diff.after.unwrap()),
+                    location_badge(after, diff.after)
+                );
+            }
+            crate_api::diff::FN_ARITY_CHANGED
+            | crate_api::diff::FN_PARAMETER_CHANGED
+            | crate_api::diff::FN_OUTPUT_CHANGED => {
+                let signature = |api: &crate_api::Api, location: crate_api::diff::Location| {
+                    location
+                        .item_id
+                        .and_then(|item_id| api.items.get(item_id))
+                        .and_then(|item| {
+                            let signature = item.signature.as_ref()?;
+                            Some(signature.with_generics(&item.generics).to_string())
+                        })
+                        .unwrap_or_default()
+                };
+                let _ = writeln!(
+                    writer,
+                    "- `{}`: changed signature from `{}` to `{}`{}",
+                    location_name(after, diff.after.unwrap()),
+                    signature(before, diff.before.unwrap()),
+                    signature(after, diff.after.unwrap()),
+                    location_badge(after, diff.after)
+                );
+            }
+            crate_api::diff::CONST_TYPE_CHANGED
+            | crate_api::diff::CONST_VALUE_CHANGED
+            | crate_api::diff::STATIC_MUTABILITY_CHANGED
+            | crate_api::diff::TYPE_ALIAS_CHANGED => {
+                let declaration = |api: &crate_api::Api, location: crate_api::diff::Location| {
+                    location
+                        .path_id
+                        .and_then(|path_id| api.paths.get(path_id))
+                        .and_then(|path| declaration(api, path))
+                        .unwrap_or_default()
+                };
+                let _ = writeln!(
+                    writer,
+                    "- `{}`: changed from `{}` to `{}`{}",
+                    location_name(after, diff.after.unwrap()),
+                    declaration(before, diff.before.unwrap()),
+                    declaration(after, diff.after.unwrap()),
+                    location_badge(after, diff.after)
+                );
+            }
+            crate_api::diff::DERIVE_HELPER_REMOVED => {
+                let helpers = |api: &crate_api::Api, location: crate_api::diff::Location| {
+                    location
+                        .item_id
+                        .and_then(|item_id| api.items.get(item_id))
+                        .map(|item| item.helpers.clone())
+                        .unwrap_or_default()
+                };
+                let remaining = helpers(after, diff.after.unwrap());
+                let removed: Vec<_> = helpers(before, diff.before.unwrap())
+                    .into_iter()
+                    .filter(|helper| !remaining.contains(helper))
+                    .map(|helper| format!("`#[{}]`", helper))
+                    .collect();
+                let _ = writeln!(
+                    writer,
+                    "- `{}`: no longer declares {}{}",
+                    location_name(after, diff.after.unwrap()),
+                    removed.join(", "),
+                    location_badge(after, diff.after)
+                );
+            }
+            crate_api::diff::MACRO_RULE_REMOVED | crate_api::diff::MACRO_RULE_ADDED => {
+                let rules = |api: &crate_api::Api, location: crate_api::diff::Location| {
+                    location
+                        .item_id
+                        .and_then(|item_id| api.items.get(item_id))
+                        .map(|item| (item.name.clone().unwrap_or_default(), item.matchers.clone()))
+                        .unwrap_or_default()
+                };
+                let (name, before_matchers) = rules(before, diff.before.unwrap());
+                let (_, after_matchers) = rules(after, diff.after.unwrap());
+                let (change, from, to) = if diff.id == crate_api::diff::MACRO_RULE_REMOVED {
+                    ("no longer accepts", &before_matchers, &after_matchers)
+                } else {
+                    ("now also accepts", &after_matchers, &before_matchers)
+                };
+                let changed: Vec<_> = from
+                    .iter()
+                    .filter(|matcher| !to.contains(matcher))
+                    .map(|matcher| format!("`{}!{}`", name, matcher))
+                    .collect();
+                let _ = writeln!(
+                    writer,
+                    "- `{}`: {} {}{}",
+                    location_name(after, diff.after.unwrap()),
+                    change,
+                    changed.join(", "),
+                    location_badge(after, diff.after)
+                );
+            }
+            crate_api::diff::ASSOC_CONST_TYPE_CHANGED
+            | crate_api::diff::ASSOC_TYPE_BOUND_ADDED
+            | crate_api::diff::ASSOC_TYPE_BOUND_REMOVED => {
+                let member = |api: &crate_api::Api, location: crate_api::diff::Location| {
+                    location
+                        .item_id
+                        .and_then(|item_id| api.items.get(item_id))
+                        .map(associated_item)
+                        .unwrap_or_default()
+                };
+                let _ = writeln!(
+                    writer,
+                    "- `{}`: changed from `{}` to `{}`{}",
+                    location_name(after, diff.after.unwrap()),
+                    member(before, diff.before.unwrap()),
+                    member(after, diff.after.unwrap()),
+                    location_badge(after, diff.after)
+                );
+            }
+            crate_api::diff::FN_ABI_CHANGED => {
+                let abi = |api: &crate_api::Api, location: crate_api::diff::Location| {
+                    location
+                        .item_id
+                        .and_then(|item_id| api.items.get(item_id))
+                        .and_then(|item| item.abi.clone())
+                        .unwrap_or_else(|| "Rust".to_owned())
+                };
+                let _ = writeln!(
+                    writer,
+                    "- `{}`: changed ABI from `\"{}\"` to `\"{}\"`{}",
+                    location_name(after, diff.after.unwrap()),
+                    abi(before, diff.before.unwrap()),
+                    abi(after, diff.after.unwrap()),
+                    location_badge(after, diff.after)
+                );
+            }
+            crate_api::diff::FN_UNSAFE_ADDED
+            | crate_api::diff::FN_UNSAFE_REMOVED
+            | crate_api::diff::FN_CONST_REMOVED
+            | crate_api::diff::FN_ASYNC_CHANGED
+            | crate_api::diff::TRAIT_UNSAFE_ADDED
+            | crate_api::diff::TRAIT_UNSAFE_REMOVED => {
+                let is_async = || {
+                    diff.after
+                        .and_then(|location| location.item_id)
+                        .and_then(|item_id| after.items.get(item_id))
+                        .and_then(|item| item.signature.as_ref())
+                        .map_or(false, |signature| signature.is_async)
+                };
+                let (change, qualifier) = match diff.id {
+                    crate_api::diff::FN_UNSAFE_ADDED | crate_api::diff::TRAIT_UNSAFE_ADDED => {
+                        ("became", "unsafe")
+                    }
+                    crate_api::diff::FN_CONST_REMOVED => ("is no longer", "const"),
+                    crate_api::diff::FN_ASYNC_CHANGED if is_async() => ("became", "async"),
+                    crate_api::diff::FN_ASYNC_CHANGED => ("is no longer", "async"),
+                    _ => ("is no longer", "unsafe"),
+                };
+                let _ = writeln!(
+                    writer,
+                    "- `{}`: {} `{}`{}",
+                    location_name(after, diff.after.unwrap()),
+                    change,
+                    qualifier,
+                    location_badge(after, diff.after)
+                );
+            }
+            _ => {
+                let (api, location) = diff_location(before, after, &diff);
+                let name = location_name(api, location);
+                let badge = location_badge(api, Some(location));
+                let _ = writeln!(writer, "- `{}`: {}{}", name, diff.id.explanation, badge);
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Keep a Changelog sections describing a diff, for the next release's `CHANGELOG.md` entry
+///
+/// Only the outermost added or removed path is listed, as its children go with it.  Changes
+/// that are breaking are called out.
+pub fn render_diff_changelog(
+    writer: &mut dyn std::io::Write,
+    before: &crate_api::Api,
+    after: &crate_api::Api,
+    diffs: &[crate_api::diff::Diff],
+    excluded: &std::collections::HashSet<String>,
+) -> Result<(), eyre::Report> {
+    let mut before_surface = crate::freeze::surface(before);
+    before_surface.retain(|(path, _), _| !excluded.contains(path));
+    let mut after_surface = crate::freeze::surface(after);
+    after_surface.retain(|(path, _), _| !excluded.contains(path));
+    let before_paths = paths_by_key(before);
+    let after_paths = paths_by_key(after);
+
+    let breaking: std::collections::HashSet<_> = diffs
+        .iter()
+        .filter(|diff| diff.severity == crate_api::diff::Severity::Warn)
+        .filter_map(|diff| {
+            let (api, location) = diff_location(before, after, diff);
+            location_path(api, location)
+        })
+        .collect();
+    let breaking_badge = |path: &str| {
+        if breaking.contains(path) {
+            "**Breaking:** "
+        } else {
+            ""
+        }
+    };
+    let entry = |api: &crate_api::Api, path: &crate_api::Path| match declaration(api, path) {
+        Some(declaration) => format!("`{}`: `{}`", path.path, declaration),
+        None => format!("{} `{}`", path.kind, path.path),
+    };
+    let added: Vec<_> = outermost(
+        after_surface
+            .keys()
+            .filter(|key| !before_surface.contains_key(*key))
+            .collect(),
+    )
+    .into_iter()
+    .map(|key| {
+        format!(
+            "- {}{}",
+            breaking_badge(&key.0),
+            entry(after, after_paths[key])
+        )
+    })
+    .collect();
+
+    let mut changed: Vec<_> = diffs
+        .iter()
+        .filter_map(|diff| {
+            if diff.id == crate_api::diff::RUST_VERSION_RAISED {
+                Some(format!(
+                    "- {}Raised `rust-version` to {}",
+                    breaking_badge(location_path(after, diff.after?)?),
+                    after.rust_version.as_deref().unwrap_or_default()
+                ))
+            } else {
+                // Changes to the API itself are described by the items they affected
+                let (api, location) = diff_location(before, after, diff);
+                location.path_id.is_none().then(|| {
+                    format!(
+                        "- `{}`: {}",
+                        location_name(api, location),
+                        diff.id.explanation
+                    )
+                })
+            }
+        })
+        .collect();
+    changed.extend(before_surface.iter().filter_map(|(key, before_item)| {
+        let after_item = after_surface.get(key)?;
+        (without_deprecation(before_item) != without_deprecation(after_item)).then(|| {
+            format!(
+                "- {}{}",
+                breaking_badge(&key.0),
+                entry(after, after_paths[key])
+            )
+        })
+    }));
+
+    let deprecated: Vec<_> = diffs
+        .iter()
+        .filter(|diff| diff.id == crate_api::diff::DEPRECATION_ADDED)
+        .map(|diff| {
+            let location = diff.after.unwrap();
+            let note = location
+                .item_id
+                .and_then(|item_id| after.items.get(item_id))
+                .and_then(|item| item.deprecated.as_ref())
+                .and_then(|deprecation| deprecation.note.as_deref())
+                .map(|note| format!(": {}", note))
+                .unwrap_or_default();
+            format!("- `{}`{}", location_name(after, location), note)
+        })
+        .collect();
+
+    let removed: Vec<_> = outermost(
+        before_surface
+            .keys()
+            .filter(|key| !after_surface.contains_key(*key))
+            .collect(),
+    )
+    .into_iter()
+    .map(|key| {
+        format!(
+            "- {}{}",
+            breaking_badge(&key.0),
+            entry(before, before_paths[key])
+        )
+    })
+    .collect();
+
+    for (section, entries) in [
+        ("Added", added),
+        ("Changed", changed),
+        ("Deprecated", deprecated),
+        ("Removed", removed),
+    ] {
+        if entries.is_empty() {
+            continue;
+        }
+        let _ = writeln!(writer, "### {}", section);
+        let _ = writeln!(writer);
+        for entry in entries {
+            let _ = writeln!(writer, "{}", entry);
+        }
+        let _ = writeln!(writer);
+    }
+
+    Ok(())
+}
+
+/// Leave out paths under another of `keys`, as they go along with it
+fn outermost(keys: Vec<&(String, crate_api::PathKind)>) -> Vec<&(String, crate_api::PathKind)> {
+    let paths: std::collections::HashSet<_> = keys.iter().map(|(path, _)| path.as_str()).collect();
+    keys.iter()
+        .copied()
+        .filter(|(path, _)| {
+            let parent = path.rsplit_once("::").map(|(parent, _)| parent);
+            !parent.map(|parent| paths.contains(parent)).unwrap_or(false)
+        })
+        .collect()
+}
+
+/// Leave out deprecation, which the changelog lists on its own
+fn without_deprecation(item: &serde_json::Value) -> serde_json::Value {
+    let mut item = item.clone();
+    if let Some(fields) = item.as_object_mut() {
+        fields.remove("deprecated");
+    }
+    item
+}
+
+/// One line per change, marked `+` for added, `-` for removed, or `~` for changed
+pub fn render_diff_compact(
+    writer: &mut dyn std::io::Write,
+    before: &crate_api::Api,
+    after: &crate_api::Api,
+    diffs: &[crate_api::diff::Diff],
+) -> Result<(), eyre::Report> {
+    for diff in diffs {
+        let marker = match diff.id.category {
+            crate_api::diff::Category::Added => '+',
+            crate_api::diff::Category::Removed => '-',
+            crate_api::diff::Category::Changed | crate_api::diff::Category::Unknown => '~',
+        };
+        let (api, location) = diff_location(before, after, diff);
+        let name = location_name(api, location);
+        let breaking = match diff.severity {
+            crate_api::diff::Severity::Warn => " (breaking)",
+            crate_api::diff::Severity::Allow | crate_api::diff::Severity::Report => "",
+        };
+        let _ = writeln!(
+            writer,
+            "{} {}: {}{}",
+            marker, name, diff.id.explanation, breaking
+        );
+    }
+    Ok(())
+}
+
+/// GitHub Actions workflow commands, annotating the source of each change on the PR
+///
+/// Breaking changes are errors and other reported changes are warnings.  Changes are placed
+/// where the item is now, falling back to where it was, like for removed items.
+pub fn render_diff_github(
+    writer: &mut dyn std::io::Write,
+    before: &crate_api::Api,
+    after: &crate_api::Api,
+    diffs: &[crate_api::diff::Diff],
+) -> Result<(), eyre::Report> {
+    for diff in diffs {
+        let command = match diff.severity {
+            crate_api::diff::Severity::Allow => continue,
+            crate_api::diff::Severity::Report => "warning",
+            crate_api::diff::Severity::Warn => "error",
+        };
+        let (api, location) = diff_location(before, after, diff);
+        let message = format!(
+            "`{}`: {}",
+            location_name(api, location),
+            diff.id.explanation
+        );
+        let span = diff
+            .after
+            .and_then(|location| location_span(after, location))
+            .or_else(|| {
+                diff.before
+                    .and_then(|location| location_span(before, location))
+            });
+        let mut properties = Vec::new();
+        if let Some(span) = span {
+            properties.push(format!(
+                "file={}",
+                escape_github_property(&span.filename.display().to_string())
+            ));
+            properties.push(format!("line={}", span.begin.0));
+            properties.push(format!("endLine={}", span.end.0));
+        }
+        properties.push(format!("title={}", escape_github_property(diff.id.name)));
+        let _ = writeln!(
+            writer,
+            "::{} {}::{}",
+            command,
+            properties.join(","),
+            escape_github_data(&message)
+        );
+    }
+    Ok(())
+}
+
+/// A [SARIF](https://sarifweb.azurewebsites.net/) log of the changes, like for GitHub code scanning
+///
+/// Every kind of change is described as a rule, whether or not it was found.  Like with
+/// annotations, breaking changes are errors and other reported changes are warnings.
+pub fn render_diff_sarif(
+    before: &crate_api::Api,
+    after: &crate_api::Api,
+    diffs: &[crate_api::diff::Diff],
+) -> serde_json::Value {
+    let level = |severity| match severity {
+        crate_api::diff::Severity::Allow => "none",
+        crate_api::diff::Severity::Report => "warning",
+        crate_api::diff::Severity::Warn => "error",
+    };
+    let rules: Vec<_> = crate_api::diff::ALL_IDS
+        .iter()
+        .map(|id| {
+            serde_json::json!({
+                "id": id.name,
+                "shortDescription": {"text": id.explanation},
+                "helpUri": crate_api::diff::rules::rule(*id).reference,
+                "defaultConfiguration": {"level": level(id.default_severity)},
+            })
+        })
+        .collect();
+    let results: Vec<_> = diffs
+        .iter()
+        .filter(|diff| diff.severity != crate_api::diff::Severity::Allow)
+        .map(|diff| {
+            let (api, location) = diff_location(before, after, diff);
+            let span = diff
+                .after
+                .and_then(|location| location_span(after, location))
+                .or_else(|| {
+                    diff.before
+                        .and_then(|location| location_span(before, location))
+                });
+            let locations: Vec<_> = span
+                .map(|span| {
+                    serde_json::json!({
+                        "physicalLocation": {
+                            "artifactLocation": {
+                                "uri": span.filename.display().to_string().replace('\\', "/"),
+                            },
+                            "region": {
+                                "startLine": span.begin.0,
+                                "startColumn": span.begin.1 + 1,
+                                "endLine": span.end.0,
+                                "endColumn": span.end.1 + 1,
+                            },
+                        },
+                        "logicalLocations": [{
+                            "fullyQualifiedName": location_name(api, location),
+                        }],
+                    })
+                })
+                .into_iter()
+                .collect();
+            serde_json::json!({
+                "ruleId": diff.id.name,
+                "ruleIndex": crate_api::diff::ALL_IDS
+                    .iter()
+                    .position(|id| id.name == diff.id.name),
+                "level": level(diff.severity),
+                "message": {
+                    "text": format!("`{}`: {}", location_name(api, location), diff.id.explanation),
+                },
+                "locations": locations,
+            })
+        })
+        .collect();
+
+    serde_json::json!({
+        "$schema": "https://json.schemastore.org/sarif-2.1.0.json",
+        "version": "2.1.0",
+        "runs": [{
+            "tool": {
+                "driver": {
+                    "name": env!("CARGO_PKG_NAME"),
+                    "version": env!("CARGO_PKG_VERSION"),
+                    "informationUri": env!("CARGO_PKG_HOMEPAGE"),
+                    "rules": rules,
+                },
+            },
+            "results": results,
+        }],
+    })
+}
+
+/// A standalone page showing each change's item before and after, grouped by module
+///
+/// Changes can be filtered by severity, so reviewers can focus on the breaking ones.
+pub fn render_diff_html(
+    writer: &mut dyn std::io::Write,
+    name: &str,
+    before: &crate_api::Api,
+    after: &crate_api::Api,
+    diffs: &[crate_api::diff::Diff],
+) -> Result<(), eyre::Report> {
+    let mut modules: std::collections::BTreeMap<String, Vec<&crate_api::diff::Diff>> =
+        Default::default();
+    for diff in diffs {
+        let (api, location) = diff_location(before, after, diff);
+        let name = location_name(api, location);
+        let module = name
+            .rsplit_once("::")
+            .map(|(module, _)| module)
+            .unwrap_or(&name);
+        modules.entry(module.to_owned()).or_default().push(diff);
+    }
+
+    let _ = writeln!(writer, "<!DOCTYPE html>");
+    let _ = writeln!(writer, "<html lang=\"en\">");
+    let _ = writeln!(writer, "<head>");
+    let _ = writeln!(writer, "<meta charset=\"utf-8\">");
+    let _ = writeln!(
+        writer,
+        "<title>API changes to {}</title>",
+        escape_html(name)
+    );
+    let _ = writeln!(writer, "<style>");
+    let _ = writeln!(
+        writer,
+        "body {{ font-family: sans-serif; margin: 2em; }}
+.change {{ border-left: 4px solid; margin: 1em 0; padding: 0 1em; }}
+.change.warn {{ border-color: #d73a49; }}
+.change.report {{ border-color: #dbab09; }}
+.change.allow {{ border-color: #6a737d; }}
+.id {{ color: #6a737d; font-size: small; font-weight: normal; }}
+table {{ border-collapse: collapse; width: 100%; table-layout: fixed; }}
+th, td {{ border: 1px solid #e1e4e8; padding: 0.5em; text-align: left; vertical-align: top; }}
+pre {{ margin: 0; white-space: pre-wrap; }}"
+    );
+    let _ = writeln!(writer, "</style>");
+    let _ = writeln!(writer, "</head>");
+    let _ = writeln!(writer, "<body>");
+    let _ = writeln!(
+        writer,
+        "<h1>API changes to <code>{}</code></h1>",
+        escape_html(name)
+    );
+
+    let _ = writeln!(writer, "<fieldset>");
+    let _ = writeln!(writer, "<legend>Show</legend>");
+    for (severity, label) in [
+        (crate_api::diff::Severity::Warn, "Breaking"),
+        (crate_api::diff::Severity::Report, "Reported"),
+        (crate_api::diff::Severity::Allow, "Allowed"),
+    ] {
+        let count = diffs
+            .iter()
+            .filter(|diff| diff.severity == severity)
+            .count();
+        let _ = writeln!(
+            writer,
+            "<label><input type=\"checkbox\" data-severity=\"{}\" checked> {} ({})</label>",
+            severity_class(severity),
+            label,
+            count
+        );
+    }
+    let _ = writeln!(writer, "</fieldset>");
+
+    for (module, diffs) in modules {
+        let _ = writeln!(writer, "<section>");
+        let _ = writeln!(writer, "<h2><code>{}</code></h2>", escape_html(&module));
+        for diff in diffs {
+            let (api, location) = diff_location(before, after, diff);
+            let _ = writeln!(
+                writer,
+                "<div class=\"change {}\">",
+                severity_class(diff.severity)
+            );
+            let _ = writeln!(
+                writer,
+                "<h3><code>{}</code> <span class=\"id\">{}</span></h3>",
+                escape_html(&location_name(api, location)),
+                diff.id.name
+            );
+            let _ = writeln!(writer, "<p>{}</p>", escape_html(diff.id.explanation));
+            let rendering = |api, location: Option<crate_api::diff::Location>| {
+                location
+                    .map(|location| escape_html(&item_rendering(api, location)))
+                    .unwrap_or_default()
+            };
+            let _ = writeln!(writer, "<table>");
+            let _ = writeln!(writer, "<tr><th>Before</th><th>After</th></tr>");
+            let _ = writeln!(
+                writer,
+                "<tr><td><pre>{}</pre></td><td><pre>{}</pre></td></tr>",
+                rendering(before, diff.before),
+                rendering(after, diff.after)
+            );
+            let _ = writeln!(writer, "</table>");
+            let _ = writeln!(writer, "</div>");
+        }
+        let _ = writeln!(writer, "</section>");
+    }
+
+    let _ = writeln!(writer, "<script>");
+    let _ = writeln!(
+        writer,
+        "for (const filter of document.querySelectorAll('input[data-severity]')) {{
+  filter.addEventListener('change', () => {{
+    for (const change of document.querySelectorAll('.change.' + filter.dataset.severity)) {{
+      change.hidden = !filter.checked;
+    }}
+  }});
+}}"
+    );
+    let _ = writeln!(writer, "</script>");
+    let _ = writeln!(writer, "</body>");
+    let _ = writeln!(writer, "</html>");
+
+    Ok(())
+}
+
+fn severity_class(severity: crate_api::diff::Severity) -> &'static str {
+    match severity {
+        crate_api::diff::Severity::Allow => "allow",
+        crate_api::diff::Severity::Report => "report",
+        crate_api::diff::Severity::Warn => "warn",
+    }
+}
+
+/// The item at `location` with the attributes that affect compatibility
+fn item_rendering(api: &crate_api::Api, location: crate_api::diff::Location) -> String {
+    if let Some(impl_id) = location.impl_id {
+        let impl_ = api.impls.get(impl_id).unwrap();
+        return cfg_attribute(&impl_.features)
+            .into_iter()
+            .chain(std::iter::once(impl_.to_string()))
+            .collect::<Vec<_>>()
+            .join("\n");
+    }
+    let path = match location.path_id.and_then(|path_id| api.paths.get(path_id)) {
+        Some(path) => path,
+        None => return location_name(api, location).into_owned(),
+    };
+    let item = location
+        .item_id
+        .or(path.item_id)
+        .and_then(|item_id| api.items.get(item_id));
+    let mut lines = Vec::new();
+    if let Some(item) = item {
+        lines.extend(cfg_attribute(&item.features));
+        if let Some(deprecation) = &item.deprecated {
+            lines.push(deprecation.to_string());
+        }
+        lines.extend(attributes(item));
+    }
+    let declaration = match item {
+        Some(item) if location.item_id != path.item_id => {
+            // Trait items have no path of their own and share their trait's visibility
+            lines.push(associated_item(item));
+            return lines.join("\n");
+        }
+        _ => declaration(api, path).unwrap_or_else(|| {
+            let generics = item
+                .map(|item| item.generics.to_string())
+                .unwrap_or_default();
+            let qualifiers = item.map(qualifiers).unwrap_or_default();
+            format!("{}{} {}{}", qualifiers, path.kind, path.path, generics)
+        }),
+    };
+    match item.map(|item| &item.visibility) {
+        Some(crate_api::Visibility::Public) | None => lines.push(declaration),
+        Some(visibility) => lines.push(format!("{} {}", visibility, declaration)),
+    }
+    lines.join("\n")
+}
+
+fn escape_html(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+/// Escape a workflow command's message
+fn escape_github_data(data: &str) -> String {
+    data.replace('%', "%25")
+        .replace('\r', "%0D")
+        .replace('\n', "%0A")
+}
+
+/// Escape a workflow command's property value, which also can't contain its delimiters
+fn escape_github_property(value: &str) -> String {
+    escape_github_data(value)
+        .replace(':', "%3A")
+        .replace(',', "%2C")
+}
+
+pub fn render_feature_docs_markdown(
+    writer: &mut dyn std::io::Write,
+    docs: &FeatureDocs,
+) -> Result<(), eyre::Report> {
+    let _ = writeln!(writer, "## Feature flags");
+    let _ = writeln!(writer);
+    if docs.features.is_empty() {
+        let _ = writeln!(writer, "`{}` has no features.", docs.name);
+        let _ = writeln!(writer);
+        return Ok(());
+    }
+    for feature in &docs.features {
+        let default_badge = if feature.is_default {
+            " *(default)*"
+        } else {
+            ""
+        };
+        let _ = writeln!(writer, "- `{}`{}", feature.name, default_badge);
+        if let Some(dependency) = &feature.dependency {
+            let _ = writeln!(writer, "  - Adds a dependency on `{}`", dependency);
+        }
+        if !feature.enables.is_empty() {
+            let enables: Vec<_> = feature
+                .enables
+                .iter()
+                .map(|enabled| format!("`{}`", enabled))
+                .collect();
+            let _ = writeln!(writer, "  - Enables {}", enables.join(", "));
+        }
+        for path in &feature.gates {
+            let _ = writeln!(writer, "  - Provides `{}`", path);
+        }
+    }
+    let _ = writeln!(writer);
+
+    Ok(())
+}
+
+pub fn render_verification_markdown(
+    writer: &mut dyn std::io::Write,
+    verification: &Verification,
+) -> Result<(), eyre::Report> {
+    let status = match verification.status {
+        VerificationStatus::Verified => "matches its source",
+        VerificationStatus::Mismatch => "does not match its source",
+        VerificationStatus::NotEmbedded => "has no embedded API snapshot",
+    };
+    let _ = writeln!(
+        writer,
+        "# `{}` {} {}",
+        verification.name, verification.version, status
+    );
+    if !verification.mismatches.is_empty() {
+        let _ = writeln!(writer);
+        for path in &verification.mismatches {
+            let _ = writeln!(writer, "- `{}`", path);
+        }
+    }
+    let _ = writeln!(writer);
+
+    Ok(())
+}
+
+pub fn render_metrics_markdown(
+    writer: &mut dyn std::io::Write,
+    metrics: &Metrics,
+) -> Result<(), eyre::Report> {
+    let _ = writeln!(writer, "# `{}` {}", metrics.name, metrics.version);
+    let _ = writeln!(writer);
+    let _ = writeln!(writer, "*recorded {}*", metrics.timestamp);
+    let _ = writeln!(writer);
+    let _ = writeln!(writer, "| Metric | Value |");
+    let _ = writeln!(writer, "|--------|-------|");
+    let _ = writeln!(writer, "| Paths | {} |", metrics.paths);
+    for (kind, count) in &metrics.paths_by_kind {
+        let _ = writeln!(writer, "| Paths ({}) | {} |", kind, count);
+    }
+    let _ = writeln!(writer, "| Items | {} |", metrics.items);
+    let _ = writeln!(
+        writer,
+        "| Doc coverage | {:.1}% ({}/{}) |",
+        metrics.doc_coverage * 100.0,
+        metrics.documented_items,
+        metrics.items
+    );
+    let _ = writeln!(
+        writer,
+        "| Public dependencies | {} |",
+        metrics.public_dependencies
+    );
+    let _ = writeln!(writer, "| Features | {} |", metrics.features);
+    let _ = writeln!(writer);
+
+    Ok(())
+}
+
+pub fn render_unsafe_markdown(
+    writer: &mut dyn std::io::Write,
+    api: &crate_api::Api,
+    report: &UnsafeReport,
+) -> Result<(), eyre::Report> {
+    let root = api
+        .root_id
+        .and_then(|root_id| api.paths.get(root_id))
+        .map(|root| root.path.as_str())
+        .unwrap_or_default();
+    let _ = writeln!(writer, "# Unsafe API of `{}`", root);
+    let _ = writeln!(writer);
+
+    if report.modules.is_empty() {
+        let _ = writeln!(writer, "*No unsafe API found*");
+        let _ = writeln!(writer);
+    }
+
+    for module in &report.modules {
+        let _ = writeln!(writer, "## `{}`", module.path);
+        let _ = writeln!(writer);
+        for entry in &module.entries {
+            let hazards = entry
+                .hazards
+                .iter()
+                .map(|hazard| hazard.to_string())
+                .collect::<Vec<_>>()
+                .join(", ");
+            let _ = write!(writer, "- `{}` *({})*", entry.path, hazards);
+            if let Some(span) = entry.span.as_ref() {
+                let _ = write!(writer, " at {}:{}", span.filename.display(), span.begin.0);
+            }
+            let _ = writeln!(writer);
+        }
+        let _ = writeln!(writer);
+    }
+
+    Ok(())
+}
+
+pub fn render_ffi_markdown(
+    writer: &mut dyn std::io::Write,
+    report: &FfiReport,
+) -> Result<(), eyre::Report> {
+    let _ = writeln!(writer, "## FFI Symbols");
+    let _ = writeln!(writer);
+
+    if report.symbols.is_empty() {
+        let _ = writeln!(writer, "*No FFI symbols found*");
+        let _ = writeln!(writer);
+    } else {
+        let _ = writeln!(writer, "| Symbol | Path | Declaration |");
+        let _ = writeln!(writer, "|--------|------|-------------|");
+        for symbol in &report.symbols {
+            let _ = writeln!(
+                writer,
+                "| {} | `{}` | `{}` |",
+                symbol
+                    .symbol
+                    .as_deref()
+                    .map(|s| format!("`{}`", s))
+                    .unwrap_or_else(|| "*(mangled)*".to_owned()),
+                symbol.path,
+                symbol.declaration
+            );
+        }
+        let _ = writeln!(writer);
+    }
+
+    if !report.layouts.is_empty() {
+        let _ = writeln!(writer, "## Type Layouts");
+        let _ = writeln!(writer);
+        let _ = writeln!(writer, "| Path | Kind | Representation |");
+        let _ = writeln!(writer, "|------|------|----------------|");
+        for layout in &report.layouts {
+            let _ = writeln!(
+                writer,
+                "| `{}` | {} | `#[repr({})]` |",
+                layout.path,
+                layout.kind,
+                layout.repr.join(", ")
+            );
+        }
+        let _ = writeln!(writer);
+    }
+
+    Ok(())
+}
+
+pub fn render_api_by_owner_markdown(
+    writer: &mut dyn std::io::Write,
+    api: &crate_api::Api,
+    groups: &std::collections::BTreeMap<String, Vec<OwnedPath>>,
+) -> Result<(), eyre::Report> {
+    if let Some(root) = api.root_id.and_then(|root_id| api.paths.get(root_id)) {
+        let _ = writeln!(writer, "# `{}`", root.path);
+        let _ = writeln!(writer);
+    }
+
+    for (owner, paths) in groups {
+        let _ = writeln!(writer, "## {}", owner);
+        let _ = writeln!(writer);
+        for path in paths {
+            let _ = writeln!(writer, "- `{}` *({})*", path.path, path.kind);
+        }
+        let _ = writeln!(writer);
+    }
+
+    Ok(())
+}
+
+pub fn render_diff_by_owner_markdown(
+    writer: &mut dyn std::io::Write,
+    before: &crate_api::Api,
+    after: &crate_api::Api,
+    groups: &std::collections::BTreeMap<String, Vec<crate_api::diff::Diff>>,
+) -> Result<(), eyre::Report> {
+    for (owner, diffs) in groups {
+        let _ = writeln!(writer, "# {}", owner);
+        let _ = writeln!(writer);
+        render_diff_markdown(writer, before, after, diffs)?;
+        let _ = writeln!(writer);
+    }
+
+    Ok(())
+}
+
+pub fn render_breaking_markdown(
+    writer: &mut dyn std::io::Write,
+    report: &Breaking,
+) -> Result<(), eyre::Report> {
+    let _ = writeln!(writer, "## Breaking Changes since `{}`", report.against);
+    let _ = writeln!(writer);
+
+    if report.changes.is_empty() {
+        let _ = writeln!(writer, "None");
+        return Ok(());
+    }
+
+    for change in &report.changes {
+        let _ = writeln!(
+            writer,
+            "- `{}`: {} ({})",
+            change.path, change.diff.id.explanation, change.diff.id.name
+        );
+        match &change.introduced_by {
+            Some(attribution) => {
+                let commit = attribution.commit.get(..7).unwrap_or(&attribution.commit);
+                let _ = write!(writer, "  - {} by {}", commit, attribution.author);
+                if let Some(email) = &attribution.email {
+                    let _ = write!(writer, " <{}>", email);
+                }
+                if let Some(summary) = &attribution.summary {
+                    let _ = write!(writer, ": {}", summary);
+                }
+                let _ = writeln!(writer);
+            }
+            None => {
+                let _ = writeln!(writer, "  - *(unattributed)*");
+            }
+        }
+    }
+
+    Ok(())
+}
+
+pub fn render_release_markdown(
+    writer: &mut dyn std::io::Write,
+    report: &Release,
+) -> Result<(), eyre::Report> {
+    let _ = writeln!(
+        writer,
+        "## Release {} since `{}` ({})",
+        report.after, report.against, report.before
+    );
+    let _ = writeln!(writer);
+
+    let next = crate_api::diff::rules::next_version(&report.before, report.required);
+    match report.bumped {
+        _ if report.is_sufficient() => {
+            let _ = writeln!(
+                writer,
+                "The API changes require a {} bump, like {}, which this is",
+                report.required, next
+            );
+        }
+        Some(bumped) => {
+            let _ = writeln!(
+                writer,
+                "This is a {} bump but the API changes require a {} bump, like {}",
+                bumped, report.required, next
+            );
+        }
+        None => {
+            let _ = writeln!(
+                writer,
+                "This is older than the baseline; the API changes require a {} bump, like {}",
+                report.required, next
+            );
+        }
+    }
+
+    if report.changes.is_empty() {
+        return Ok(());
+    }
+    let _ = writeln!(writer);
+    for change in &report.changes {
+        let _ = writeln!(
+            writer,
+            "- `{}`: {} ({}, [{}]({}))",
+            change.path,
+            change.diff.id.explanation,
+            change.diff.id.name,
+            change.bump,
+            change.reference
+        );
+    }
+
+    Ok(())
+}
+
+pub fn render_bisect_markdown(
+    writer: &mut dyn std::io::Write,
+    report: &Bisect,
+) -> Result<(), eyre::Report> {
+    let change = report
+        .change
+        .as_deref()
+        .map(|change| format!("`{}`", change))
+        .unwrap_or_else(|| "a breaking change".to_owned());
+    let _ = writeln!(
+        writer,
+        "## First commit with {} since `{}`",
+        change, report.good
+    );
+    let _ = writeln!(writer);
+    let first_bad = &report.first_bad;
+    let _ = write!(writer, "- {} by {}", first_bad.commit, first_bad.author);
+    if let Some(email) = &first_bad.email {
+        let _ = write!(writer, " <{}>", email);
+    }
+    let _ = writeln!(writer);
+    if let Some(summary) = &first_bad.summary {
+        let _ = writeln!(writer, "- {}", summary);
+    }
+    let _ = writeln!(writer);
+    let _ = writeln!(
+        writer,
+        "*Checked {} commit(s) between `{}` and `{}`*",
+        report.tested, report.good, report.bad
+    );
+
+    Ok(())
+}
+
+pub fn render_feature_powerset_markdown(
+    writer: &mut dyn std::io::Write,
+    report: &FeaturePowerset,
+) -> Result<(), eyre::Report> {
+    let _ = writeln!(
+        writer,
+        "## {} unique API(s) across {} feature combination(s)",
+        report.unique.len(),
+        report.combinations
+    );
+    let _ = writeln!(writer);
+    for (i, combinations) in report.unique.iter().enumerate() {
+        let combinations: Vec<_> = combinations
+            .iter()
+            .map(|features| render_features(features))
+            .collect();
+        let _ = writeln!(writer, "{}. {}", i + 1, combinations.join(", "));
+    }
+    let _ = writeln!(writer);
+
+    let _ = writeln!(writer, "## Conditional Paths");
+    let _ = writeln!(writer);
+    if report.conditional.is_empty() {
+        let _ = writeln!(writer, "None");
+    }
+    for path in &report.conditional {
+        let requires: Vec<_> = path
+            .requires
+            .iter()
+            .map(|features| render_features(features))
+            .collect();
+        let _ = writeln!(
+            writer,
+            "- `{}` *({})*: {}",
+            path.path,
+            path.kind,
+            requires.join(" or ")
+        );
+    }
+
+    Ok(())
+}
+
+pub fn render_feature_additivity_markdown(
+    writer: &mut dyn std::io::Write,
+    report: &FeatureAdditivity,
+) -> Result<(), eyre::Report> {
+    let _ = writeln!(
+        writer,
+        "## Non-additive Features ({} additions checked)",
+        report.checked
+    );
+    let _ = writeln!(writer);
+    if report.violations.is_empty() {
+        let _ = writeln!(writer, "None");
+    }
+    for violation in &report.violations {
+        let change = match violation.change {
+            AdditivityChange::Removed => "removes",
+            AdditivityChange::Changed => "changes",
+        };
+        let _ = writeln!(
+            writer,
+            "- `{}` {} `{}` *({})* on top of {}",
+            violation.feature,
+            change,
+            violation.path,
+            violation.kind,
+            render_features(&violation.base)
+        );
+    }
+
+    Ok(())
+}
+
+pub fn render_upgrade_guide_markdown(
+    writer: &mut dyn std::io::Write,
+    guide: &UpgradeGuide,
+) -> Result<(), eyre::Report> {
+    let _ = writeln!(
+        writer,
+        "# Upgrading `{}` from `{}` to {}",
+        guide.name,
+        guide.against,
+        guide.version.as_deref().unwrap_or("the latest version")
+    );
+    let _ = writeln!(writer);
+    let _ = writeln!(writer, "TODO: summarize what changed and why");
+    let _ = writeln!(writer);
+
+    if !guide.removed.is_empty() {
+        let _ = writeln!(writer, "## Removed");
+        let _ = writeln!(writer);
+        let _ = writeln!(writer, "| Item | Replacement |");
+        let _ = writeln!(writer, "|------|-------------|");
+        for removed in &guide.removed {
+            let replacement = removed
+                .replacement
+                .as_deref()
+                .map(|replacement| format!("`{}`", replacement))
+                .unwrap_or_else(|| "TODO".to_owned());
+            let _ = writeln!(
+                writer,
+                "| `{}` *({})* | {} |",
+                removed.path, removed.kind, replacement
+            );
+        }
+        let _ = writeln!(writer);
+    }
+
+    if !guide.changed.is_empty() {
+        let _ = writeln!(writer, "## Changed");
+        let _ = writeln!(writer);
+        for changed in &guide.changed {
+            let _ = writeln!(writer, "### `{}` *({})*", changed.path, changed.kind);
+            let _ = writeln!(writer);
+            if changed.before.is_some() || changed.after.is_some() {
+                let _ = writeln!(writer, "```diff");
+                if let Some(before) = &changed.before {
+                    let _ = writeln!(writer, "-{}", before);
+                }
+                if let Some(after) = &changed.after {
+                    let _ = writeln!(writer, "+{}", after);
+                }
+                let _ = writeln!(writer, "```");
+                let _ = writeln!(writer);
+            }
+            let _ = writeln!(writer, "TODO: explain how to migrate");
+            let _ = writeln!(writer);
+        }
+    }
+
+    if !guide.dependencies.is_empty() {
+        let _ = writeln!(writer, "## Dependencies");
+        let _ = writeln!(writer);
+        for dependency in &guide.dependencies {
+            let _ = writeln!(writer, "- {}", dependency);
+        }
+        let _ = writeln!(writer);
+    }
+
+    Ok(())
+}
+
+pub fn render_history_markdown(
+    writer: &mut dyn std::io::Write,
+    history: &History,
+) -> Result<(), eyre::Report> {
+    let _ = writeln!(
+        writer,
+        "# `{}` API history ({})",
+        history.name,
+        history.versions.join(", ")
+    );
+    let _ = writeln!(writer);
+    let _ = writeln!(writer, "| Path | Kind | Added | Deprecated | Removed |");
+    let _ = writeln!(writer, "|------|------|-------|------------|---------|");
+    for path in &history.paths {
+        let _ = writeln!(
+            writer,
+            "| `{}` | {} | {} | {} | {} |",
+            path.path,
+            path.kind,
+            path.added,
+            path.deprecated.as_deref().unwrap_or(""),
+            path.removed.as_deref().unwrap_or("")
+        );
+    }
+
+    Ok(())
+}
+
+/// Badges use rustdoc's styling so they can be pasted into doc comments
+pub fn render_since_markdown(
+    writer: &mut dyn std::io::Write,
+    report: &Since,
+) -> Result<(), eyre::Report> {
+    for path in &report.paths {
+        let since = match &path.since {
+            Some(version) => format!("since {}", version),
+            None => "unreleased".to_owned(),
+        };
+        let _ = writeln!(
+            writer,
+            "- `{}` *({})*: <span class=\"stab portability\">{}</span>",
+            path.path, path.kind, since
+        );
+    }
+
+    Ok(())
+}
+
+pub fn render_compatibility_markdown(
+    writer: &mut dyn std::io::Write,
+    report: &Compatibility,
+) -> Result<(), eyre::Report> {
+    let total = report.shared.len() + report.missing.len() + report.differing.len();
+    let _ = writeln!(
+        writer,
+        "## `{}` compared to `{}`: {} of {} path(s) shared",
+        report.name,
+        report.other,
+        report.shared.len(),
+        total
+    );
+    let _ = writeln!(writer);
+    for (title, paths) in [
+        ("Missing", &report.missing),
+        ("Differing", &report.differing),
+        ("Extra", &report.extra),
+    ] {
+        if paths.is_empty() {
+            continue;
+        }
+        let _ = writeln!(writer, "**{}**", title);
+        for path in paths {
+            let _ = writeln!(writer, "- `{}` *({})*", path.path, path.kind);
+        }
+        let _ = writeln!(writer);
+    }
+
+    Ok(())
+}
+
+fn render_features(features: &[String]) -> String {
+    if features.is_empty() {
+        "*(no features)*".to_owned()
+    } else {
+        features
+            .iter()
+            .map(|f| format!("`{}`", f))
+            .collect::<Vec<_>>()
+            .join(" + ")
+    }
+}
+
+/// Where a change lives in the source, preferring the path's declaration over the item's
+pub fn location_span(
+    api: &crate_api::Api,
+    location: crate_api::diff::Location,
+) -> Option<&crate_api::Span> {
+    let path = location
+        .path_id
+        .map(|path_id| api.paths.get(path_id).unwrap());
+    let item = location
+        .item_id
+        .or_else(|| path.and_then(|path| path.item_id))
+        .map(|item_id| api.items.get(item_id).unwrap());
+    let impl_ = location
+        .impl_id
+        .map(|impl_id| api.impls.get(impl_id).unwrap());
+    path.and_then(|path| path.span.as_ref())
+        .or_else(|| item.and_then(|item| item.span.as_ref()))
+        .or_else(|| impl_.and_then(|impl_| impl_.span.as_ref()))
+}
+
+fn experimental_badge(path: &crate_api::Path) -> &'static str {
+    if path.is_experimental {
+        " *(experimental)*"
+    } else {
+        ""
+    }
+}
+
+fn location_badge(
+    api: &crate_api::Api,
+    location: Option<crate_api::diff::Location>,
+) -> &'static str {
+    location
+        .and_then(|location| location.path_id)
+        .and_then(|path_id| api.paths.get(path_id))
+        .map(experimental_badge)
+        .unwrap_or_default()
+}
+
+fn location_path(api: &crate_api::Api, location: crate_api::diff::Location) -> Option<&str> {
+    location
+        .path_id
+        .map(|path_id| api.paths.get(path_id).unwrap().path.as_str())
+}
+
+/// Where a change is best described, like the old path of a removed item
+pub fn diff_location<'a>(
+    before: &'a crate_api::Api,
+    after: &'a crate_api::Api,
+    diff: &crate_api::diff::Diff,
+) -> (&'a crate_api::Api, crate_api::diff::Location) {
+    match (diff.id.category, diff.before, diff.after) {
+        (crate_api::diff::Category::Removed, Some(location), _) => (before, location),
+        (_, _, Some(location)) => (after, location),
+        (_, Some(location), None) => (before, location),
+        (_, None, None) => unreachable!("{:?} had no location", diff),
+    }
+}
+
+pub fn location_name(
+    api: &crate_api::Api,
+    location: crate_api::diff::Location,
+) -> std::borrow::Cow<'_, str> {
+    if let Some(impl_id) = location.impl_id {
+        api.impls.get(impl_id).unwrap().to_string().into()
+    } else if let Some(path_id) = location.path_id {
+        let path = api.paths.get(path_id).unwrap();
+        // Items without a path of their own, like trait items, are named under their parent's
+        let member = location
+            .item_id
+            .filter(|item_id| Some(*item_id) != path.item_id)
+            .and_then(|item_id| api.items.get(item_id))
+            .and_then(|item| item.name.as_deref());
+        match member {
+            Some(member) => format!("{}::{}", path.path, member).into(),
+            None => path.path.as_str().into(),
+        }
+    } else if let Some(item_id) = location.item_id {
+        api.items
+            .get(item_id)
+            .unwrap()
+            .name
+            .as_deref()
+            .unwrap()
+            .into()
+    } else if let Some(crate_id) = location.crate_id {
+        api.crates.get(crate_id).unwrap().name.as_str().into()
+    } else {
+        unimplemented!("{:?} had no location", location)
+    }
+}
+
+/// Write one row per path, for loading into spreadsheets
+pub fn render_api_csv(
+    writer: &mut dyn std::io::Write,
+    api: &crate_api::Api,
+    delimiter: u8,
+) -> Result<(), eyre::Report> {
+    let root_crate = api
+        .root_id
+        .and_then(|root_id| api.paths.get(root_id))
+        .map(|root| root.path.as_str())
+        .unwrap_or_default();
+
+    let mut paths: Vec<_> = api.paths.iter().map(|(_, path)| path).collect();
+    paths.sort_unstable_by_key(|path| (path.path.as_str(), path.kind));
+
+    let mut csv = csv::WriterBuilder::new()
+        .delimiter(delimiter)
+        .from_writer(writer);
+    csv.write_record([
+        "path",
+        "kind",
+        "crate",
+        "features",
+        "deprecated",
+        "unsafe",
+        "span",
+    ])?;
+    for path in paths {
+        let item = path.item_id.map(|item_id| api.items.get(item_id).unwrap());
+        let crate_name = path
+            .crate_id
+            .map(|crate_id| api.crates.get(crate_id).unwrap().name.as_str())
+            .unwrap_or(root_crate);
+        let features = item.map(|item| item.features.join(" ")).unwrap_or_default();
+        let deprecated = item
+            .and_then(|item| item.deprecated.as_ref())
+            .map(|deprecated| deprecated.since.clone().unwrap_or_else(|| "yes".to_owned()))
+            .unwrap_or_default();
+        let is_unsafe = item.map(|item| item.is_unsafe).unwrap_or(false);
+        let span = path
+            .span
+            .as_ref()
+            .map(|span| {
+                format!(
+                    "{}:{}:{}",
+                    span.filename.display(),
+                    span.begin.0,
+                    span.begin.1 + 1
+                )
+            })
+            .unwrap_or_default();
+        csv.write_record([
+            path.path.as_str(),
+            path.kind.as_str(),
+            crate_name,
+            &features,
+            &deprecated,
+            if is_unsafe { "true" } else { "false" },
+            &span,
+        ])?;
+    }
+    csv.flush()?;
+
+    Ok(())
+}
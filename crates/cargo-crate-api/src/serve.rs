@@ -0,0 +1,299 @@
+//! Answer queries over JSON-RPC 2.0, keeping extracted APIs in memory between requests
+//!
+//! Each request and response is one line of JSON.  Methods:
+//! - `lookup {package?, path}`: the paths named `path`, with their items
+//! - `search {package?, query}`: paths containing `query` or matching it as a glob
+//! - `diff {package?, base?}`: changes against `base` (`{"git": REF}`, `{"path": TOML}`, or
+//!   `{"registry": VERSION}`), defaulting to the latest tag
+//! - `lint {package?, base?}`: like `diff` but only the breaking changes
+//! - `reload {package?}`: forget what was extracted, like after editing the source
+//! - `shutdown`
+//!
+//! `package` may be left out when only one package is selected.
+
+use std::io::BufRead;
+use std::io::Write;
+
+const PARSE_ERROR: i64 = -32700;
+const INVALID_REQUEST: i64 = -32600;
+const METHOD_NOT_FOUND: i64 = -32601;
+const INVALID_PARAMS: i64 = -32602;
+/// Failures running the query, with the [`ErrorCode`][crate::error::ErrorCode] as `data`
+const QUERY_FAILED: i64 = -32000;
+
+#[derive(serde::Deserialize)]
+struct Request {
+    #[serde(default)]
+    id: serde_json::Value,
+    method: String,
+    #[serde(default)]
+    params: serde_json::Value,
+}
+
+#[derive(Default, serde::Deserialize)]
+#[serde(default)]
+struct Params {
+    package: Option<String>,
+    path: Option<String>,
+    query: Option<String>,
+    base: Option<crate::report::Source>,
+}
+
+struct RpcError {
+    code: i64,
+    message: String,
+    data: Option<serde_json::Value>,
+}
+
+impl RpcError {
+    fn new(code: i64, message: impl std::fmt::Display) -> Self {
+        Self {
+            code,
+            message: message.to_string(),
+            data: None,
+        }
+    }
+}
+
+impl From<eyre::Report> for RpcError {
+    fn from(err: eyre::Report) -> Self {
+        Self {
+            code: QUERY_FAILED,
+            message: err.to_string(),
+            data: serde_json::to_value(crate::error::code_of(&err)).ok(),
+        }
+    }
+}
+
+pub struct Server<'m> {
+    rustdoc: &'m crate_api::RustDocBuilder,
+    metadata: &'m cargo_metadata::Metadata,
+    packages: Vec<&'m cargo_metadata::Package>,
+    docs_rs: bool,
+    apis: std::collections::HashMap<String, crate_api::Api>,
+    diffs: std::collections::HashMap<(String, crate::report::Source), crate::Loaded>,
+}
+
+impl<'m> Server<'m> {
+    pub fn new(
+        rustdoc: &'m crate_api::RustDocBuilder,
+        metadata: &'m cargo_metadata::Metadata,
+        packages: Vec<&'m cargo_metadata::Package>,
+        docs_rs: bool,
+    ) -> Self {
+        Self {
+            rustdoc,
+            metadata,
+            packages,
+            docs_rs,
+            apis: Default::default(),
+            diffs: Default::default(),
+        }
+    }
+
+    /// Answer requests until `shutdown` or the end of `input`
+    ///
+    /// Returns whether `shutdown` was requested.
+    pub fn serve(
+        &mut self,
+        input: impl BufRead,
+        mut output: impl Write,
+    ) -> Result<bool, eyre::Report> {
+        for line in input.lines() {
+            let line = line?;
+            if line.trim().is_empty() {
+                continue;
+            }
+            let (id, method, result) = match serde_json::from_str::<serde_json::Value>(&line) {
+                Ok(request) => match serde_json::from_value::<Request>(request) {
+                    Ok(request) => {
+                        let result = self.handle(&request.method, request.params);
+                        (request.id, Some(request.method), result)
+                    }
+                    Err(err) => (
+                        serde_json::Value::Null,
+                        None,
+                        Err(RpcError::new(INVALID_REQUEST, err)),
+                    ),
+                },
+                Err(err) => (
+                    serde_json::Value::Null,
+                    None,
+                    Err(RpcError::new(PARSE_ERROR, err)),
+                ),
+            };
+
+            let response = match result {
+                Ok(result) => serde_json::json!({"jsonrpc": "2.0", "id": id, "result": result}),
+                Err(err) => serde_json::json!({
+                    "jsonrpc": "2.0",
+                    "id": id,
+                    "error": {"code": err.code, "message": err.message, "data": err.data},
+                }),
+            };
+            writeln!(output, "{}", response)?;
+            output.flush()?;
+
+            if method.as_deref() == Some("shutdown") {
+                return Ok(true);
+            }
+        }
+        Ok(false)
+    }
+
+    fn handle(
+        &mut self,
+        method: &str,
+        params: serde_json::Value,
+    ) -> Result<serde_json::Value, RpcError> {
+        let params: Params = if params.is_null() {
+            Params::default()
+        } else {
+            serde_json::from_value(params).map_err(|err| RpcError::new(INVALID_PARAMS, err))?
+        };
+        let _span = tracing::debug_span!("request", method).entered();
+        match method {
+            "lookup" => {
+                let path = params
+                    .path
+                    .as_deref()
+                    .ok_or_else(|| RpcError::new(INVALID_PARAMS, "`path` is required"))?;
+                let api = self.api(params.package.as_deref())?;
+                let found: Vec<_> = api
+                    .paths
+                    .iter()
+                    .filter(|(_, candidate)| candidate.path == path)
+                    .map(|(_, found)| {
+                        let item = found.item_id.and_then(|item_id| api.items.get(item_id));
+                        serde_json::json!({"path": found, "item": item})
+                    })
+                    .collect();
+                Ok(serde_json::Value::Array(found))
+            }
+            "search" => {
+                let query = params
+                    .query
+                    .as_deref()
+                    .ok_or_else(|| RpcError::new(INVALID_PARAMS, "`query` is required"))?;
+                let glob = crate_api::PathGlob::new(query);
+                let lowercase = query.to_lowercase();
+                let api = self.api(params.package.as_deref())?;
+                let mut found: Vec<_> = api
+                    .paths
+                    .iter()
+                    .filter(|(_, candidate)| {
+                        glob.matches(&candidate.path)
+                            || candidate.path.to_lowercase().contains(&lowercase)
+                    })
+                    .map(|(_, found)| (found.path.as_str(), found.kind))
+                    .collect();
+                found.sort_unstable();
+                found.dedup();
+                let found: Vec<_> = found
+                    .into_iter()
+                    .map(|(path, kind)| serde_json::json!({"path": path, "kind": kind}))
+                    .collect();
+                Ok(serde_json::Value::Array(found))
+            }
+            "diff" | "lint" => {
+                let (before, after, diffs) =
+                    self.diff(params.package.as_deref(), params.base.clone())?;
+                let changes: Vec<_> = diffs
+                    .iter()
+                    .filter(|diff| {
+                        method == "diff" || diff.severity == crate_api::diff::Severity::Warn
+                    })
+                    .filter(|diff| diff.severity != crate_api::diff::Severity::Allow)
+                    .map(|diff| {
+                        serde_json::json!({
+                            "id": diff.id.name,
+                            "severity": diff.severity,
+                            "explanation": diff.id.explanation,
+                            "before": diff.before.map(|location| crate::report::location_name(before, location)),
+                            "after": diff.after.map(|location| crate::report::location_name(after, location)),
+                            "confidence": diff.confidence,
+                        })
+                    })
+                    .collect();
+                Ok(serde_json::Value::Array(changes))
+            }
+            "reload" => {
+                match params.package.as_deref() {
+                    Some(package) => {
+                        self.apis.remove(package);
+                        self.diffs.retain(|(name, _), _| name != package);
+                    }
+                    None => {
+                        self.apis.clear();
+                        self.diffs.clear();
+                    }
+                }
+                Ok(serde_json::Value::Null)
+            }
+            "shutdown" => Ok(serde_json::Value::Null),
+            _ => Err(RpcError::new(
+                METHOD_NOT_FOUND,
+                format!("Unknown method `{}`", method),
+            )),
+        }
+    }
+
+    fn package(&self, name: Option<&str>) -> Result<&'m cargo_metadata::Package, RpcError> {
+        match name {
+            Some(name) => self
+                .packages
+                .iter()
+                .find(|pkg| pkg.name == name)
+                .copied()
+                .ok_or_else(|| {
+                    RpcError::new(
+                        INVALID_PARAMS,
+                        format!("`{}` is not a selected package", name),
+                    )
+                }),
+            None => match self.packages.as_slice() {
+                [pkg] => Ok(*pkg),
+                _ => Err(RpcError::new(
+                    INVALID_PARAMS,
+                    "`package` is required with multiple packages",
+                )),
+            },
+        }
+    }
+
+    fn api(&mut self, package: Option<&str>) -> Result<&crate_api::Api, RpcError> {
+        let pkg = self.package(package)?;
+        if !self.apis.contains_key(&pkg.name) {
+            let mut api = self
+                .rustdoc
+                .clone()
+                .into_api(pkg.manifest_path.as_path().as_std_path())
+                .map_err(eyre::Report::new)?;
+            crate_api::manifest::Manifest::from(pkg).into_api(&mut api);
+            crate::config::PackageConfig::from_package(pkg)?.mark_experimental(&mut api);
+            self.apis.insert(pkg.name.clone(), api);
+        }
+        Ok(&self.apis[&pkg.name])
+    }
+
+    fn diff(
+        &mut self,
+        package: Option<&str>,
+        base: Option<crate::report::Source>,
+    ) -> Result<(&crate_api::Api, &crate_api::Api, &[crate_api::diff::Diff]), RpcError> {
+        let pkg = self.package(package)?;
+        let base = match base {
+            Some(base) => base,
+            None => crate::find_default_base(self.metadata.workspace_root.as_std_path()).map_err(
+                |err| crate::error::coded(crate::error::ErrorCode::BaselineUnavailable, err),
+            )?,
+        };
+        let key = (pkg.name.clone(), base);
+        if !self.diffs.contains_key(&key) {
+            let loaded = crate::load_diff(self.rustdoc, self.metadata, pkg, &key.1, self.docs_rs)?;
+            self.diffs.insert(key.clone(), loaded);
+        }
+        let (before, after, diffs) = &self.diffs[&key];
+        Ok((before, after, diffs))
+    }
+}
@@ -0,0 +1,25 @@
+//! User-provided [Tera](https://keats.github.io/tera/) report templates
+//!
+//! The template is rendered with the same `mode` and `data` a [plugin][crate::plugin] receives.
+
+pub fn render<D: serde::Serialize>(
+    template: &std::path::Path,
+    mode: &'static str,
+    data: &D,
+) -> Result<String, eyre::Report> {
+    let content = std::fs::read_to_string(template)
+        .map_err(|e| eyre::eyre!("Failed when reading {}: {}", template.display(), e))?;
+    let context = tera::Context::from_serialize(&crate::plugin::Request { mode, data })?;
+    // Reports aren't HTML, so leave escaping to the template
+    tera::Tera::one_off(&content, &context, false).map_err(|e| {
+        // Tera's top-level error only says rendering failed, the cause is in the source chain
+        let mut message = e.to_string();
+        let mut source = std::error::Error::source(&e);
+        while let Some(cause) = source {
+            message.push_str(": ");
+            message.push_str(&cause.to_string());
+            source = cause.source();
+        }
+        eyre::eyre!("Failed when rendering {}: {}", template.display(), message)
+    })
+}
@@ -0,0 +1,184 @@
+//! Find the paths a crate's source uses from one of its dependencies
+//!
+//! This is a textual scan rather than name resolution, so glob imports and re-exports through
+//! other crates are not followed.
+
+use std::collections::BTreeSet;
+
+/// Paths under `lib_name` referenced by `.rs` files within `root`
+pub fn used_paths(
+    root: &std::path::Path,
+    lib_name: &str,
+) -> Result<BTreeSet<String>, eyre::Report> {
+    let mut used = BTreeSet::new();
+    let mut pending = vec![root.to_owned()];
+    while let Some(dir) = pending.pop() {
+        let entries = std::fs::read_dir(&dir)
+            .map_err(|e| eyre::eyre!("Failed when reading {}: {}", dir.display(), e))?;
+        for entry in entries {
+            let entry = entry?;
+            let path = entry.path();
+            let name = entry.file_name();
+            let name = name.to_string_lossy();
+            if entry.file_type()?.is_dir() {
+                if name != "target" && !name.starts_with('.') {
+                    pending.push(path);
+                }
+            } else if name.ends_with(".rs") {
+                let source = std::fs::read_to_string(&path)
+                    .map_err(|e| eyre::eyre!("Failed when reading {}: {}", path.display(), e))?;
+                scan(&source, lib_name, &mut used);
+            }
+        }
+    }
+    Ok(used)
+}
+
+/// Whether a change to `changed` can affect code using `used`
+pub fn affects(used: &BTreeSet<String>, changed: &str) -> bool {
+    used.iter()
+        .any(|used| used == changed || is_ancestor(used, changed) || is_ancestor(changed, used))
+}
+
+fn is_ancestor(ancestor: &str, path: &str) -> bool {
+    path.strip_prefix(ancestor)
+        .map(|rest| rest.starts_with("::"))
+        .unwrap_or(false)
+}
+
+fn scan(source: &str, lib_name: &str, used: &mut BTreeSet<String>) {
+    let mut remaining = source;
+    while let Some(start) = remaining.find(lib_name) {
+        let is_boundary = remaining[..start]
+            .chars()
+            .next_back()
+            .map(|c| !is_ident_char(c) && c != ':')
+            .unwrap_or(true);
+        let rest = &remaining[start + lib_name.len()..];
+        remaining = rest;
+        if !is_boundary {
+            continue;
+        }
+        if let Some(rest) = rest.trim_start().strip_prefix("::") {
+            parse_tree(lib_name, rest, used);
+        }
+    }
+}
+
+/// Expand the remainder of a path, including `use` trees like `{a, b::{c, d}}`
+fn parse_tree<'s>(prefix: &str, source: &'s str, used: &mut BTreeSet<String>) -> &'s str {
+    let source = source.trim_start();
+    if let Some(mut group) = source.strip_prefix('{') {
+        loop {
+            group = parse_tree(prefix, group, used).trim_start();
+            if let Some(rest) = group.strip_prefix(',') {
+                group = rest;
+            } else if let Some(rest) = group.strip_prefix('}') {
+                return rest;
+            } else {
+                return group;
+            }
+        }
+    }
+
+    let end = source
+        .find(|c: char| !is_ident_char(c))
+        .unwrap_or(source.len());
+    let (segment, rest) = source.split_at(end);
+    let path = match segment {
+        "" => {
+            // Glob import or something we don't understand
+            used.insert(prefix.to_owned());
+            return rest.strip_prefix('*').unwrap_or(rest);
+        }
+        "self" => prefix.to_owned(),
+        segment => format!("{}::{}", prefix, segment),
+    };
+    match rest.trim_start().strip_prefix("::") {
+        Some(rest) => parse_tree(&path, rest, used),
+        None => {
+            used.insert(path);
+            // Skip renames, like `Foo as Bar`
+            let rest = rest.trim_start();
+            match rest.strip_prefix("as ") {
+                Some(rest) => {
+                    let rest = rest.trim_start();
+                    let end = rest.find(|c: char| !is_ident_char(c)).unwrap_or(rest.len());
+                    &rest[end..]
+                }
+                None => rest,
+            }
+        }
+    }
+}
+
+fn is_ident_char(c: char) -> bool {
+    c.is_alphanumeric() || c == '_'
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn scanned(source: &str) -> Vec<String> {
+        let mut used = BTreeSet::new();
+        scan(source, "serde", &mut used);
+        used.into_iter().collect()
+    }
+
+    #[test]
+    fn scan_use() {
+        assert_eq!(scanned("use serde::Serialize;"), ["serde::Serialize"]);
+        assert_eq!(
+            scanned("use serde::de::{self, Visitor, value::{Error as ValueError}};"),
+            ["serde::de", "serde::de::Visitor", "serde::de::value::Error"]
+        );
+        assert_eq!(scanned("use serde::de::*;"), ["serde::de"]);
+    }
+
+    #[test]
+    fn scan_qualified() {
+        assert_eq!(
+            scanned("fn f() -> serde :: de :: IgnoredAny { serde::de::IgnoredAny }"),
+            ["serde::de::IgnoredAny"]
+        );
+    }
+
+    #[test]
+    fn scan_boundaries() {
+        assert!(scanned("use my_serde::Serialize; use other::serde::Serialize;").is_empty());
+        assert!(scanned("let serde = 5;").is_empty());
+    }
+
+    #[test]
+    fn used_paths_walks_sources() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::create_dir_all(dir.path().join("src/nested")).unwrap();
+        std::fs::create_dir_all(dir.path().join("target")).unwrap();
+        std::fs::write(dir.path().join("src/lib.rs"), "use serde::Serialize;").unwrap();
+        std::fs::write(
+            dir.path().join("src/nested/mod.rs"),
+            "use serde::Deserialize;",
+        )
+        .unwrap();
+        std::fs::write(dir.path().join("src/notes.txt"), "use serde::Ignored;").unwrap();
+        std::fs::write(dir.path().join("target/gen.rs"), "use serde::Generated;").unwrap();
+
+        let used: Vec<_> = used_paths(dir.path(), "serde")
+            .unwrap()
+            .into_iter()
+            .collect();
+        assert_eq!(used, ["serde::Deserialize", "serde::Serialize"]);
+    }
+
+    #[test]
+    fn affects_related_paths() {
+        let used: BTreeSet<_> = ["serde::de::Visitor".to_owned()].into_iter().collect();
+        assert!(affects(&used, "serde::de::Visitor"));
+        // Changes to what the used path is in, or to its members
+        assert!(affects(&used, "serde::de"));
+        assert!(affects(&used, "serde::de::Visitor::visit_str"));
+        assert!(!affects(&used, "serde::de::VisitorExt"));
+        assert!(!affects(&used, "serde::ser"));
+    }
+}
@@ -0,0 +1,212 @@
+//! Re-extract the API whenever a package's source changes, reporting what changed since the last
+//! extraction
+
+use std::io::Write;
+
+/// How long to wait for more events, so one save is one extraction
+const DEBOUNCE: std::time::Duration = std::time::Duration::from_millis(500);
+
+pub struct Watcher<'m> {
+    rustdoc: &'m crate_api::RustDocBuilder,
+    metadata: &'m cargo_metadata::Metadata,
+    packages: Vec<&'m cargo_metadata::Package>,
+    format: crate::args::Format,
+    error_format: crate::args::ErrorFormat,
+    apis: std::collections::HashMap<String, crate_api::Api>,
+}
+
+impl<'m> Watcher<'m> {
+    pub fn new(
+        rustdoc: &'m crate_api::RustDocBuilder,
+        metadata: &'m cargo_metadata::Metadata,
+        packages: Vec<&'m cargo_metadata::Package>,
+        format: crate::args::Format,
+        error_format: crate::args::ErrorFormat,
+    ) -> Self {
+        Self {
+            rustdoc,
+            metadata,
+            packages,
+            format,
+            error_format,
+            apis: Default::default(),
+        }
+    }
+
+    /// Extract every package, then again as their files change, until the watch fails
+    pub fn watch(&mut self) -> Result<(), eyre::Report> {
+        match self.format {
+            crate::args::Format::Silent
+            | crate::args::Format::Pretty
+            | crate::args::Format::Json => {}
+            unsupported @ (crate::args::Format::Md
+            | crate::args::Format::MdChangelog
+            | crate::args::Format::Github
+            | crate::args::Format::Sarif
+            | crate::args::Format::Html
+            | crate::args::Format::Csv
+            | crate::args::Format::Tsv) => {
+                return Err(crate::error::unsupported_format(unsupported, "--watch"));
+            }
+        }
+
+        let (tx, rx) = std::sync::mpsc::channel();
+        let mut watcher = notify::watcher(tx, DEBOUNCE)?;
+        for pkg in self.packages.clone() {
+            for (path, mode) in self.watched(pkg)? {
+                tracing::debug!(path = %path.display(), ?mode, "watching");
+                notify::Watcher::watch(&mut watcher, &path, mode)
+                    .map_err(|e| eyre::eyre!("Failed when watching {}: {}", path.display(), e))?;
+            }
+            self.extract(pkg);
+        }
+        ::log::info!("Watching {} package(s) for changes", self.packages.len());
+
+        loop {
+            let event = rx.recv()?;
+            let mut changed = Vec::new();
+            for event in std::iter::once(event).chain(rx.try_iter()) {
+                match event {
+                    notify::DebouncedEvent::Create(path)
+                    | notify::DebouncedEvent::Write(path)
+                    | notify::DebouncedEvent::Remove(path) => changed.push(path),
+                    notify::DebouncedEvent::Rename(from, to) => {
+                        changed.push(from);
+                        changed.push(to);
+                    }
+                    notify::DebouncedEvent::Rescan => {
+                        changed.extend(self.packages.iter().map(|pkg| root(pkg).to_owned()));
+                    }
+                    notify::DebouncedEvent::Error(err, path) => {
+                        ::log::warn!("Watch failed for {:?}: {}", path, err);
+                    }
+                    notify::DebouncedEvent::NoticeWrite(_)
+                    | notify::DebouncedEvent::NoticeRemove(_)
+                    | notify::DebouncedEvent::Chmod(_) => {}
+                }
+            }
+            for pkg in self.packages.clone() {
+                if changed.iter().any(|path| path.starts_with(root(pkg))) {
+                    tracing::debug!(package = %pkg.name, "changed");
+                    self.extract(pkg);
+                }
+            }
+        }
+    }
+
+    /// The package's files, leaving out build output so extracting doesn't trigger itself
+    fn watched(
+        &self,
+        pkg: &cargo_metadata::Package,
+    ) -> Result<Vec<(std::path::PathBuf, notify::RecursiveMode)>, eyre::Report> {
+        let root = root(pkg);
+        let target_dir = self.metadata.target_directory.as_std_path();
+        let mut watched = vec![(root.to_owned(), notify::RecursiveMode::NonRecursive)];
+        for entry in std::fs::read_dir(root)? {
+            let entry = entry?;
+            let path = entry.path();
+            let name = entry.file_name();
+            if !entry.file_type()?.is_dir()
+                || name.to_string_lossy().starts_with('.')
+                || name == "target"
+                || path == target_dir
+            {
+                continue;
+            }
+            watched.push((path, notify::RecursiveMode::Recursive));
+        }
+        Ok(watched)
+    }
+
+    /// Extract `pkg`, reporting the changes since the last successful extraction
+    fn extract(&mut self, pkg: &cargo_metadata::Package) {
+        let mut api = match self
+            .rustdoc
+            .clone()
+            .into_api(pkg.manifest_path.as_std_path())
+        {
+            Ok(api) => api,
+            Err(err) => {
+                // Keep the last API, so fixing the error reports against it
+                crate::error::report(self.error_format, Some(&pkg.name), &eyre::Report::new(err));
+                return;
+            }
+        };
+        crate_api::manifest::Manifest::from(pkg).into_api(&mut api);
+        let config = match crate::config::PackageConfig::from_package(pkg) {
+            Ok(config) => config,
+            Err(err) => {
+                crate::error::report(self.error_format, Some(&pkg.name), &err);
+                return;
+            }
+        };
+        config.mark_experimental(&mut api);
+
+        match self.apis.insert(pkg.name.clone(), api) {
+            Some(before) => {
+                let after = &self.apis[&pkg.name];
+                let mut diffs = Vec::new();
+                crate_api::diff::diff(&before, after, &mut diffs);
+                config.filter_diffs(&before, after, &mut diffs);
+                diffs.retain(|diff| diff.severity != crate_api::diff::Severity::Allow);
+                if let Err(err) = self.render(pkg, &before, after, &diffs) {
+                    crate::error::report(self.error_format, Some(&pkg.name), &err);
+                }
+            }
+            None => {
+                let api = &self.apis[&pkg.name];
+                ::log::info!("{}: extracted {} path(s)", pkg.name, api.paths.len());
+            }
+        }
+    }
+
+    fn render(
+        &self,
+        pkg: &cargo_metadata::Package,
+        before: &crate_api::Api,
+        after: &crate_api::Api,
+        diffs: &[crate_api::diff::Diff],
+    ) -> Result<(), eyre::Report> {
+        match self.format {
+            crate::args::Format::Silent => {}
+            crate::args::Format::Pretty => {
+                let stdout = crate::output::stdout();
+                let mut stdout = stdout.lock();
+                let _ = writeln!(stdout, "{}: {} change(s)", pkg.name, diffs.len());
+                crate::report::render_diff_compact(&mut stdout, before, after, diffs)?;
+            }
+            crate::args::Format::Json => {
+                let changes: Vec<_> = diffs
+                    .iter()
+                    .map(|diff| {
+                        serde_json::json!({
+                            "id": diff.id.name,
+                            "severity": diff.severity,
+                            "before": diff.before.map(|location| crate::report::location_name(before, location)),
+                            "after": diff.after.map(|location| crate::report::location_name(after, location)),
+                        })
+                    })
+                    .collect();
+                let report = serde_json::json!({"package": pkg.name, "changes": changes});
+                let _ = writeln!(crate::output::stdout(), "{}", report);
+            }
+            crate::args::Format::Md
+            | crate::args::Format::MdChangelog
+            | crate::args::Format::Github
+            | crate::args::Format::Sarif
+            | crate::args::Format::Html
+            | crate::args::Format::Csv
+            | crate::args::Format::Tsv => {
+                unreachable!("rejected before watching")
+            }
+        }
+        Ok(())
+    }
+}
+
+fn root(pkg: &cargo_metadata::Package) -> &std::path::Path {
+    pkg.manifest_path
+        .as_std_path()
+        .parent()
+        .expect("manifests are always in a directory")
+}
@@ -7,12 +7,131 @@ pub struct Api {
     pub items: Items,
     pub crates: Crates,
     pub features: std::collections::BTreeMap<String, AnyFeature>,
+    /// Trait impls, including derived ones, which downstream code can rely on
+    #[serde(default)]
+    pub impls: Impls,
+    /// Minimum supported Rust version, from `package.rust-version`, like `1.60`
+    #[serde(default)]
+    pub rust_version: Option<String>,
+    /// Rust edition of the package, like `2021`
+    #[serde(default)]
+    pub edition: Option<String>,
 }
 
 impl Api {
     pub fn new() -> Self {
         Self::default()
     }
+
+    /// The trait impls of the type at `item_id`, including derived ones like `Clone`
+    pub fn trait_impls(&self, item_id: ItemId) -> impl Iterator<Item = (ImplId, &Impl)> + '_ {
+        self.items
+            .get(item_id)
+            .into_iter()
+            .flat_map(|item| item.trait_impls.iter())
+            .filter_map(|impl_id| Some((*impl_id, self.impls.get(*impl_id)?)))
+    }
+
+    /// Combine `other` into this API, deduplicating paths with the same name and kind
+    ///
+    /// Returns where each of `other`'s paths ended up.
+    pub fn merge(&mut self, other: &Api) -> std::collections::HashMap<PathId, PathId> {
+        let mut crate_ids = std::collections::HashMap::new();
+        for (other_id, crate_) in other.crates.iter() {
+            let existing = self
+                .crates
+                .iter()
+                .find(|(_, existing)| existing.name == crate_.name)
+                .map(|(id, _)| id);
+            let id = match existing {
+                Some(id) => id,
+                None => self.crates.push(crate_.clone()),
+            };
+            crate_ids.insert(other_id, id);
+        }
+
+        let mut impl_ids = std::collections::HashMap::new();
+        for (other_id, impl_) in other.impls.iter() {
+            let mut impl_ = impl_.clone();
+            impl_.crate_id = impl_.crate_id.map(|id| crate_ids[&id]);
+            let existing = self
+                .impls
+                .iter()
+                .find(|(_, existing)| **existing == impl_)
+                .map(|(id, _)| id);
+            let id = match existing {
+                Some(id) => id,
+                None => self.impls.push(impl_),
+            };
+            impl_ids.insert(other_id, id);
+        }
+
+        let mut item_ids = std::collections::HashMap::new();
+        for (other_id, item) in other.items.iter() {
+            let mut item = item.clone();
+            item.crate_id = item.crate_id.map(|id| crate_ids[&id]);
+            item.trait_impls = item.trait_impls.iter().map(|id| impl_ids[id]).collect();
+            item.blanket_impls = item.blanket_impls.iter().map(|id| impl_ids[id]).collect();
+            item_ids.insert(other_id, self.items.push(item));
+        }
+        // Items refer to their associated items, which are only all known now
+        for id in item_ids.values() {
+            let item = self.items.get_mut(*id).unwrap();
+            for trait_item in &mut item.trait_items {
+                *trait_item = item_ids[trait_item];
+            }
+            for inherent_impl in &mut item.inherent_impls {
+                for inherent_item in &mut inherent_impl.items {
+                    *inherent_item = item_ids[inherent_item];
+                }
+            }
+        }
+
+        let mut existing: std::collections::HashMap<_, _> = self
+            .paths
+            .iter()
+            .map(|(id, path)| ((path.path.clone(), path.kind), id))
+            .collect();
+        let mut path_ids = std::collections::HashMap::new();
+        for (other_id, path) in other.paths.iter() {
+            let id = *existing
+                .entry((path.path.clone(), path.kind))
+                .or_insert_with(|| {
+                    let mut path = path.clone();
+                    path.crate_id = path.crate_id.map(|id| crate_ids[&id]);
+                    path.item_id = path.item_id.map(|id| item_ids[&id]);
+                    path.children.clear();
+                    self.paths.push(path)
+                });
+            path_ids.insert(other_id, id);
+        }
+        for (other_id, path) in other.paths.iter() {
+            let id = path_ids[&other_id];
+            for child in &path.children {
+                let child = path_ids[child];
+                let children = &mut self.paths.get_mut(id).unwrap().children;
+                if !children.contains(&child) {
+                    children.push(child);
+                }
+            }
+        }
+
+        if self.root_id.is_none() {
+            self.root_id = other.root_id.map(|id| path_ids[&id]);
+        }
+        for (name, feature) in &other.features {
+            self.features
+                .entry(name.clone())
+                .or_insert_with(|| feature.clone());
+        }
+        if self.rust_version.is_none() {
+            self.rust_version = other.rust_version.clone();
+        }
+        if self.edition.is_none() {
+            self.edition = other.edition.clone();
+        }
+        path_ids
+    }
 }
 
 #[derive(Clone, Default, Debug, serde::Serialize, serde::Deserialize)]
@@ -72,6 +191,12 @@ pub struct Path {
     pub span: Option<Span>,
     pub item_id: Option<ItemId>,
     pub children: Vec<PathId>,
+    /// Exempt from compatibility guarantees, see [`crate::diff::diff`]
+    #[serde(default)]
+    pub is_experimental: bool,
+    /// Of the path rather than the item, as a re-export can be less visible than what it names
+    #[serde(default)]
+    pub visibility: Visibility,
 }
 
 impl Path {
@@ -83,12 +208,14 @@ impl Path {
             span: None,
             item_id: None,
             children: Vec::new(),
+            is_experimental: false,
+            visibility: Visibility::Public,
         }
     }
 }
 
 #[derive(
-    Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord, serde::Serialize, serde::Deserialize,
+    Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash, serde::Serialize, serde::Deserialize,
 )]
 #[serde(rename_all = "snake_case")]
 #[non_exhaustive]
@@ -119,6 +246,43 @@ pub enum PathKind {
     Keyword,
 }
 
+impl PathKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Module => "module",
+            Self::ExternCrate => "extern_crate",
+            Self::Import => "import",
+            Self::Struct => "struct",
+            Self::Union => "union",
+            Self::Enum => "enum",
+            Self::Variant => "variant",
+            Self::Function => "function",
+            Self::Typedef => "typedef",
+            Self::OpaqueTy => "opaque_ty",
+            Self::Constant => "constant",
+            Self::Trait => "trait",
+            Self::TraitAlias => "trait_alias",
+            Self::Method => "method",
+            Self::Impl => "impl",
+            Self::Static => "static",
+            Self::ForeignType => "foreign_type",
+            Self::Macro => "macro",
+            Self::ProcAttribute => "proc_attribute",
+            Self::ProcDerive => "proc_derive",
+            Self::AssocConst => "assoc_const",
+            Self::AssocType => "assoc_type",
+            Self::Primitive => "primitive",
+            Self::Keyword => "keyword",
+        }
+    }
+}
+
+impl std::fmt::Display for PathKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        self.as_str().fmt(f)
+    }
+}
+
 #[derive(Clone, Default, Debug, serde::Serialize, serde::Deserialize)]
 #[serde(rename_all = "snake_case")]
 pub struct Items {
@@ -173,6 +337,101 @@ pub struct Item {
     pub crate_id: Option<CrateId>,
     pub name: Option<String>,
     pub span: Option<Span>,
+    /// The first paragraph of the item's documentation
+    #[serde(default)]
+    pub docs: Option<String>,
+    #[serde(default)]
+    pub deprecated: Option<Deprecation>,
+    #[serde(default)]
+    pub is_unsafe: bool,
+    /// Non-`Rust` ABI of a function, like `C`
+    #[serde(default)]
+    pub abi: Option<String>,
+    /// Unmangled symbol name from `#[no_mangle]` or `#[export_name]`
+    #[serde(default)]
+    pub symbol: Option<String>,
+    #[serde(default)]
+    pub signature: Option<Signature>,
+    /// Generic parameters of a function, type, or trait
+    #[serde(default)]
+    pub generics: Generics,
+    #[serde(default)]
+    pub fields: Vec<Field>,
+    /// How an enum variant's fields are declared
+    #[serde(default)]
+    pub variant_kind: Option<VariantKind>,
+    /// Whether a struct or union has fields that aren't public, whether or not they are in
+    /// [`Item::fields`], so users can't construct it with a literal
+    #[serde(default)]
+    pub has_private_fields: bool,
+    /// Cargo features this item is `cfg`-gated behind, including those of the modules, trait,
+    /// or `impl` block it is in
+    #[serde(default)]
+    pub features: Vec<String>,
+    /// From `#[doc(hidden)]`, usually meaning it isn't covered by semver
+    #[serde(default)]
+    pub is_hidden: bool,
+    /// From `#[non_exhaustive]`, so users can't rely on having seen every variant or field
+    #[serde(default)]
+    pub is_non_exhaustive: bool,
+    /// From `#[must_use]`, so users are warned when they ignore the value
+    #[serde(default)]
+    pub must_use: Option<MustUse>,
+    /// The associated functions, types, and consts of a trait
+    #[serde(default)]
+    pub trait_items: Vec<ItemId>,
+    /// A type's own `impl` blocks, without a trait
+    #[serde(default)]
+    pub inherent_impls: Vec<InherentImpl>,
+    /// A type's trait impls, including derived ones like `Clone`
+    #[serde(default)]
+    pub trait_impls: Vec<ImplId>,
+    /// Blanket impls from [`Api::impls`] that cover a type, like `impl<T: Display> Show for T`,
+    /// which it gets the trait's items from
+    #[serde(default)]
+    pub blanket_impls: Vec<ImplId>,
+    /// Whether a trait item has a default, like a provided method, so implementors can leave it
+    /// out
+    #[serde(default)]
+    pub has_default: bool,
+    /// Whether a trait has a supertrait users can't name, so only its own crate can implement it
+    #[serde(default)]
+    pub is_sealed: bool,
+    /// Bounds a trait's implementors must also implement, like `Clone`
+    #[serde(default)]
+    pub supertraits: Vec<String>,
+    /// Bounds on an associated type, like `Iterator<Item = u8>`
+    #[serde(default)]
+    pub bounds: Vec<String>,
+    /// Type of a const, static, or associated const, or the type a type alias stands for
+    #[serde(rename = "type", default)]
+    pub type_: Option<String>,
+    /// Default of an associated type or const, like `u8` or `5`
+    #[serde(default)]
+    pub default: Option<String>,
+    /// Value of a const or static as written, like `5`, when rustdoc has it
+    #[serde(default)]
+    pub value: Option<String>,
+    /// From `static mut`, so it can only be accessed in `unsafe` blocks
+    #[serde(default)]
+    pub is_mutable: bool,
+    /// Matchers of a `macro_rules!` macro's rules, in order, like `($name:ident, $($arg:expr),*)`
+    #[serde(default)]
+    pub matchers: Vec<String>,
+    /// Helper attributes of a derive macro, like `serde` for `#[serde(rename = "id")]`
+    #[serde(default)]
+    pub helpers: Vec<String>,
+    /// From `#[repr(...)]`, like `C`, `u8`, or `align(8)`, which FFI and layout assumptions rely on
+    #[serde(default)]
+    pub repr: Vec<String>,
+    /// Auto traits a type implements, like `Send`, including when only for some generic
+    /// arguments
+    #[serde(default)]
+    pub auto_traits: Vec<String>,
+    /// Only public unless extracted with
+    /// [`RustDocBuilder::private_items`][crate::RustDocBuilder::private_items]
+    #[serde(default)]
+    pub visibility: Visibility,
 }
 
 impl Item {
@@ -181,6 +440,37 @@ impl Item {
             crate_id: None,
             name: None,
             span: None,
+            docs: None,
+            deprecated: None,
+            is_unsafe: false,
+            abi: None,
+            symbol: None,
+            signature: None,
+            generics: Generics::new(),
+            fields: Vec::new(),
+            variant_kind: None,
+            has_private_fields: false,
+            features: Vec::new(),
+            is_hidden: false,
+            is_non_exhaustive: false,
+            must_use: None,
+            trait_items: Vec::new(),
+            inherent_impls: Vec::new(),
+            trait_impls: Vec::new(),
+            blanket_impls: Vec::new(),
+            has_default: false,
+            is_sealed: false,
+            supertraits: Vec::new(),
+            bounds: Vec::new(),
+            type_: None,
+            default: None,
+            value: None,
+            is_mutable: false,
+            matchers: Vec::new(),
+            helpers: Vec::new(),
+            repr: Vec::new(),
+            auto_traits: Vec::new(),
+            visibility: Visibility::Public,
         }
     }
 }
@@ -191,6 +481,445 @@ impl Default for Item {
     }
 }
 
+#[derive(Clone, Default, Debug, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "snake_case")]
+#[non_exhaustive]
+/// A function's parameters and return type
+///
+/// Its generics are [`Item::generics`] and it being `unsafe` or `extern` is [`Item::is_unsafe`]
+/// and [`Item::abi`], as those apply to other items too.
+pub struct Signature {
+    pub inputs: Vec<Input>,
+    /// Rendered return type, if not `()`
+    pub output: Option<String>,
+    pub c_variadic: bool,
+    #[serde(default)]
+    pub is_const: bool,
+    #[serde(default)]
+    pub is_async: bool,
+}
+
+impl Signature {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Rendered along with the function's generics, like `<T: Clone>(value: T) where T: Send`
+    pub fn with_generics<'s>(&'s self, generics: &'s Generics) -> impl std::fmt::Display + 's {
+        GenericSignature {
+            signature: self,
+            generics,
+        }
+    }
+}
+
+/// Rendered like in a declaration, like `(value: T) -> T`
+impl std::fmt::Display for Signature {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "(")?;
+        for (i, input) in self.inputs.iter().enumerate() {
+            if i != 0 {
+                write!(f, ", ")?;
+            }
+            write!(f, "{}: {}", input.name, input.type_)?;
+        }
+        if self.c_variadic {
+            if !self.inputs.is_empty() {
+                write!(f, ", ")?;
+            }
+            write!(f, "...")?;
+        }
+        write!(f, ")")?;
+        if let Some(output) = self.output.as_deref() {
+            write!(f, " -> {}", output)?;
+        }
+        Ok(())
+    }
+}
+
+struct GenericSignature<'s> {
+    signature: &'s Signature,
+    generics: &'s Generics,
+}
+
+impl std::fmt::Display for GenericSignature<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        self.generics.fmt_params(f)?;
+        self.signature.fmt(f)?;
+        self.generics.fmt_where_clause(f)
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "snake_case")]
+#[non_exhaustive]
+pub struct Input {
+    pub name: String,
+    /// Rendered type of the input, like `&str`
+    #[serde(rename = "type")]
+    pub type_: String,
+}
+
+impl Input {
+    pub fn new(name: impl Into<String>, type_: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            type_: type_.into(),
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "snake_case")]
+#[non_exhaustive]
+pub struct Field {
+    pub name: String,
+    /// Rendered type of the field, like `Vec<u8>`
+    #[serde(rename = "type")]
+    pub type_: String,
+    /// Fields of enum variants are [`Visibility::Inherited`], as they follow the enum's
+    #[serde(default)]
+    pub visibility: Visibility,
+}
+
+impl Field {
+    pub fn new(name: impl Into<String>, type_: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            type_: type_.into(),
+            visibility: Visibility::Public,
+        }
+    }
+}
+
+/// Generic parameters and `where` clauses of a function, type, or trait
+#[derive(Clone, Default, Debug, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "snake_case")]
+#[non_exhaustive]
+pub struct Generics {
+    pub params: Vec<GenericParam>,
+    pub where_predicates: Vec<WherePredicate>,
+}
+
+impl Generics {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.params.is_empty() && self.where_predicates.is_empty()
+    }
+}
+
+impl Generics {
+    /// The parameter list, like `<'a, T: Clone = u8>`
+    fn fmt_params(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if self.params.is_empty() {
+            return Ok(());
+        }
+        write!(f, "<")?;
+        for (i, param) in self.params.iter().enumerate() {
+            if i != 0 {
+                write!(f, ", ")?;
+            }
+            match &param.kind {
+                GenericParamKind::Lifetime | GenericParamKind::Type => write!(f, "{}", param.name)?,
+                GenericParamKind::Const(type_) => write!(f, "const {}: {}", param.name, type_)?,
+            }
+            if !param.bounds.is_empty() {
+                write!(f, ": {}", param.bounds.join(" + "))?;
+            }
+            if let Some(default) = &param.default {
+                write!(f, " = {}", default)?;
+            }
+        }
+        write!(f, ">")
+    }
+
+    /// The `where` clause, with a leading space, like ` where T: 'a`
+    fn fmt_where_clause(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for (i, predicate) in self.where_predicates.iter().enumerate() {
+            write!(f, "{}", if i == 0 { " where " } else { ", " })?;
+            match predicate {
+                WherePredicate::Bound { target, bounds } => {
+                    write!(f, "{}: {}", target, bounds.join(" + "))?
+                }
+                WherePredicate::Eq { lhs, rhs } => write!(f, "{} = {}", lhs, rhs)?,
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Rendered like in a declaration, like `<'a, T: Clone = u8> where T: 'a`
+impl std::fmt::Display for Generics {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        self.fmt_params(f)?;
+        self.fmt_where_clause(f)
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "snake_case")]
+#[non_exhaustive]
+pub struct GenericParam {
+    pub name: String,
+    pub kind: GenericParamKind,
+    /// Rendered inline bounds, like `Clone` in `T: Clone` or `'b` in `'a: 'b`
+    pub bounds: Vec<String>,
+    /// Rendered default, like `u8` in `T = u8`
+    pub default: Option<String>,
+}
+
+impl GenericParam {
+    pub fn new(name: impl Into<String>, kind: GenericParamKind) -> Self {
+        Self {
+            name: name.into(),
+            kind,
+            bounds: Vec::new(),
+            default: None,
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum GenericParamKind {
+    Lifetime,
+    Type,
+    /// With the rendered type of the const
+    Const(String),
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum WherePredicate {
+    /// Rendered bounds on a type or lifetime, like `T::Item: Clone` or `'a: 'b`
+    Bound { target: String, bounds: Vec<String> },
+    /// Rendered equality, like `T::Item = u8`
+    Eq { lhs: String, rhs: String },
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum VariantKind {
+    /// Like `None`
+    Unit,
+    /// Like `Some(T)`, with fields named by position
+    Tuple,
+    /// Like `Move { x: i32 }`
+    Struct,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Visibility {
+    Public,
+    /// No modifier, so private except for enum variants and trait items, which follow their parent
+    Inherited,
+    /// `pub(crate)`
+    Crate,
+    /// `pub(in path)`, with the path as written
+    Restricted(String),
+}
+
+impl Default for Visibility {
+    fn default() -> Self {
+        Self::Public
+    }
+}
+
+/// Rendered like the modifier, like `pub(crate)`, with no modifier being `private`
+impl std::fmt::Display for Visibility {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Public => "pub".fmt(f),
+            Self::Inherited => "private".fmt(f),
+            Self::Crate => "pub(crate)".fmt(f),
+            Self::Restricted(path) => write!(f, "pub(in {})", path),
+        }
+    }
+}
+
+#[derive(Clone, Default, Debug, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "snake_case")]
+#[non_exhaustive]
+pub struct Deprecation {
+    pub since: Option<String>,
+    pub note: Option<String>,
+}
+
+impl Deprecation {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl std::fmt::Display for Deprecation {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match (&self.since, &self.note) {
+            (Some(since), Some(note)) => {
+                write!(f, "#[deprecated(since = {:?}, note = {:?})]", since, note)
+            }
+            (Some(since), None) => write!(f, "#[deprecated(since = {:?})]", since),
+            (None, Some(note)) => write!(f, "#[deprecated(note = {:?})]", note),
+            (None, None) => write!(f, "#[deprecated]"),
+        }
+    }
+}
+
+#[derive(Clone, Default, Debug, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "snake_case")]
+#[non_exhaustive]
+pub struct MustUse {
+    /// Why the value shouldn't be ignored, like `#[must_use = "iterators are lazy"]`
+    pub message: Option<String>,
+}
+
+impl MustUse {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl std::fmt::Display for MustUse {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match &self.message {
+            Some(message) => write!(f, "#[must_use = {:?}]", message),
+            None => write!(f, "#[must_use]"),
+        }
+    }
+}
+
+#[derive(Clone, Default, Debug, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub struct Impls {
+    impls: Vec<(ImplId, Impl)>,
+}
+
+impl Impls {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn push(&mut self, impl_: Impl) -> ImplId {
+        let id = ImplId(self.impls.len());
+        self.impls.push((id, impl_));
+        id
+    }
+
+    pub fn len(&self) -> usize {
+        self.impls.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.impls.is_empty()
+    }
+
+    pub fn get(&self, id: ImplId) -> Option<&Impl> {
+        self.impls.get(id.0).map(|(_i, c)| c)
+    }
+
+    pub fn get_mut(&mut self, id: ImplId) -> Option<&mut Impl> {
+        self.impls.get_mut(id.0).map(|(_i, c)| c)
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (ImplId, &Impl)> {
+        self.impls.iter().map(|(i, c)| (*i, c))
+    }
+
+    pub fn iter_mut(&mut self) -> impl Iterator<Item = (ImplId, &mut Impl)> {
+        self.impls.iter_mut().map(|(i, c)| (*i, c))
+    }
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "snake_case")]
+#[repr(transparent)]
+pub struct ImplId(usize);
+
+/// An `impl Trait for Type`, other than auto-trait and blanket impls
+#[derive(Clone, Debug, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "snake_case")]
+#[non_exhaustive]
+pub struct Impl {
+    pub crate_id: Option<CrateId>,
+    #[serde(default)]
+    pub generics: Generics,
+    /// Full path of the trait, with any generic arguments
+    pub trait_: String,
+    /// Rendered type the trait is implemented for, with its full path when known
+    pub for_: String,
+    pub is_negative: bool,
+    /// `unsafe impl`, as required by `unsafe trait`s
+    #[serde(default)]
+    pub is_unsafe: bool,
+    /// Cargo features this impl is `cfg`-gated behind
+    #[serde(default)]
+    pub features: Vec<String>,
+    pub span: Option<Span>,
+}
+
+impl Impl {
+    pub fn new(trait_: impl Into<String>, for_: impl Into<String>) -> Self {
+        Self {
+            crate_id: None,
+            generics: Generics::new(),
+            trait_: trait_.into(),
+            for_: for_.into(),
+            is_negative: false,
+            is_unsafe: false,
+            features: Vec::new(),
+            span: None,
+        }
+    }
+}
+
+impl std::fmt::Display for Impl {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if self.is_unsafe {
+            write!(f, "unsafe ")?;
+        }
+        write!(f, "impl")?;
+        self.generics.fmt_params(f)?;
+        let negative = if self.is_negative { "!" } else { "" };
+        write!(f, " {}{} for {}", negative, self.trait_, self.for_)?;
+        self.generics.fmt_where_clause(f)
+    }
+}
+
+/// An `impl Type` block, like `impl Foo<u8>`
+#[derive(Clone, Debug, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "snake_case")]
+#[non_exhaustive]
+pub struct InherentImpl {
+    #[serde(default)]
+    pub generics: Generics,
+    /// Rendered type the block is for, with its generic arguments
+    pub for_: String,
+    /// The associated functions and consts the block defines
+    pub items: Vec<ItemId>,
+}
+
+impl InherentImpl {
+    pub fn new(for_: impl Into<String>) -> Self {
+        Self {
+            generics: Generics::new(),
+            for_: for_.into(),
+            items: Vec::new(),
+        }
+    }
+}
+
+impl std::fmt::Display for InherentImpl {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "impl")?;
+        self.generics.fmt_params(f)?;
+        write!(f, " {}", self.for_)?;
+        self.generics.fmt_where_clause(f)
+    }
+}
+
 #[derive(Clone, Default, Debug, serde::Serialize, serde::Deserialize)]
 #[serde(rename_all = "snake_case")]
 pub struct Crates {
@@ -260,9 +989,9 @@ impl Crate {
 pub struct Span {
     /// The relative path to the source file for this span
     pub filename: std::path::PathBuf,
-    /// Zero indexed Line and Column of the first character of the `Span`
+    /// One indexed line and zero indexed column of the first character of the `Span`
     pub begin: (usize, usize),
-    /// Zero indexed Line and Column of the last character of the `Span`
+    /// One indexed line and zero indexed column of the last character of the `Span`
     pub end: (usize, usize),
 }
 
@@ -309,3 +1038,30 @@ impl OptionalDependency {
         }
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn item_fields_default() {
+        let item: Item =
+            serde_json::from_str(r#"{"crate_id": null, "name": "foo", "span": null}"#).unwrap();
+        assert_eq!(item.name.as_deref(), Some("foo"));
+        assert!(item.signature.is_none());
+        assert!(item.features.is_empty());
+        assert!(!item.is_hidden);
+        assert_eq!(item.visibility, Visibility::Public);
+    }
+
+    #[test]
+    fn path_fields_default() {
+        let path: Path = serde_json::from_str(
+            r#"{"crate_id": null, "path": "foo", "kind": "module", "span": null, "item_id": null, "children": []}"#,
+        )
+        .unwrap();
+        assert_eq!(path.path, "foo");
+        assert!(!path.is_experimental);
+        assert_eq!(path.visibility, Visibility::Public);
+    }
+}
@@ -0,0 +1,135 @@
+/// Construct an [`Api`][crate::Api] in code, like for testing tools built on this crate
+///
+/// Paths are attached to their parent module, creating any missing modules along the way.
+///
+/// ```
+/// use crate_api::{ApiBuilder, Item, PathKind, Signature};
+///
+/// let mut builder = ApiBuilder::new("mycrate");
+/// builder.path(PathKind::Struct, "mycrate::net::Stream");
+///
+/// let mut connect = Item::new();
+/// connect.signature = Some(Signature::new());
+/// builder.path_with(PathKind::Function, "mycrate::net::connect", connect);
+///
+/// let api = builder.build();
+/// assert_eq!(api.paths.len(), 4);
+/// ```
+#[derive(Clone, Debug)]
+pub struct ApiBuilder {
+    api: crate::Api,
+    crate_id: Option<crate::CrateId>,
+}
+
+impl ApiBuilder {
+    /// Start an API for the crate named `root`
+    pub fn new(root: impl Into<String>) -> Self {
+        let mut builder = Self {
+            api: crate::Api::new(),
+            crate_id: None,
+        };
+        let root = builder.path(crate::PathKind::Module, root);
+        builder.api.root_id = Some(root);
+        builder
+    }
+
+    /// Add the paths that follow to the external crate, until [`ApiBuilder::local`]
+    pub fn external(&mut self, name: impl Into<String>) -> &mut Self {
+        let name = name.into();
+        let existing = self
+            .api
+            .crates
+            .iter()
+            .find(|(_, crate_)| crate_.name == name)
+            .map(|(crate_id, _)| crate_id);
+        let crate_id = match existing {
+            Some(crate_id) => crate_id,
+            None => self.api.crates.push(crate::Crate::new(name)),
+        };
+        self.crate_id = Some(crate_id);
+        self
+    }
+
+    /// Add the paths that follow to the crate being described
+    pub fn local(&mut self) -> &mut Self {
+        self.crate_id = None;
+        self
+    }
+
+    /// Add a path with an item named after its last segment
+    pub fn path(&mut self, kind: crate::PathKind, path: impl Into<String>) -> crate::PathId {
+        self.path_with(kind, path, crate::Item::new())
+    }
+
+    /// Add a path for `item`, naming the item after the path if it has no name
+    pub fn path_with(
+        &mut self,
+        kind: crate::PathKind,
+        path: impl Into<String>,
+        mut item: crate::Item,
+    ) -> crate::PathId {
+        let path = path.into();
+        let parent_id = path
+            .rsplit_once("::")
+            .map(|(parent, _)| self.module(parent));
+
+        let name = path.rsplit("::").next().unwrap_or_default().to_owned();
+        item.name.get_or_insert(name);
+        item.crate_id = self.crate_id;
+        let item_id = self.api.items.push(item);
+
+        let mut path = crate::Path::new(kind, path);
+        path.crate_id = self.crate_id;
+        path.item_id = Some(item_id);
+        let path_id = self.api.paths.push(path);
+
+        if let Some(parent_id) = parent_id {
+            self.api
+                .paths
+                .get_mut(parent_id)
+                .expect("parent_id to always be valid")
+                .children
+                .push(path_id);
+        }
+        path_id
+    }
+
+    /// Add a trait impl
+    pub fn trait_impl(&mut self, mut impl_: crate::Impl) -> crate::ImplId {
+        impl_.crate_id = self.crate_id;
+        self.api.impls.push(impl_)
+    }
+
+    /// Add a cargo feature
+    pub fn feature(&mut self, feature: crate::AnyFeature) -> &mut Self {
+        let name = match &feature {
+            crate::AnyFeature::Feature(feature) => feature.name.clone(),
+            crate::AnyFeature::OptionalDependency(dependency) => dependency.name.clone(),
+        };
+        self.api.features.insert(name, feature);
+        self
+    }
+
+    /// Access the API so far, like to adjust a path added earlier
+    pub fn api_mut(&mut self) -> &mut crate::Api {
+        &mut self.api
+    }
+
+    pub fn build(self) -> crate::Api {
+        self.api
+    }
+
+    /// Find or create the module at `path`
+    fn module(&mut self, path: &str) -> crate::PathId {
+        let existing = self
+            .api
+            .paths
+            .iter()
+            .find(|(_, existing)| existing.path == path && existing.kind == crate::PathKind::Module)
+            .map(|(path_id, _)| path_id);
+        match existing {
+            Some(path_id) => path_id,
+            None => self.path(crate::PathKind::Module, path),
+        }
+    }
+}
@@ -1,3 +1,7 @@
+pub mod rules;
+
+use std::collections::BTreeMap;
+use std::collections::BTreeSet;
 use std::collections::HashMap;
 use std::collections::HashSet;
 
@@ -19,6 +23,10 @@ pub struct Diff {
     pub id: Id,
     pub before: Option<Location>,
     pub after: Option<Location>,
+    /// How likely a heuristic, like rename detection, is to be right, as a percentage
+    pub confidence: Option<u8>,
+    /// To an experimental item, so it was relaxed and needs no more than a minor bump
+    pub is_experimental: bool,
 }
 
 #[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord, serde::Serialize)]
@@ -30,7 +38,9 @@ pub enum Category {
     Changed,
 }
 
-#[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord, serde::Serialize)]
+#[derive(
+    Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord, serde::Serialize, serde::Deserialize,
+)]
 #[serde(rename_all = "snake_case")]
 pub enum Severity {
     Allow,
@@ -45,10 +55,63 @@ pub struct Location {
     pub crate_id: Option<crate::CrateId>,
     pub path_id: Option<crate::PathId>,
     pub item_id: Option<crate::ItemId>,
+    pub impl_id: Option<crate::ImplId>,
 }
 
+/// Find the changes between two APIs
+///
+/// Breaking changes to paths marked as experimental are only reported.
 pub fn diff(before: &crate::Api, after: &crate::Api, changes: &mut Vec<Diff>) {
+    let _span = tracing::debug_span!(
+        "diff",
+        before = before.paths.len(),
+        after = after.paths.len()
+    )
+    .entered();
+    let start = changes.len();
     public_dependencies(before, after, changes);
+    rust_version(before, after, changes);
+    renamed_items(before, after, changes);
+    removed_items(before, after, changes);
+    deprecations(before, after, changes);
+    signatures(before, after, changes);
+    consts(before, after, changes);
+    type_aliases(before, after, changes);
+    macros(before, after, changes);
+    derive_helpers(before, after, changes);
+    variants(before, after, changes);
+    struct_fields(before, after, changes);
+    trait_items(before, after, changes);
+    inherent_items(before, after, changes);
+    auto_traits(before, after, changes);
+    blanket_impls(before, after, changes);
+    generics(before, after, changes);
+    visibility(before, after, changes);
+    repr(before, after, changes);
+    non_exhaustive(before, after, changes);
+    must_use(before, after, changes);
+    feature_gates(before, after, changes);
+    trait_impls(before, after, changes);
+    relax_experimental(before, after, &mut changes[start..]);
+    tracing::debug!(changes = changes.len() - start, "diffed");
+}
+
+fn relax_experimental(before: &crate::Api, after: &crate::Api, changes: &mut [Diff]) {
+    let is_experimental = |api: &crate::Api, location: Option<Location>| {
+        location
+            .and_then(|location| location.path_id)
+            .and_then(|path_id| api.paths.get(path_id))
+            .map(|path| path.is_experimental)
+            .unwrap_or(false)
+    };
+    for change in changes {
+        if is_experimental(before, change.before) || is_experimental(after, change.after) {
+            change.is_experimental = true;
+            if change.severity == Severity::Warn {
+                change.severity = Severity::Report;
+            }
+        }
+    }
 }
 
 pub const ALL_IDS: &[Id] = &[
@@ -56,6 +119,68 @@ pub const ALL_IDS: &[Id] = &[
     DEPENDENCY_ADDED,
     DEPENDENCY_AMBIGUOUS,
     DEPENDENCY_REQUIREMENT,
+    RUST_VERSION_RAISED,
+    ITEM_RENAMED,
+    ITEM_REMOVED,
+    PATH_REMOVED,
+    DEPRECATED_ITEM_REMOVED,
+    DEPRECATION_ADDED,
+    FN_ARITY_CHANGED,
+    FN_PARAMETER_CHANGED,
+    FN_OUTPUT_CHANGED,
+    FN_UNSAFE_ADDED,
+    FN_UNSAFE_REMOVED,
+    FN_CONST_REMOVED,
+    FN_ASYNC_CHANGED,
+    FN_ABI_CHANGED,
+    CONST_TYPE_CHANGED,
+    CONST_VALUE_CHANGED,
+    STATIC_MUTABILITY_CHANGED,
+    TYPE_ALIAS_CHANGED,
+    MACRO_RULE_REMOVED,
+    MACRO_RULE_ADDED,
+    DERIVE_HELPER_REMOVED,
+    VARIANT_ADDED,
+    NON_EXHAUSTIVE_VARIANT_ADDED,
+    VARIANT_REMOVED,
+    VARIANT_FIELDS_CHANGED,
+    STRUCT_FIELD_ADDED,
+    STRUCT_PRIVATE_FIELD_ADDED,
+    STRUCT_FIELD_REMOVED,
+    STRUCT_FIELD_CHANGED,
+    TRAIT_ITEM_ADDED,
+    TRAIT_PROVIDED_ITEM_ADDED,
+    SEALED_TRAIT_ITEM_ADDED,
+    TRAIT_ITEM_REMOVED,
+    TRAIT_ITEM_DEFAULT_REMOVED,
+    TRAIT_ITEM_DEFAULT_ADDED,
+    ASSOC_CONST_TYPE_CHANGED,
+    ASSOC_TYPE_BOUND_ADDED,
+    ASSOC_TYPE_BOUND_REMOVED,
+    SUPERTRAIT_ADDED,
+    SUPERTRAIT_REMOVED,
+    TRAIT_UNSAFE_ADDED,
+    TRAIT_UNSAFE_REMOVED,
+    INHERENT_ITEM_REMOVED,
+    INHERENT_ITEM_ADDED,
+    AUTO_TRAIT_REMOVED,
+    BLANKET_IMPL_REMOVED,
+    GENERIC_PARAM_ADDED,
+    DEFAULTED_GENERIC_PARAM_ADDED,
+    GENERIC_PARAM_REMOVED,
+    GENERIC_BOUND_TIGHTENED,
+    GENERIC_BOUND_LOOSENED,
+    VISIBILITY_REDUCED,
+    REPR_ADDED,
+    REPR_CHANGED,
+    NON_EXHAUSTIVE_ADDED,
+    NON_EXHAUSTIVE_REMOVED,
+    MUST_USE_ADDED,
+    MUST_USE_REMOVED,
+    FEATURE_GATE_ADDED,
+    FEATURE_GATE_REMOVED,
+    TRAIT_IMPL_REMOVED,
+    TRAIT_IMPL_ADDED,
 ];
 
 pub const DEPENDENCY_REMOVED: Id = Id {
@@ -114,6 +239,8 @@ pub fn public_dependencies(before: &crate::Api, after: &crate::Api, changes: &mu
                 ..Default::default()
             }),
             after: None,
+            confidence: None,
+            is_experimental: false,
         });
     }
 
@@ -127,6 +254,8 @@ pub fn public_dependencies(before: &crate::Api, after: &crate::Api, changes: &mu
                 crate_id: Some(after_crate_id),
                 ..Default::default()
             }),
+            confidence: None,
+            is_experimental: false,
         });
     }
 
@@ -136,26 +265,33 @@ pub fn public_dependencies(before: &crate::Api, after: &crate::Api, changes: &mu
         let after_crate_id = *after_by_name.get(*common_name).unwrap();
         let after_crate = after.crates.get(after_crate_id).unwrap();
 
-        if before_crate.version.is_none() || after_crate.version.is_none() {
-            changes.push(Diff {
-                severity: DEPENDENCY_AMBIGUOUS.default_severity,
-                id: DEPENDENCY_AMBIGUOUS,
-                before: Some(Location {
-                    crate_id: Some(before_crate_id),
-                    ..Default::default()
-                }),
-                after: Some(Location {
-                    crate_id: Some(after_crate_id),
-                    ..Default::default()
-                }),
-            });
-        } else if before_crate.version == after_crate.version {
-        } else {
-            let (before_lower, before_upper) = breaking(before_crate.version.as_ref().unwrap());
+        let (before_version, after_version) =
+            match (before_crate.version.as_ref(), after_crate.version.as_ref()) {
+                (Some(before_version), Some(after_version)) => (before_version, after_version),
+                _ => {
+                    changes.push(Diff {
+                        severity: DEPENDENCY_AMBIGUOUS.default_severity,
+                        id: DEPENDENCY_AMBIGUOUS,
+                        before: Some(Location {
+                            crate_id: Some(before_crate_id),
+                            ..Default::default()
+                        }),
+                        after: Some(Location {
+                            crate_id: Some(after_crate_id),
+                            ..Default::default()
+                        }),
+                        confidence: None,
+                        is_experimental: false,
+                    });
+                    continue;
+                }
+            };
+        if before_version != after_version {
+            let (before_lower, before_upper) = breaking(before_version);
             let before_lower = before_lower.unwrap_or((0, 0, 0));
             let before_upper = before_upper.unwrap_or((u64::MAX, u64::MAX, u64::MAX));
 
-            let (after_lower, after_upper) = breaking(after_crate.version.as_ref().unwrap());
+            let (after_lower, after_upper) = breaking(after_version);
             let after_lower = after_lower.unwrap_or((0, 0, 0));
             let after_upper = after_upper.unwrap_or((u64::MAX, u64::MAX, u64::MAX));
 
@@ -171,6 +307,8 @@ pub fn public_dependencies(before: &crate::Api, after: &crate::Api, changes: &mu
                         crate_id: Some(after_crate_id),
                         ..Default::default()
                     }),
+                    confidence: None,
+                    is_experimental: false,
                 });
             }
         }
@@ -322,3 +460,2296 @@ fn exact_break(comparator: &semver::Comparator) -> Option<(u64, u64, u64)> {
         Some((0, 0, major))
     }
 }
+
+pub const RUST_VERSION_RAISED: Id = Id {
+    name: "rust-version-raised",
+    explanation: "Minimum supported Rust version raised, dropping users on older toolchains",
+    category: Category::Changed,
+    default_severity: Severity::Report,
+};
+
+/// Report a raised `package.rust-version`
+///
+/// Whether that needs a major release is up to each project, so it is only reported.  Set its
+/// severity to `warn` to require one.  The change's location is the crate root.
+pub fn rust_version(before: &crate::Api, after: &crate::Api, changes: &mut Vec<Diff>) {
+    let parse = |api: &crate::Api| {
+        let rust_version = api.rust_version.as_deref()?;
+        let mut components = rust_version.split('.').map(|c| c.parse::<u64>().ok());
+        let major = components.next()??;
+        let minor = components.next().unwrap_or(Some(0))?;
+        let patch = components.next().unwrap_or(Some(0))?;
+        Some(semver::Version::new(major, minor, patch))
+    };
+    let raised = match (parse(before), parse(after)) {
+        (Some(before_version), Some(after_version)) => before_version < after_version,
+        // Not declaring one before promised nothing
+        _ => false,
+    };
+    if raised {
+        let root = |api: &crate::Api| {
+            Some(Location {
+                path_id: api.root_id,
+                ..Default::default()
+            })
+        };
+        changes.push(Diff {
+            severity: RUST_VERSION_RAISED.default_severity,
+            id: RUST_VERSION_RAISED,
+            before: root(before),
+            after: root(after),
+            confidence: None,
+            is_experimental: false,
+        });
+    }
+}
+
+pub const ITEM_RENAMED: Id = Id {
+    name: "item-renamed",
+    explanation: "Item was probably renamed or moved, breaking the old path",
+    category: Category::Changed,
+    default_severity: Severity::Warn,
+};
+
+pub const TRAIT_IMPL_REMOVED: Id = Id {
+    name: "trait-impl-removed",
+    explanation: "Trait impl removed, breaking code relying on it",
+    category: Category::Removed,
+    default_severity: Severity::Warn,
+};
+
+pub const TRAIT_IMPL_ADDED: Id = Id {
+    name: "trait-impl-added",
+    explanation: "Trait impl added",
+    category: Category::Added,
+    default_severity: Severity::Report,
+};
+
+/// Compare trait impls by their trait and type, like a derived `PartialEq` for a struct
+///
+/// The bounds of an impl found on both sides are compared like those of an item, as tightening
+/// them takes the impl away from some types.
+pub fn trait_impls(before: &crate::Api, after: &crate::Api, changes: &mut Vec<Diff>) {
+    let before_impls = impls_by_key(before);
+    let after_impls = impls_by_key(after);
+
+    for (key, before_impl_id) in &before_impls {
+        if let Some(after_impl_id) = after_impls.get(key) {
+            let before_bounds =
+                generic_bounds(&before.impls.get(*before_impl_id).unwrap().generics);
+            let after_bounds = generic_bounds(&after.impls.get(*after_impl_id).unwrap().generics);
+            let mut ids = Vec::new();
+            if after_bounds.difference(&before_bounds).next().is_some() {
+                ids.push(GENERIC_BOUND_TIGHTENED);
+            }
+            if before_bounds.difference(&after_bounds).next().is_some() {
+                ids.push(GENERIC_BOUND_LOOSENED);
+            }
+            for id in ids {
+                changes.push(Diff {
+                    severity: id.default_severity,
+                    id,
+                    before: Some(Location {
+                        impl_id: Some(*before_impl_id),
+                        ..Default::default()
+                    }),
+                    after: Some(Location {
+                        impl_id: Some(*after_impl_id),
+                        ..Default::default()
+                    }),
+                    confidence: None,
+                    is_experimental: false,
+                });
+            }
+        } else {
+            changes.push(Diff {
+                severity: TRAIT_IMPL_REMOVED.default_severity,
+                id: TRAIT_IMPL_REMOVED,
+                before: Some(Location {
+                    impl_id: Some(*before_impl_id),
+                    ..Default::default()
+                }),
+                after: None,
+                confidence: None,
+                is_experimental: false,
+            });
+        }
+    }
+    for (key, after_impl_id) in &after_impls {
+        if !before_impls.contains_key(key) {
+            changes.push(Diff {
+                severity: TRAIT_IMPL_ADDED.default_severity,
+                id: TRAIT_IMPL_ADDED,
+                before: None,
+                after: Some(Location {
+                    impl_id: Some(*after_impl_id),
+                    ..Default::default()
+                }),
+                confidence: None,
+                is_experimental: false,
+            });
+        }
+    }
+}
+
+/// Index impls by their trait and type, with the local crate's name normalized
+fn impls_by_key(api: &crate::Api) -> std::collections::BTreeMap<(String, String), crate::ImplId> {
+    api.impls
+        .iter()
+        .map(|(impl_id, impl_)| (impl_key(api, impl_), impl_id))
+        .collect()
+}
+
+fn impl_key(api: &crate::Api, impl_: &crate::Impl) -> (String, String) {
+    let root = api
+        .root_id
+        .and_then(|root_id| api.paths.get(root_id))
+        .map(|root| format!("{}::", root.path))
+        .unwrap_or_default();
+    let normalize = |rendered: &str| {
+        if root.is_empty() {
+            rendered.to_owned()
+        } else {
+            rendered.replace(&root, "crate::")
+        }
+    };
+    (normalize(&impl_.trait_), normalize(&impl_.for_))
+}
+
+/// Minimum confidence for reporting a removed and added path as a rename
+const RENAME_CONFIDENCE: u8 = 60;
+
+/// Pair up paths that disappeared with same-kind paths that appeared when their items look alike
+pub fn renamed_items(before: &crate::Api, after: &crate::Api, changes: &mut Vec<Diff>) {
+    // Compare relative to the crate root, in case the crate itself was renamed
+    let before_keys: BTreeSet<_> = before
+        .paths
+        .iter()
+        .map(|(_, path)| (relative_path(before, &path.path), path.kind))
+        .collect();
+    let after_keys: BTreeSet<_> = after
+        .paths
+        .iter()
+        .map(|(_, path)| (relative_path(after, &path.path), path.kind))
+        .collect();
+    let removed: Vec<_> = before
+        .paths
+        .iter()
+        .filter(|(_, path)| is_renameable(path.kind))
+        .filter(|(_, path)| !after_keys.contains(&(relative_path(before, &path.path), path.kind)))
+        .collect();
+    let added: Vec<_> = after
+        .paths
+        .iter()
+        .filter(|(_, path)| is_renameable(path.kind))
+        .filter(|(_, path)| !before_keys.contains(&(relative_path(after, &path.path), path.kind)))
+        .collect();
+
+    let mut candidates = Vec::new();
+    for (before_id, before_path) in &removed {
+        for (after_id, after_path) in &added {
+            if before_path.kind != after_path.kind {
+                continue;
+            }
+            if let Some(confidence) = rename_confidence(before, before_path, after, after_path) {
+                if RENAME_CONFIDENCE <= confidence {
+                    candidates.push((confidence, *before_id, *after_id));
+                }
+            }
+        }
+    }
+    // Stable, so ties fall back to declaration order
+    candidates.sort_by_key(|(confidence, _, _)| std::cmp::Reverse(*confidence));
+
+    let mut renamed_before = HashSet::new();
+    let mut renamed_after = HashSet::new();
+    for (confidence, before_id, after_id) in candidates {
+        if renamed_before.contains(&before_id) || renamed_after.contains(&after_id) {
+            continue;
+        }
+        renamed_before.insert(before_id);
+        renamed_after.insert(after_id);
+        let before_path = before.paths.get(before_id).unwrap();
+        let after_path = after.paths.get(after_id).unwrap();
+        changes.push(Diff {
+            severity: ITEM_RENAMED.default_severity,
+            id: ITEM_RENAMED,
+            before: Some(Location {
+                crate_id: before_path.crate_id,
+                path_id: Some(before_id),
+                item_id: before_path.item_id,
+                impl_id: None,
+            }),
+            after: Some(Location {
+                crate_id: after_path.crate_id,
+                path_id: Some(after_id),
+                item_id: after_path.item_id,
+                impl_id: None,
+            }),
+            confidence: Some(confidence),
+            is_experimental: false,
+        });
+    }
+}
+
+pub const ITEM_REMOVED: Id = Id {
+    name: "item-removed",
+    explanation: "Item removed, breaking code using it",
+    category: Category::Removed,
+    default_severity: Severity::Warn,
+};
+
+pub const PATH_REMOVED: Id = Id {
+    name: "path-removed",
+    explanation: "Path removed, like a dropped re-export, though the item is reachable elsewhere",
+    category: Category::Removed,
+    default_severity: Severity::Warn,
+};
+
+pub const DEPRECATED_ITEM_REMOVED: Id = Id {
+    name: "deprecated-item-removed",
+    explanation: "Item removed after being deprecated, breaking code that ignored the warning",
+    category: Category::Removed,
+    default_severity: Severity::Report,
+};
+
+/// Report paths that are no longer reachable
+///
+/// When the item is still reachable under another path, like when only a re-export was dropped,
+/// that path is the change's `after`.  Only the outermost removed path is reported, as its
+/// children go with it, and paths already reported as renamed are skipped.
+///
+/// Removing what was already deprecated follows the deprecate-then-remove discipline, so it is
+/// only reported.
+pub fn removed_items(before: &crate::Api, after: &crate::Api, changes: &mut Vec<Diff>) {
+    let renamed: HashSet<_> = changes
+        .iter()
+        .filter(|change| change.id == ITEM_RENAMED)
+        .filter_map(|change| change.before.and_then(|location| location.path_id))
+        .collect();
+    let before_definitions = definitions(before);
+    let after_definitions = definitions(after);
+    let after_keys: HashSet<_> = local_paths(after)
+        .map(|(_, path)| reachable_key(after, &after_definitions, path))
+        .collect();
+
+    // Variants are compared along with their enum
+    let removed: Vec<_> = local_paths(before)
+        .filter(|(_, path)| path.kind != crate::PathKind::Variant)
+        .filter(|(path_id, _)| !renamed.contains(path_id))
+        .filter(|(_, path)| !after_keys.contains(&reachable_key(before, &before_definitions, path)))
+        .collect();
+    let removed_paths: HashSet<_> = removed
+        .iter()
+        .map(|(_, path)| relative_path(before, &path.path))
+        .collect();
+
+    for (path_id, path) in removed {
+        let relative = relative_path(before, &path.path);
+        let mut ancestors = std::iter::successors(Some(relative), |path| {
+            path.rsplit_once("::").map(|(parent, _)| parent)
+        })
+        .skip(1);
+        if ancestors.any(|ancestor| removed_paths.contains(ancestor)) {
+            continue;
+        }
+
+        let definition = path
+            .item_id
+            .and_then(|item_id| before_definitions.get(&item_id))
+            .copied();
+        // Prefer where the item is defined over other re-exports of it
+        let alternative = definition.and_then(|definition| {
+            local_paths(after)
+                .filter(|(_, candidate)| relative_path(after, &candidate.path) != relative)
+                .filter(|(_, candidate)| {
+                    candidate
+                        .item_id
+                        .and_then(|item_id| after_definitions.get(&item_id))
+                        == Some(&definition)
+                })
+                .min_by_key(|(_, candidate)| candidate.kind == crate::PathKind::Import)
+        });
+
+        let before_location = Some(Location {
+            crate_id: path.crate_id,
+            path_id: Some(path_id),
+            item_id: path.item_id,
+            impl_id: None,
+        });
+        let was_deprecated = path_item(before, path_id)
+            .map(|item| item.deprecated.is_some())
+            .unwrap_or(false);
+        let change = match alternative {
+            _ if was_deprecated => Diff {
+                severity: DEPRECATED_ITEM_REMOVED.default_severity,
+                id: DEPRECATED_ITEM_REMOVED,
+                before: before_location,
+                after: alternative
+                    .map(|(alternative_id, _)| path_location(after, alternative_id).unwrap()),
+                confidence: None,
+                is_experimental: false,
+            },
+            Some((alternative_id, alternative)) => Diff {
+                severity: PATH_REMOVED.default_severity,
+                id: PATH_REMOVED,
+                before: before_location,
+                after: Some(Location {
+                    crate_id: alternative.crate_id,
+                    path_id: Some(alternative_id),
+                    item_id: alternative.item_id,
+                    impl_id: None,
+                }),
+                confidence: None,
+                is_experimental: false,
+            },
+            None => Diff {
+                severity: ITEM_REMOVED.default_severity,
+                id: ITEM_REMOVED,
+                before: before_location,
+                after: None,
+                confidence: None,
+                is_experimental: false,
+            },
+        };
+        changes.push(change);
+    }
+}
+
+pub const DEPRECATION_ADDED: Id = Id {
+    name: "deprecation-added",
+    explanation: "Item deprecated, warning code using it ahead of its removal",
+    category: Category::Changed,
+    default_severity: Severity::Report,
+};
+
+/// Report items that became `#[deprecated]`
+///
+/// An item reachable under several paths is only reported once.  Associated items are reported
+/// with their trait's or type's path.
+pub fn deprecations(before: &crate::Api, after: &crate::Api, changes: &mut Vec<Diff>) {
+    let before_paths: HashMap<_, _> = local_paths(before)
+        .map(|(path_id, path)| ((relative_path(before, &path.path), path.kind), path_id))
+        .collect();
+    let after_paths: BTreeMap<_, _> = local_paths(after)
+        .filter(|(_, path)| path.kind != crate::PathKind::Import)
+        .map(|(path_id, path)| ((relative_path(after, &path.path), path.kind), path_id))
+        .collect();
+
+    let mut reported = HashSet::new();
+    for (key, after_id) in after_paths {
+        let before_id = match before_paths.get(&key) {
+            Some(before_id) => *before_id,
+            None => continue,
+        };
+        let (was_deprecated, is_deprecated) =
+            match (path_item(before, before_id), path_item(after, after_id)) {
+                (Some(before_item), Some(after_item)) => (
+                    before_item.deprecated.is_some(),
+                    after_item.deprecated.is_some(),
+                ),
+                _ => continue,
+            };
+        if was_deprecated || !is_deprecated {
+            continue;
+        }
+        let item_id = after.paths.get(after_id).and_then(|path| path.item_id);
+        if !reported.insert(item_id) {
+            continue;
+        }
+        changes.push(Diff {
+            severity: DEPRECATION_ADDED.default_severity,
+            id: DEPRECATION_ADDED,
+            before: path_location(before, before_id),
+            after: path_location(after, after_id),
+            confidence: None,
+            is_experimental: false,
+        });
+    }
+
+    // Associated items have no paths of their own, so are found through their trait or type
+    for kind in [
+        crate::PathKind::Struct,
+        crate::PathKind::Enum,
+        crate::PathKind::Union,
+        crate::PathKind::Trait,
+    ] {
+        let after_types = paths_by_kind(after, kind);
+        for (relative, before_id) in paths_by_kind(before, kind) {
+            let after_id = match after_types.get(relative) {
+                Some(after_id) => *after_id,
+                None => continue,
+            };
+            let (before_item, after_item) =
+                match (path_item(before, before_id), path_item(after, after_id)) {
+                    (Some(before_item), Some(after_item)) => (before_item, after_item),
+                    _ => continue,
+                };
+            let before_members: HashMap<_, _> = associated_items(before, before_item).collect();
+            for (key, after_member_id) in associated_items(after, after_item) {
+                let before_member_id = match before_members.get(&key) {
+                    Some(before_member_id) => *before_member_id,
+                    None => continue,
+                };
+                let was_deprecated = before
+                    .items
+                    .get(before_member_id)
+                    .map_or(false, |member| member.deprecated.is_some());
+                let is_deprecated = after
+                    .items
+                    .get(after_member_id)
+                    .map_or(false, |member| member.deprecated.is_some());
+                if was_deprecated || !is_deprecated || !reported.insert(Some(after_member_id)) {
+                    continue;
+                }
+                changes.push(Diff {
+                    severity: DEPRECATION_ADDED.default_severity,
+                    id: DEPRECATION_ADDED,
+                    before: path_location(before, before_id).map(|location| Location {
+                        item_id: Some(before_member_id),
+                        ..location
+                    }),
+                    after: path_location(after, after_id).map(|location| Location {
+                        item_id: Some(after_member_id),
+                        ..location
+                    }),
+                    confidence: None,
+                    is_experimental: false,
+                });
+            }
+        }
+    }
+}
+
+pub const FN_ARITY_CHANGED: Id = Id {
+    name: "fn-arity-changed",
+    explanation: "Number of parameters changed, breaking callers",
+    category: Category::Changed,
+    default_severity: Severity::Warn,
+};
+
+pub const FN_PARAMETER_CHANGED: Id = Id {
+    name: "fn-parameter-changed",
+    explanation: "Parameter type changed, breaking callers",
+    category: Category::Changed,
+    default_severity: Severity::Warn,
+};
+
+pub const FN_OUTPUT_CHANGED: Id = Id {
+    name: "fn-output-changed",
+    explanation: "Return type changed, breaking callers using the result",
+    category: Category::Changed,
+    default_severity: Severity::Warn,
+};
+
+pub const FN_UNSAFE_ADDED: Id = Id {
+    name: "fn-unsafe-added",
+    explanation: "Function became `unsafe`, breaking callers outside `unsafe` blocks",
+    category: Category::Changed,
+    default_severity: Severity::Warn,
+};
+
+pub const FN_UNSAFE_REMOVED: Id = Id {
+    name: "fn-unsafe-removed",
+    explanation: "Function is no longer `unsafe`, leaving its callers' `unsafe` blocks unused",
+    category: Category::Changed,
+    default_severity: Severity::Report,
+};
+
+pub const FN_CONST_REMOVED: Id = Id {
+    name: "fn-const-removed",
+    explanation: "Function is no longer `const`, breaking callers in const contexts",
+    category: Category::Changed,
+    default_severity: Severity::Warn,
+};
+
+pub const FN_ASYNC_CHANGED: Id = Id {
+    name: "fn-async-changed",
+    explanation: "Function became or is no longer `async`, changing what callers get back",
+    category: Category::Changed,
+    default_severity: Severity::Warn,
+};
+
+pub const FN_ABI_CHANGED: Id = Id {
+    name: "fn-abi-changed",
+    explanation: "Function's ABI changed, breaking callers and function pointers relying on it",
+    category: Category::Changed,
+    default_severity: Severity::Warn,
+};
+
+/// Compare the signatures of functions and methods found at the same path
+///
+/// Associated functions of traits and of types' own impl blocks are compared too, located by
+/// their parent's path along with the function.  A changed number of parameters is reported
+/// instead of their types, as they no longer line up.  Qualifiers like `unsafe` and the ABI are
+/// reported apart from the types, as they break callers of an otherwise unchanged signature, while
+/// becoming `const` isn't reported at all.
+pub fn signatures(before: &crate::Api, after: &crate::Api, changes: &mut Vec<Diff>) {
+    let after_paths: HashMap<_, _> = local_paths(after)
+        .map(|(path_id, path)| ((relative_path(after, &path.path), path.kind), path_id))
+        .collect();
+    for (before_id, before_path) in local_paths(before) {
+        let after_id =
+            match after_paths.get(&(relative_path(before, &before_path.path), before_path.kind)) {
+                Some(after_id) => *after_id,
+                None => continue,
+            };
+        let after_path = after.paths.get(after_id).unwrap();
+        let mut compared = Vec::new();
+        if matches!(
+            before_path.kind,
+            crate::PathKind::Function | crate::PathKind::Method
+        ) {
+            if let (Some(before_item_id), Some(after_item_id)) =
+                (before_path.item_id, after_path.item_id)
+            {
+                compared.push((before_item_id, after_item_id));
+            }
+        }
+        if let (Some(before_item), Some(after_item)) =
+            (path_item(before, before_id), path_item(after, after_id))
+        {
+            let after_members = associated_fns(after, after_item);
+            compared.extend(associated_fns(before, before_item).into_iter().filter_map(
+                |(key, before_member_id)| Some((before_member_id, *after_members.get(&key)?)),
+            ));
+        }
+
+        for (before_item_id, after_item_id) in compared {
+            let (before_item, after_item) = match (
+                before.items.get(before_item_id),
+                after.items.get(after_item_id),
+            ) {
+                (Some(before_item), Some(after_item)) => (before_item, after_item),
+                _ => continue,
+            };
+            for id in signature_changes(before_item, after_item) {
+                changes.push(Diff {
+                    severity: id.default_severity,
+                    id,
+                    before: Some(Location {
+                        crate_id: before_path.crate_id,
+                        path_id: Some(before_id),
+                        item_id: Some(before_item_id),
+                        impl_id: None,
+                    }),
+                    after: Some(Location {
+                        crate_id: after_path.crate_id,
+                        path_id: Some(after_id),
+                        item_id: Some(after_item_id),
+                        impl_id: None,
+                    }),
+                    confidence: None,
+                    is_experimental: false,
+                });
+            }
+        }
+    }
+}
+
+fn signature_changes(before_item: &crate::Item, after_item: &crate::Item) -> Vec<Id> {
+    let mut ids = Vec::new();
+    let (before, after) = match (&before_item.signature, &after_item.signature) {
+        (Some(before), Some(after)) => (before, after),
+        _ => return ids,
+    };
+    if before.inputs.len() != after.inputs.len() || before.c_variadic != after.c_variadic {
+        ids.push(FN_ARITY_CHANGED);
+    } else if before
+        .inputs
+        .iter()
+        .zip(&after.inputs)
+        .any(|(before_input, after_input)| before_input.type_ != after_input.type_)
+    {
+        ids.push(FN_PARAMETER_CHANGED);
+    }
+    if before.output != after.output {
+        ids.push(FN_OUTPUT_CHANGED);
+    }
+    match (before_item.is_unsafe, after_item.is_unsafe) {
+        (false, true) => ids.push(FN_UNSAFE_ADDED),
+        (true, false) => ids.push(FN_UNSAFE_REMOVED),
+        _ => {}
+    }
+    if before.is_const && !after.is_const {
+        ids.push(FN_CONST_REMOVED);
+    }
+    // `output` is the declared type, so it doesn't change along with this
+    if before.is_async != after.is_async {
+        ids.push(FN_ASYNC_CHANGED);
+    }
+    if before_item.abi != after_item.abi {
+        ids.push(FN_ABI_CHANGED);
+    }
+    ids
+}
+
+/// A trait's or type's associated functions, keyed by the type of their impl block and name
+fn associated_fns(
+    api: &crate::Api,
+    item: &crate::Item,
+) -> HashMap<(String, String), crate::ItemId> {
+    associated_items(api, item)
+        .filter(|(_, item_id)| {
+            api.items
+                .get(*item_id)
+                .map_or(false, |member| member.signature.is_some())
+        })
+        .collect()
+}
+
+/// A trait's or type's associated items, keyed by the type of their impl block and name
+fn associated_items<'a>(
+    api: &'a crate::Api,
+    item: &'a crate::Item,
+) -> impl Iterator<Item = ((String, String), crate::ItemId)> + 'a {
+    let trait_items = item
+        .trait_items
+        .iter()
+        .map(|item_id| (String::new(), *item_id));
+    let inherent_items = item.inherent_impls.iter().flat_map(|inherent_impl| {
+        inherent_impl
+            .items
+            .iter()
+            .map(move |item_id| (inherent_impl.for_.clone(), *item_id))
+    });
+    trait_items
+        .chain(inherent_items)
+        .filter_map(|(for_, item_id)| {
+            let member = api.items.get(item_id)?;
+            Some(((for_, member.name.clone()?), item_id))
+        })
+}
+
+pub const CONST_TYPE_CHANGED: Id = Id {
+    name: "const-type-changed",
+    explanation: "Type of a const or static changed, breaking its users",
+    category: Category::Changed,
+    default_severity: Severity::Warn,
+};
+
+pub const CONST_VALUE_CHANGED: Id = Id {
+    name: "const-value-changed",
+    explanation: "Value of a const or static changed",
+    category: Category::Changed,
+    default_severity: Severity::Allow,
+};
+
+pub const STATIC_MUTABILITY_CHANGED: Id = Id {
+    name: "static-mutability-changed",
+    explanation: "Static became or is no longer `static mut`, breaking its readers or writers",
+    category: Category::Changed,
+    default_severity: Severity::Warn,
+};
+
+/// Compare the declarations of consts and statics found at the same path
+///
+/// Values are only compared when rustdoc has them for both, which for consts is only literals.
+/// A changed value is allowed by default, as most are free to change, like a version number.
+pub fn consts(before: &crate::Api, after: &crate::Api, changes: &mut Vec<Diff>) {
+    for kind in [crate::PathKind::Constant, crate::PathKind::Static] {
+        let after_paths = paths_by_kind(after, kind);
+        for (relative, before_id) in paths_by_kind(before, kind) {
+            let after_id = match after_paths.get(relative) {
+                Some(after_id) => *after_id,
+                None => continue,
+            };
+            let (before_item, after_item) =
+                match (path_item(before, before_id), path_item(after, after_id)) {
+                    (Some(before_item), Some(after_item)) => (before_item, after_item),
+                    _ => continue,
+                };
+
+            let mut ids = Vec::new();
+            if before_item.type_ != after_item.type_ {
+                ids.push(CONST_TYPE_CHANGED);
+            }
+            if before_item.is_mutable != after_item.is_mutable {
+                ids.push(STATIC_MUTABILITY_CHANGED);
+            }
+            if let (Some(before_value), Some(after_value)) = (&before_item.value, &after_item.value)
+            {
+                if before_value != after_value {
+                    ids.push(CONST_VALUE_CHANGED);
+                }
+            }
+            for id in ids {
+                changes.push(Diff {
+                    severity: id.default_severity,
+                    id,
+                    before: path_location(before, before_id),
+                    after: path_location(after, after_id),
+                    confidence: None,
+                    is_experimental: false,
+                });
+            }
+        }
+    }
+}
+
+pub const TYPE_ALIAS_CHANGED: Id = Id {
+    name: "type-alias-changed",
+    explanation: "Type alias stands for a different type, breaking its users",
+    category: Category::Changed,
+    default_severity: Severity::Warn,
+};
+
+/// Report type aliases found at the same path that stand for a different type
+///
+/// Changes to the alias's own generic parameters are left to [`generics`].
+pub fn type_aliases(before: &crate::Api, after: &crate::Api, changes: &mut Vec<Diff>) {
+    let after_paths = paths_by_kind(after, crate::PathKind::Typedef);
+    for (relative, before_id) in paths_by_kind(before, crate::PathKind::Typedef) {
+        let after_id = match after_paths.get(relative) {
+            Some(after_id) => *after_id,
+            None => continue,
+        };
+        let (before_item, after_item) =
+            match (path_item(before, before_id), path_item(after, after_id)) {
+                (Some(before_item), Some(after_item)) => (before_item, after_item),
+                _ => continue,
+            };
+        if before_item.type_ == after_item.type_ {
+            continue;
+        }
+        changes.push(Diff {
+            severity: TYPE_ALIAS_CHANGED.default_severity,
+            id: TYPE_ALIAS_CHANGED,
+            before: path_location(before, before_id),
+            after: path_location(after, after_id),
+            confidence: None,
+            is_experimental: false,
+        });
+    }
+}
+
+pub const MACRO_RULE_REMOVED: Id = Id {
+    name: "macro-rule-removed",
+    explanation: "Macro rule removed or its matcher changed, breaking invocations relying on it",
+    category: Category::Changed,
+    default_severity: Severity::Warn,
+};
+
+pub const MACRO_RULE_ADDED: Id = Id {
+    name: "macro-rule-added",
+    explanation: "Macro rule added, accepting new invocations",
+    category: Category::Changed,
+    default_severity: Severity::Report,
+};
+
+/// Compare the rules of `macro_rules!` macros found at the same path by their matchers
+///
+/// A changed matcher is reported as a removed rule, as invocations that matched it may no longer
+/// match any rule.
+pub fn macros(before: &crate::Api, after: &crate::Api, changes: &mut Vec<Diff>) {
+    let after_paths = paths_by_kind(after, crate::PathKind::Macro);
+    for (relative, before_id) in paths_by_kind(before, crate::PathKind::Macro) {
+        let after_id = match after_paths.get(relative) {
+            Some(after_id) => *after_id,
+            None => continue,
+        };
+        let (before_item, after_item) =
+            match (path_item(before, before_id), path_item(after, after_id)) {
+                (Some(before_item), Some(after_item)) => (before_item, after_item),
+                _ => continue,
+            };
+        let removed = before_item
+            .matchers
+            .iter()
+            .any(|matcher| !after_item.matchers.contains(matcher));
+        let added = after_item
+            .matchers
+            .iter()
+            .any(|matcher| !before_item.matchers.contains(matcher));
+        for (id, changed) in [(MACRO_RULE_REMOVED, removed), (MACRO_RULE_ADDED, added)] {
+            if changed {
+                changes.push(Diff {
+                    severity: id.default_severity,
+                    id,
+                    before: path_location(before, before_id),
+                    after: path_location(after, after_id),
+                    confidence: None,
+                    is_experimental: false,
+                });
+            }
+        }
+    }
+}
+
+pub const DERIVE_HELPER_REMOVED: Id = Id {
+    name: "derive-helper-removed",
+    explanation: "Derive macro helper attribute removed, breaking items using it",
+    category: Category::Removed,
+    default_severity: Severity::Warn,
+};
+
+/// Report derive macros found at the same path that no longer declare a helper attribute
+pub fn derive_helpers(before: &crate::Api, after: &crate::Api, changes: &mut Vec<Diff>) {
+    let after_paths = paths_by_kind(after, crate::PathKind::ProcDerive);
+    for (relative, before_id) in paths_by_kind(before, crate::PathKind::ProcDerive) {
+        let after_id = match after_paths.get(relative) {
+            Some(after_id) => *after_id,
+            None => continue,
+        };
+        let (before_item, after_item) =
+            match (path_item(before, before_id), path_item(after, after_id)) {
+                (Some(before_item), Some(after_item)) => (before_item, after_item),
+                _ => continue,
+            };
+        if before_item
+            .helpers
+            .iter()
+            .any(|helper| !after_item.helpers.contains(helper))
+        {
+            changes.push(Diff {
+                severity: DERIVE_HELPER_REMOVED.default_severity,
+                id: DERIVE_HELPER_REMOVED,
+                before: path_location(before, before_id),
+                after: path_location(after, after_id),
+                confidence: None,
+                is_experimental: false,
+            });
+        }
+    }
+}
+
+pub const VARIANT_ADDED: Id = Id {
+    name: "variant-added",
+    explanation: "Variant added to an exhaustive enum, breaking exhaustive `match`es",
+    category: Category::Added,
+    default_severity: Severity::Warn,
+};
+
+pub const NON_EXHAUSTIVE_VARIANT_ADDED: Id = Id {
+    name: "non-exhaustive-variant-added",
+    explanation: "Variant added to a `#[non_exhaustive]` enum",
+    category: Category::Added,
+    default_severity: Severity::Report,
+};
+
+pub const VARIANT_REMOVED: Id = Id {
+    name: "variant-removed",
+    explanation: "Variant removed, breaking users constructing or matching on it",
+    category: Category::Removed,
+    default_severity: Severity::Warn,
+};
+
+pub const VARIANT_FIELDS_CHANGED: Id = Id {
+    name: "variant-fields-changed",
+    explanation: "Variant fields changed, breaking users constructing or matching on it",
+    category: Category::Changed,
+    default_severity: Severity::Warn,
+};
+
+/// Compare the variants of enums found at the same path
+///
+/// Whether adding a variant breaks `match`es depends on the enum being `#[non_exhaustive]`
+/// before the change.  Likewise, adding fields to a `#[non_exhaustive]` struct variant is
+/// allowed, but changing how a variant is declared, like from `Abc` to `Abc()`, isn't.  Variants
+/// of removed or renamed enums are left to [`removed_items`] and [`renamed_items`].
+pub fn variants(before: &crate::Api, after: &crate::Api, changes: &mut Vec<Diff>) {
+    let is_non_exhaustive = |api, path_id| {
+        path_item(api, path_id).map_or(false, |item: &crate::Item| item.is_non_exhaustive)
+    };
+
+    let before_enums = paths_by_kind(before, crate::PathKind::Enum);
+    let after_enums = paths_by_kind(after, crate::PathKind::Enum);
+    let before_variants = paths_by_kind(before, crate::PathKind::Variant);
+    let after_variants = paths_by_kind(after, crate::PathKind::Variant);
+
+    for (relative, before_id) in &before_variants {
+        let has_enum = parent_path(relative).map_or(false, |parent| {
+            before_enums.contains_key(parent) && after_enums.contains_key(parent)
+        });
+        if !has_enum {
+            continue;
+        }
+        let after_id = match after_variants.get(relative) {
+            Some(after_id) => *after_id,
+            None => {
+                changes.push(Diff {
+                    severity: VARIANT_REMOVED.default_severity,
+                    id: VARIANT_REMOVED,
+                    before: path_location(before, *before_id),
+                    after: None,
+                    confidence: None,
+                    is_experimental: false,
+                });
+                continue;
+            }
+        };
+
+        let (before_item, after_item) =
+            match (path_item(before, *before_id), path_item(after, after_id)) {
+                (Some(before_item), Some(after_item)) => (before_item, after_item),
+                _ => continue,
+            };
+        let (before_fields, after_fields) = (&before_item.fields, &after_item.fields);
+        let only_added = before_fields
+            .iter()
+            .all(|field| after_fields.contains(field));
+        let allowed = only_added
+            && is_non_exhaustive(before, *before_id)
+            && is_non_exhaustive(after, after_id);
+        // Switching between unit, tuple, and struct syntax breaks patterns, even without fields
+        if before_item.variant_kind != after_item.variant_kind
+            || (before_fields != after_fields && !allowed)
+        {
+            changes.push(Diff {
+                severity: VARIANT_FIELDS_CHANGED.default_severity,
+                id: VARIANT_FIELDS_CHANGED,
+                before: path_location(before, *before_id),
+                after: path_location(after, after_id),
+                confidence: None,
+                is_experimental: false,
+            });
+        }
+    }
+
+    for (relative, after_id) in &after_variants {
+        if before_variants.contains_key(relative) {
+            continue;
+        }
+        let before_enum = match parent_path(relative).and_then(|parent| before_enums.get(parent)) {
+            Some(before_enum) => *before_enum,
+            None => continue,
+        };
+        let id = if is_non_exhaustive(before, before_enum) {
+            NON_EXHAUSTIVE_VARIANT_ADDED
+        } else {
+            VARIANT_ADDED
+        };
+        changes.push(Diff {
+            severity: id.default_severity,
+            id,
+            before: path_location(before, before_enum),
+            after: path_location(after, *after_id),
+            confidence: None,
+            is_experimental: false,
+        });
+    }
+}
+
+pub const STRUCT_FIELD_ADDED: Id = Id {
+    name: "struct-field-added",
+    explanation: "Public field added to a struct with only public fields, breaking struct literals",
+    category: Category::Added,
+    default_severity: Severity::Warn,
+};
+
+pub const STRUCT_PRIVATE_FIELD_ADDED: Id = Id {
+    name: "struct-private-field-added",
+    explanation:
+        "Private field added to a struct with only public fields, breaking struct literals",
+    category: Category::Added,
+    default_severity: Severity::Warn,
+};
+
+pub const STRUCT_FIELD_REMOVED: Id = Id {
+    name: "struct-field-removed",
+    explanation: "Public field removed or made private, breaking users accessing it",
+    category: Category::Removed,
+    default_severity: Severity::Warn,
+};
+
+pub const STRUCT_FIELD_CHANGED: Id = Id {
+    name: "struct-field-type-changed",
+    explanation: "Public field type changed, breaking users accessing it",
+    category: Category::Changed,
+    default_severity: Severity::Warn,
+};
+
+/// Compare the public fields of structs found at the same path
+///
+/// Users can only construct and exhaustively destructure a struct when it is exhaustive and all
+/// of its fields are public, so only then is adding a field breaking.
+pub fn struct_fields(before: &crate::Api, after: &crate::Api, changes: &mut Vec<Diff>) {
+    let after_structs = paths_by_kind(after, crate::PathKind::Struct);
+    for (relative, before_id) in paths_by_kind(before, crate::PathKind::Struct) {
+        let after_id = match after_structs.get(relative) {
+            Some(after_id) => *after_id,
+            None => continue,
+        };
+        let (before_item, after_item) =
+            match (path_item(before, before_id), path_item(after, after_id)) {
+                (Some(before_item), Some(after_item)) => (before_item, after_item),
+                _ => continue,
+            };
+        let public_fields = |item: &'_ crate::Item| -> Vec<_> {
+            item.fields
+                .iter()
+                .filter(|field| field.visibility == crate::Visibility::Public)
+                .cloned()
+                .collect()
+        };
+        let before_fields = public_fields(before_item);
+        let after_fields = public_fields(after_item);
+
+        let mut ids = Vec::new();
+        let field_changes = before_fields.iter().filter_map(|before_field| {
+            match after_fields
+                .iter()
+                .find(|after_field| after_field.name == before_field.name)
+            {
+                Some(after_field) if after_field.type_ != before_field.type_ => {
+                    Some(STRUCT_FIELD_CHANGED)
+                }
+                Some(_) => None,
+                None => Some(STRUCT_FIELD_REMOVED),
+            }
+        });
+        for id in field_changes {
+            if !ids.contains(&id) {
+                ids.push(id);
+            }
+        }
+        let constructible = !before_item.is_non_exhaustive && !before_item.has_private_fields;
+        if constructible {
+            let added = after_fields.iter().any(|after_field| {
+                !before_fields
+                    .iter()
+                    .any(|before_field| before_field.name == after_field.name)
+            });
+            if added {
+                ids.push(STRUCT_FIELD_ADDED);
+            }
+            if after_item.has_private_fields {
+                ids.push(STRUCT_PRIVATE_FIELD_ADDED);
+            }
+        }
+
+        for id in ids {
+            changes.push(Diff {
+                severity: id.default_severity,
+                id,
+                before: path_location(before, before_id),
+                after: path_location(after, after_id),
+                confidence: None,
+                is_experimental: false,
+            });
+        }
+    }
+}
+
+pub const TRAIT_ITEM_ADDED: Id = Id {
+    name: "trait-item-added",
+    explanation: "Item without a default added to a trait, breaking implementors",
+    category: Category::Added,
+    default_severity: Severity::Warn,
+};
+
+pub const TRAIT_PROVIDED_ITEM_ADDED: Id = Id {
+    name: "trait-provided-item-added",
+    explanation:
+        "Item with a default added to a trait, which may conflict with implementors' own items",
+    category: Category::Added,
+    default_severity: Severity::Report,
+};
+
+pub const SEALED_TRAIT_ITEM_ADDED: Id = Id {
+    name: "sealed-trait-item-added",
+    explanation: "Item added to a sealed trait, which only this crate can implement",
+    category: Category::Added,
+    default_severity: Severity::Report,
+};
+
+pub const TRAIT_ITEM_REMOVED: Id = Id {
+    name: "trait-item-removed",
+    explanation: "Item removed from a trait, breaking callers and implementors that define it",
+    category: Category::Removed,
+    default_severity: Severity::Warn,
+};
+
+pub const TRAIT_ITEM_DEFAULT_REMOVED: Id = Id {
+    name: "trait-item-default-removed",
+    explanation: "Trait item lost its default, breaking implementors that relied on it",
+    category: Category::Changed,
+    default_severity: Severity::Warn,
+};
+
+pub const TRAIT_ITEM_DEFAULT_ADDED: Id = Id {
+    name: "trait-item-default-added",
+    explanation: "Trait item gained a default, so implementors may leave it out",
+    category: Category::Changed,
+    default_severity: Severity::Report,
+};
+
+pub const ASSOC_CONST_TYPE_CHANGED: Id = Id {
+    name: "assoc-const-type-changed",
+    explanation: "Associated const's type changed, breaking its users and implementors",
+    category: Category::Changed,
+    default_severity: Severity::Warn,
+};
+
+pub const ASSOC_TYPE_BOUND_ADDED: Id = Id {
+    name: "assoc-type-bound-added",
+    explanation: "Associated type's bounds tightened, breaking implementors not meeting them",
+    category: Category::Changed,
+    default_severity: Severity::Warn,
+};
+
+pub const ASSOC_TYPE_BOUND_REMOVED: Id = Id {
+    name: "assoc-type-bound-removed",
+    explanation: "Associated type's bounds loosened, breaking users relying on them",
+    category: Category::Changed,
+    default_severity: Severity::Warn,
+};
+
+pub const SUPERTRAIT_ADDED: Id = Id {
+    name: "supertrait-added",
+    explanation: "Supertrait added to a trait, breaking implementors that don't implement it",
+    category: Category::Added,
+    default_severity: Severity::Warn,
+};
+
+pub const SUPERTRAIT_REMOVED: Id = Id {
+    name: "supertrait-removed",
+    explanation: "Supertrait removed from a trait, breaking users relying on it through bounds",
+    category: Category::Removed,
+    default_severity: Severity::Warn,
+};
+
+pub const TRAIT_UNSAFE_ADDED: Id = Id {
+    name: "trait-unsafe-added",
+    explanation: "Trait became `unsafe`, breaking implementors without an `unsafe impl`",
+    category: Category::Changed,
+    default_severity: Severity::Warn,
+};
+
+pub const TRAIT_UNSAFE_REMOVED: Id = Id {
+    name: "trait-unsafe-removed",
+    explanation: "Trait is no longer `unsafe`, breaking implementors with an `unsafe impl`",
+    category: Category::Changed,
+    default_severity: Severity::Warn,
+};
+
+/// Report changes to the items, supertraits, and unsafety of traits found at the same path
+///
+/// Items found on both sides are compared by their declarations, like an associated const's type
+/// or an associated type's bounds.  Only the trait's own crate has to implement items added to a
+/// sealed trait, so that only depends on the trait being sealed before the change.  The same goes
+/// for new supertraits and associated type bounds, items losing their default, and the trait
+/// becoming or no longer being `unsafe`, which are only reported for traits that weren't sealed.
+/// Each change's location is the trait's path along with the affected item.
+pub fn trait_items(before: &crate::Api, after: &crate::Api, changes: &mut Vec<Diff>) {
+    // Functions and consts share a namespace that types don't
+    let members =
+        |api: &'_ crate::Api, item: &crate::Item| -> HashMap<(String, bool), crate::ItemId> {
+            item.trait_items
+                .iter()
+                .filter_map(|item_id| {
+                    let member = api.items.get(*item_id)?;
+                    let name = member.name.clone()?;
+                    Some(((name, member.signature.is_some()), *item_id))
+                })
+                .collect()
+        };
+    let member_location = |api, path_id, item_id| {
+        path_location(api, path_id).map(|location| Location {
+            item_id: Some(item_id),
+            ..location
+        })
+    };
+
+    let after_traits = paths_by_kind(after, crate::PathKind::Trait);
+    for (relative, before_id) in paths_by_kind(before, crate::PathKind::Trait) {
+        let after_id = match after_traits.get(relative) {
+            Some(after_id) => *after_id,
+            None => continue,
+        };
+        let (before_trait, after_trait) =
+            match (path_item(before, before_id), path_item(after, after_id)) {
+                (Some(before_trait), Some(after_trait)) => (before_trait, after_trait),
+                _ => continue,
+            };
+        let before_members = members(before, before_trait);
+        let after_members = members(after, after_trait);
+        for member_id in &after_trait.trait_items {
+            let member = after.items.get(*member_id).unwrap();
+            let key = match member.name.clone() {
+                Some(name) => (name, member.signature.is_some()),
+                None => continue,
+            };
+            let id = match before_members.get(&key) {
+                Some(before_member_id) => {
+                    let before_member = before.items.get(*before_member_id).unwrap();
+                    let mut ids = Vec::new();
+                    match (before_member.has_default, member.has_default) {
+                        (true, false) if !before_trait.is_sealed => {
+                            ids.push(TRAIT_ITEM_DEFAULT_REMOVED)
+                        }
+                        (false, true) => ids.push(TRAIT_ITEM_DEFAULT_ADDED),
+                        _ => {}
+                    }
+                    if before_member.type_ != member.type_ {
+                        ids.push(ASSOC_CONST_TYPE_CHANGED);
+                    }
+                    if !before_trait.is_sealed
+                        && member
+                            .bounds
+                            .iter()
+                            .any(|bound| !before_member.bounds.contains(bound))
+                    {
+                        ids.push(ASSOC_TYPE_BOUND_ADDED);
+                    }
+                    if before_member
+                        .bounds
+                        .iter()
+                        .any(|bound| !member.bounds.contains(bound))
+                    {
+                        ids.push(ASSOC_TYPE_BOUND_REMOVED);
+                    }
+                    for id in ids {
+                        changes.push(Diff {
+                            severity: id.default_severity,
+                            id,
+                            before: member_location(before, before_id, *before_member_id),
+                            after: member_location(after, after_id, *member_id),
+                            confidence: None,
+                            is_experimental: false,
+                        });
+                    }
+                    continue;
+                }
+                None if before_trait.is_sealed => SEALED_TRAIT_ITEM_ADDED,
+                None if member.has_default => TRAIT_PROVIDED_ITEM_ADDED,
+                None => TRAIT_ITEM_ADDED,
+            };
+            changes.push(Diff {
+                severity: id.default_severity,
+                id,
+                before: path_location(before, before_id),
+                after: member_location(after, after_id, *member_id),
+                confidence: None,
+                is_experimental: false,
+            });
+        }
+        for member_id in &before_trait.trait_items {
+            let member = before.items.get(*member_id).unwrap();
+            let key = match member.name.clone() {
+                Some(name) => (name, member.signature.is_some()),
+                None => continue,
+            };
+            if after_members.contains_key(&key) {
+                continue;
+            }
+            changes.push(Diff {
+                severity: TRAIT_ITEM_REMOVED.default_severity,
+                id: TRAIT_ITEM_REMOVED,
+                before: member_location(before, before_id, *member_id),
+                after: path_location(after, after_id),
+                confidence: None,
+                is_experimental: false,
+            });
+        }
+
+        if !before_trait.is_sealed
+            && after_trait
+                .supertraits
+                .iter()
+                .any(|supertrait| !before_trait.supertraits.contains(supertrait))
+        {
+            changes.push(Diff {
+                severity: SUPERTRAIT_ADDED.default_severity,
+                id: SUPERTRAIT_ADDED,
+                before: path_location(before, before_id),
+                after: path_location(after, after_id),
+                confidence: None,
+                is_experimental: false,
+            });
+        }
+        if before_trait
+            .supertraits
+            .iter()
+            .any(|supertrait| !after_trait.supertraits.contains(supertrait))
+        {
+            changes.push(Diff {
+                severity: SUPERTRAIT_REMOVED.default_severity,
+                id: SUPERTRAIT_REMOVED,
+                before: path_location(before, before_id),
+                after: path_location(after, after_id),
+                confidence: None,
+                is_experimental: false,
+            });
+        }
+
+        // Either way, implementors' `impl` blocks have to change
+        let unsafety = match (before_trait.is_unsafe, after_trait.is_unsafe) {
+            (false, true) => Some(TRAIT_UNSAFE_ADDED),
+            (true, false) => Some(TRAIT_UNSAFE_REMOVED),
+            _ => None,
+        };
+        if let Some(id) = unsafety.filter(|_| !before_trait.is_sealed) {
+            changes.push(Diff {
+                severity: id.default_severity,
+                id,
+                before: path_location(before, before_id),
+                after: path_location(after, after_id),
+                confidence: None,
+                is_experimental: false,
+            });
+        }
+    }
+}
+
+pub const INHERENT_ITEM_REMOVED: Id = Id {
+    name: "inherent-item-removed",
+    explanation: "Associated item removed from a type's own impl block, breaking its users",
+    category: Category::Removed,
+    default_severity: Severity::Warn,
+};
+
+pub const INHERENT_ITEM_ADDED: Id = Id {
+    name: "inherent-item-added",
+    explanation: "Associated item added to a type's own impl block, which may shadow trait methods",
+    category: Category::Added,
+    default_severity: Severity::Report,
+};
+
+/// Report associated items added to or removed from the impl blocks of types at the same path
+///
+/// Impl blocks are matched by the type they are for, like `Foo<u8>`, so moving an item between
+/// blocks for different generic arguments is a removal and an addition.  Each change's location
+/// is the type's path along with the affected item.
+///
+/// Like with paths, removing an item that was already deprecated is only reported.
+pub fn inherent_items(before: &crate::Api, after: &crate::Api, changes: &mut Vec<Diff>) {
+    // Functions and consts share a namespace
+    let members =
+        |api: &'_ crate::Api, item: &crate::Item| -> Vec<((String, String), crate::ItemId)> {
+            item.inherent_impls
+                .iter()
+                .flat_map(|inherent_impl| {
+                    inherent_impl.items.iter().filter_map(move |item_id| {
+                        let name = api.items.get(*item_id)?.name.clone()?;
+                        Some(((inherent_impl.for_.clone(), name), *item_id))
+                    })
+                })
+                .collect()
+        };
+    let member_location = |api, path_id, item_id| {
+        path_location(api, path_id).map(|location| Location {
+            item_id: Some(item_id),
+            ..location
+        })
+    };
+
+    for kind in [
+        crate::PathKind::Struct,
+        crate::PathKind::Enum,
+        crate::PathKind::Union,
+    ] {
+        let after_types = paths_by_kind(after, kind);
+        for (relative, before_id) in paths_by_kind(before, kind) {
+            let after_id = match after_types.get(relative) {
+                Some(after_id) => *after_id,
+                None => continue,
+            };
+            let (before_item, after_item) =
+                match (path_item(before, before_id), path_item(after, after_id)) {
+                    (Some(before_item), Some(after_item)) => (before_item, after_item),
+                    _ => continue,
+                };
+            let before_members = members(before, before_item);
+            let after_members = members(after, after_item);
+            let before_keys: HashSet<_> = before_members.iter().map(|(key, _)| key).collect();
+            let after_keys: HashSet<_> = after_members.iter().map(|(key, _)| key).collect();
+            for (key, member_id) in &before_members {
+                if after_keys.contains(key) {
+                    continue;
+                }
+                let was_deprecated = before
+                    .items
+                    .get(*member_id)
+                    .map_or(false, |member| member.deprecated.is_some());
+                let id = if was_deprecated {
+                    DEPRECATED_ITEM_REMOVED
+                } else {
+                    INHERENT_ITEM_REMOVED
+                };
+                changes.push(Diff {
+                    severity: id.default_severity,
+                    id,
+                    before: member_location(before, before_id, *member_id),
+                    after: path_location(after, after_id),
+                    confidence: None,
+                    is_experimental: false,
+                });
+            }
+            for (key, member_id) in &after_members {
+                if before_keys.contains(key) {
+                    continue;
+                }
+                changes.push(Diff {
+                    severity: INHERENT_ITEM_ADDED.default_severity,
+                    id: INHERENT_ITEM_ADDED,
+                    before: path_location(before, before_id),
+                    after: member_location(after, after_id, *member_id),
+                    confidence: None,
+                    is_experimental: false,
+                });
+            }
+        }
+    }
+}
+
+pub const AUTO_TRAIT_REMOVED: Id = Id {
+    name: "auto-trait-removed",
+    explanation:
+        "Type stopped implementing an auto trait, like `Send`, breaking users relying on it",
+    category: Category::Removed,
+    default_severity: Severity::Warn,
+};
+
+/// Report types found at the same path that no longer implement an auto trait
+///
+/// Auto traits, like `Send` and `Sync`, are implemented based on a type's fields, so a private
+/// field can silently take them away.
+pub fn auto_traits(before: &crate::Api, after: &crate::Api, changes: &mut Vec<Diff>) {
+    for kind in [
+        crate::PathKind::Struct,
+        crate::PathKind::Enum,
+        crate::PathKind::Union,
+    ] {
+        let after_types = paths_by_kind(after, kind);
+        for (relative, before_id) in paths_by_kind(before, kind) {
+            let after_id = match after_types.get(relative) {
+                Some(after_id) => *after_id,
+                None => continue,
+            };
+            let (before_item, after_item) =
+                match (path_item(before, before_id), path_item(after, after_id)) {
+                    (Some(before_item), Some(after_item)) => (before_item, after_item),
+                    _ => continue,
+                };
+            let removed = before_item
+                .auto_traits
+                .iter()
+                .any(|auto_trait| !after_item.auto_traits.contains(auto_trait));
+            if removed {
+                changes.push(Diff {
+                    severity: AUTO_TRAIT_REMOVED.default_severity,
+                    id: AUTO_TRAIT_REMOVED,
+                    before: path_location(before, before_id),
+                    after: path_location(after, after_id),
+                    confidence: None,
+                    is_experimental: false,
+                });
+            }
+        }
+    }
+}
+
+pub const BLANKET_IMPL_REMOVED: Id = Id {
+    name: "blanket-impl-removed",
+    explanation:
+        "Type stopped being covered by a blanket impl, breaking users of the trait it provided",
+    category: Category::Removed,
+    default_severity: Severity::Warn,
+};
+
+/// Report types found at the same path that lost a blanket impl which is still around
+///
+/// A type falls out of a blanket impl, like `impl<T: Display> Show for T`, when it stops
+/// meeting the impl's bounds.  Blanket impls that were removed outright are left to
+/// [`trait_impls`], so the change is attributed to the impl rather than to every type it covered.
+pub fn blanket_impls(before: &crate::Api, after: &crate::Api, changes: &mut Vec<Diff>) {
+    let after_impls = impls_by_key(after);
+    let keys = |api: &crate::Api, item: &crate::Item| -> Vec<(String, String)> {
+        item.blanket_impls
+            .iter()
+            .filter_map(|impl_id| api.impls.get(*impl_id))
+            .map(|impl_| impl_key(api, impl_))
+            .collect()
+    };
+
+    for kind in [
+        crate::PathKind::Struct,
+        crate::PathKind::Enum,
+        crate::PathKind::Union,
+    ] {
+        let after_types = paths_by_kind(after, kind);
+        for (relative, before_id) in paths_by_kind(before, kind) {
+            let after_id = match after_types.get(relative) {
+                Some(after_id) => *after_id,
+                None => continue,
+            };
+            let (before_item, after_item) =
+                match (path_item(before, before_id), path_item(after, after_id)) {
+                    (Some(before_item), Some(after_item)) => (before_item, after_item),
+                    _ => continue,
+                };
+            let after_keys = keys(after, after_item);
+            for (key, before_impl_id) in keys(before, before_item)
+                .into_iter()
+                .zip(before_item.blanket_impls.iter())
+            {
+                if after_keys.contains(&key) || !after_impls.contains_key(&key) {
+                    continue;
+                }
+                changes.push(Diff {
+                    severity: BLANKET_IMPL_REMOVED.default_severity,
+                    id: BLANKET_IMPL_REMOVED,
+                    before: path_location(before, before_id).map(|location| Location {
+                        impl_id: Some(*before_impl_id),
+                        ..location
+                    }),
+                    after: path_location(after, after_id),
+                    confidence: None,
+                    is_experimental: false,
+                });
+            }
+        }
+    }
+}
+
+pub const GENERIC_PARAM_ADDED: Id = Id {
+    name: "generic-param-added",
+    explanation: "Generic parameter without a default added, breaking users naming the item",
+    category: Category::Added,
+    default_severity: Severity::Warn,
+};
+
+pub const DEFAULTED_GENERIC_PARAM_ADDED: Id = Id {
+    name: "defaulted-generic-param-added",
+    explanation: "Generic parameter with a default added",
+    category: Category::Added,
+    default_severity: Severity::Report,
+};
+
+pub const GENERIC_PARAM_REMOVED: Id = Id {
+    name: "generic-param-removed",
+    explanation: "Generic parameter removed, breaking users naming it",
+    category: Category::Removed,
+    default_severity: Severity::Warn,
+};
+
+pub const GENERIC_BOUND_TIGHTENED: Id = Id {
+    name: "generic-bound-tightened",
+    explanation: "Generic bounds tightened, breaking users whose types don't meet them",
+    category: Category::Changed,
+    default_severity: Severity::Warn,
+};
+
+pub const GENERIC_BOUND_LOOSENED: Id = Id {
+    name: "generic-bound-loosened",
+    explanation: "Generic bounds loosened",
+    category: Category::Changed,
+    default_severity: Severity::Report,
+};
+
+/// Compare the generics of items found at the same path
+///
+/// Parameters are matched by their position among those of the same kind, so renaming one
+/// isn't a change.  Lifetime parameters are left out, as they are usually inferred.  A bound is
+/// the same whether it is inline or in the `where` clause.
+pub fn generics(before: &crate::Api, after: &crate::Api, changes: &mut Vec<Diff>) {
+    for kind in [
+        crate::PathKind::Struct,
+        crate::PathKind::Enum,
+        crate::PathKind::Union,
+        crate::PathKind::Trait,
+        crate::PathKind::Typedef,
+        crate::PathKind::Function,
+        crate::PathKind::Method,
+    ] {
+        let after_paths = paths_by_kind(after, kind);
+        for (relative, before_id) in paths_by_kind(before, kind) {
+            let after_id = match after_paths.get(relative) {
+                Some(after_id) => *after_id,
+                None => continue,
+            };
+            let (before_generics, after_generics) =
+                match (path_item(before, before_id), path_item(after, after_id)) {
+                    (Some(before_item), Some(after_item)) => {
+                        (&before_item.generics, &after_item.generics)
+                    }
+                    _ => continue,
+                };
+
+            let mut ids = Vec::new();
+            let before_params = positional_params(before_generics);
+            let after_params = positional_params(after_generics);
+            for (position, after_param) in &after_params {
+                if !before_params.contains_key(position) {
+                    ids.push(if after_param.default.is_some() {
+                        DEFAULTED_GENERIC_PARAM_ADDED
+                    } else {
+                        GENERIC_PARAM_ADDED
+                    });
+                }
+            }
+            if before_params
+                .keys()
+                .any(|position| !after_params.contains_key(position))
+            {
+                ids.push(GENERIC_PARAM_REMOVED);
+            }
+
+            let before_bounds = generic_bounds(before_generics);
+            let after_bounds = generic_bounds(after_generics);
+            if after_bounds.difference(&before_bounds).next().is_some() {
+                ids.push(GENERIC_BOUND_TIGHTENED);
+            }
+            if before_bounds.difference(&after_bounds).next().is_some() {
+                ids.push(GENERIC_BOUND_LOOSENED);
+            }
+
+            let mut seen = Vec::new();
+            for id in ids {
+                if seen.contains(&id) {
+                    continue;
+                }
+                seen.push(id);
+                changes.push(Diff {
+                    severity: id.default_severity,
+                    id,
+                    before: path_location(before, before_id),
+                    after: path_location(after, after_id),
+                    confidence: None,
+                    is_experimental: false,
+                });
+            }
+        }
+    }
+}
+
+/// Type and const parameters by kind and position, like `("type", 0)` for `T` in `<'a, T>`
+fn positional_params(
+    generics: &crate::Generics,
+) -> BTreeMap<(&'static str, usize), &crate::GenericParam> {
+    let mut types = 0..;
+    let mut consts = 0..;
+    generics
+        .params
+        .iter()
+        .filter_map(|param| {
+            let position = match param.kind {
+                crate::GenericParamKind::Lifetime => return None,
+                crate::GenericParamKind::Type => ("type", types.next().unwrap()),
+                crate::GenericParamKind::Const(_) => ("const", consts.next().unwrap()),
+            };
+            Some((position, param))
+        })
+        .collect()
+}
+
+/// Each bound with what it bounds, naming type parameters by position
+fn generic_bounds(generics: &crate::Generics) -> BTreeSet<(String, String)> {
+    let mut positions = HashMap::new();
+    for ((kind, index), param) in positional_params(generics) {
+        positions.insert(param.name.as_str(), format!("{}#{}", kind, index));
+    }
+    let target = |name: &str| {
+        positions
+            .get(name)
+            .cloned()
+            .unwrap_or_else(|| name.to_owned())
+    };
+
+    let mut bounds = BTreeSet::new();
+    for param in &generics.params {
+        for bound in &param.bounds {
+            bounds.insert((target(&param.name), bound.clone()));
+        }
+    }
+    for predicate in &generics.where_predicates {
+        match predicate {
+            crate::WherePredicate::Bound {
+                target: bounded,
+                bounds: predicate_bounds,
+            } => {
+                for bound in predicate_bounds {
+                    bounds.insert((target(bounded), bound.clone()));
+                }
+            }
+            crate::WherePredicate::Eq { lhs, rhs } => {
+                bounds.insert((target(lhs), format!("= {}", rhs)));
+            }
+        }
+    }
+    bounds
+}
+
+pub const VISIBILITY_REDUCED: Id = Id {
+    name: "visibility-reduced",
+    explanation: "Item is no longer public, like from `pub` to `pub(crate)`",
+    category: Category::Changed,
+    default_severity: Severity::Warn,
+};
+
+/// Report paths that stopped being public while still being in the API
+///
+/// Non-public items are only in the API when documenting private items, otherwise they are
+/// reported as removed.  Making a module non-public takes everything in it along, so only the
+/// outermost path is reported.  A re-export is judged by its own visibility, so narrowing a
+/// `pub use` is reported even when what it names stays public.
+pub fn visibility(before: &crate::Api, after: &crate::Api, changes: &mut Vec<Diff>) {
+    let before_paths: BTreeMap<_, _> = local_paths(before)
+        .map(|(path_id, path)| (relative_path(before, &path.path), path_id))
+        .collect();
+    let after_paths: BTreeMap<_, _> = local_paths(after)
+        .map(|(path_id, path)| (relative_path(after, &path.path), path_id))
+        .collect();
+    let is_public = |api: &crate::Api, paths: &BTreeMap<&str, crate::PathId>, relative| {
+        std::iter::successors(Some(relative), |path: &&str| parent_path(path)).all(|path| {
+            paths.get(path).map_or(true, |path_id| {
+                let path = api.paths.get(*path_id).unwrap();
+                if path.kind == crate::PathKind::Import {
+                    path.visibility == crate::Visibility::Public
+                } else {
+                    path_item(api, *path_id)
+                        .map_or(true, |item| item.visibility == crate::Visibility::Public)
+                }
+            })
+        })
+    };
+
+    for (relative, before_id) in &before_paths {
+        let kind = before.paths.get(*before_id).unwrap().kind;
+        if kind == crate::PathKind::Variant {
+            continue;
+        }
+        let after_id = match after_paths.get(relative) {
+            Some(after_id) => *after_id,
+            None => continue,
+        };
+        if !is_public(before, &before_paths, relative) || is_public(after, &after_paths, relative) {
+            continue;
+        }
+        let parent_reduced = parent_path(relative).map_or(false, |parent| {
+            is_public(before, &before_paths, parent) && !is_public(after, &after_paths, parent)
+        });
+        if parent_reduced {
+            continue;
+        }
+        changes.push(Diff {
+            severity: VISIBILITY_REDUCED.default_severity,
+            id: VISIBILITY_REDUCED,
+            before: path_location(before, *before_id),
+            after: path_location(after, after_id),
+            confidence: None,
+            is_experimental: false,
+        });
+    }
+}
+
+pub const REPR_ADDED: Id = Id {
+    name: "repr-added",
+    explanation: "`#[repr]` added to a type with the default representation",
+    category: Category::Added,
+    default_severity: Severity::Report,
+};
+
+pub const REPR_CHANGED: Id = Id {
+    name: "repr-changed",
+    explanation: "`#[repr]` changed, breaking FFI and code relying on the type's layout",
+    category: Category::Changed,
+    default_severity: Severity::Warn,
+};
+
+/// Compare the `#[repr]` of types found at the same path
+///
+/// Guaranteeing a layout, like with `C`, `transparent`, or a primitive for an enum, is
+/// compatible, while changing or dropping one, or changing alignment, is breaking.
+pub fn repr(before: &crate::Api, after: &crate::Api, changes: &mut Vec<Diff>) {
+    for kind in [
+        crate::PathKind::Struct,
+        crate::PathKind::Enum,
+        crate::PathKind::Union,
+    ] {
+        let after_types = paths_by_kind(after, kind);
+        for (relative, before_id) in paths_by_kind(before, kind) {
+            let after_id = match after_types.get(relative) {
+                Some(after_id) => *after_id,
+                None => continue,
+            };
+            let (before_repr, after_repr) =
+                match (path_item(before, before_id), path_item(after, after_id)) {
+                    (Some(before_item), Some(after_item)) => (&before_item.repr, &after_item.repr),
+                    _ => continue,
+                };
+            if before_repr == after_repr {
+                continue;
+            }
+            let changes_alignment = after_repr
+                .iter()
+                .any(|hint| hint.starts_with("packed") || hint.starts_with("align"));
+            let id = if before_repr.is_empty() && !changes_alignment {
+                REPR_ADDED
+            } else {
+                REPR_CHANGED
+            };
+            changes.push(Diff {
+                severity: id.default_severity,
+                id,
+                before: path_location(before, before_id),
+                after: path_location(after, after_id),
+                confidence: None,
+                is_experimental: false,
+            });
+        }
+    }
+}
+
+pub const NON_EXHAUSTIVE_ADDED: Id = Id {
+    name: "non-exhaustive-added",
+    explanation:
+        "`#[non_exhaustive]` added, breaking users constructing or exhaustively matching it",
+    category: Category::Changed,
+    default_severity: Severity::Warn,
+};
+
+pub const NON_EXHAUSTIVE_REMOVED: Id = Id {
+    name: "non-exhaustive-removed",
+    explanation: "`#[non_exhaustive]` removed, letting users construct or exhaustively match it",
+    category: Category::Changed,
+    default_severity: Severity::Report,
+};
+
+/// Compare the `#[non_exhaustive]` of structs, enums, and variants found at the same path
+///
+/// A struct with private fields already can't be constructed, and in patterns it already needs
+/// `..`, so marking it is compatible.
+pub fn non_exhaustive(before: &crate::Api, after: &crate::Api, changes: &mut Vec<Diff>) {
+    for kind in [
+        crate::PathKind::Struct,
+        crate::PathKind::Enum,
+        crate::PathKind::Variant,
+    ] {
+        let after_types = paths_by_kind(after, kind);
+        for (relative, before_id) in paths_by_kind(before, kind) {
+            let after_id = match after_types.get(relative) {
+                Some(after_id) => *after_id,
+                None => continue,
+            };
+            let (before_item, after_item) =
+                match (path_item(before, before_id), path_item(after, after_id)) {
+                    (Some(before_item), Some(after_item)) => (before_item, after_item),
+                    _ => continue,
+                };
+            let id = match (before_item.is_non_exhaustive, after_item.is_non_exhaustive) {
+                (false, true) if !before_item.has_private_fields => NON_EXHAUSTIVE_ADDED,
+                (true, false) => NON_EXHAUSTIVE_REMOVED,
+                _ => continue,
+            };
+            changes.push(Diff {
+                severity: id.default_severity,
+                id,
+                before: path_location(before, before_id),
+                after: path_location(after, after_id),
+                confidence: None,
+                is_experimental: false,
+            });
+        }
+    }
+}
+
+pub const MUST_USE_ADDED: Id = Id {
+    name: "must-use-added",
+    explanation: "`#[must_use]` added, warning code that ignores the value",
+    category: Category::Changed,
+    default_severity: Severity::Report,
+};
+
+pub const MUST_USE_REMOVED: Id = Id {
+    name: "must-use-removed",
+    explanation: "`#[must_use]` removed, no longer warning code that ignores the value",
+    category: Category::Changed,
+    default_severity: Severity::Report,
+};
+
+/// Compare the `#[must_use]` of functions, types, and traits found at the same path, and of
+/// their associated functions
+///
+/// Neither breaks compilation, unless users deny `unused_must_use`, but both change what users
+/// are warned about.
+pub fn must_use(before: &crate::Api, after: &crate::Api, changes: &mut Vec<Diff>) {
+    let mut push = |before_location: Option<Location>, after_location: Option<Location>| {
+        let is_must_use = |api: &crate::Api, location: Option<Location>| {
+            location
+                .and_then(|location| location.item_id)
+                .and_then(|item_id| api.items.get(item_id))
+                .map_or(false, |item| item.must_use.is_some())
+        };
+        let id = match (
+            is_must_use(before, before_location),
+            is_must_use(after, after_location),
+        ) {
+            (false, true) => MUST_USE_ADDED,
+            (true, false) => MUST_USE_REMOVED,
+            _ => return,
+        };
+        changes.push(Diff {
+            severity: id.default_severity,
+            id,
+            before: before_location,
+            after: after_location,
+            confidence: None,
+            is_experimental: false,
+        });
+    };
+
+    for kind in [
+        crate::PathKind::Function,
+        crate::PathKind::Struct,
+        crate::PathKind::Enum,
+        crate::PathKind::Union,
+        crate::PathKind::Trait,
+    ] {
+        let after_paths = paths_by_kind(after, kind);
+        for (relative, before_id) in paths_by_kind(before, kind) {
+            let after_id = match after_paths.get(relative) {
+                Some(after_id) => *after_id,
+                None => continue,
+            };
+            push(
+                path_location(before, before_id),
+                path_location(after, after_id),
+            );
+
+            let (before_item, after_item) =
+                match (path_item(before, before_id), path_item(after, after_id)) {
+                    (Some(before_item), Some(after_item)) => (before_item, after_item),
+                    _ => continue,
+                };
+            let after_members = associated_fns(after, after_item);
+            for (key, before_member_id) in associated_fns(before, before_item) {
+                let after_member_id = match after_members.get(&key) {
+                    Some(after_member_id) => *after_member_id,
+                    None => continue,
+                };
+                push(
+                    path_location(before, before_id).map(|location| Location {
+                        item_id: Some(before_member_id),
+                        ..location
+                    }),
+                    path_location(after, after_id).map(|location| Location {
+                        item_id: Some(after_member_id),
+                        ..location
+                    }),
+                );
+            }
+        }
+    }
+}
+
+pub const FEATURE_GATE_ADDED: Id = Id {
+    name: "feature-gate-added",
+    explanation: "Item moved behind a feature, breaking users without it enabled",
+    category: Category::Changed,
+    default_severity: Severity::Warn,
+};
+
+pub const FEATURE_GATE_REMOVED: Id = Id {
+    name: "feature-gate-removed",
+    explanation: "Item no longer behind a feature, so available without it",
+    category: Category::Changed,
+    default_severity: Severity::Report,
+};
+
+/// Compare the features that items and trait impls are `cfg`-gated behind
+///
+/// This tells an item moved behind a feature apart from one that was removed, as long as both
+/// APIs were built with the feature enabled, like they are by default.  Only the outermost
+/// change is reported, as what is within a module or type shares its gate, and associated items
+/// are reported with their trait's or type's path.
+pub fn feature_gates(before: &crate::Api, after: &crate::Api, changes: &mut Vec<Diff>) {
+    let gate_change = |before: &[String], after: &[String]| {
+        if after.iter().any(|feature| !before.contains(feature)) {
+            Some(FEATURE_GATE_ADDED)
+        } else if before.iter().any(|feature| !after.contains(feature)) {
+            Some(FEATURE_GATE_REMOVED)
+        } else {
+            None
+        }
+    };
+
+    let before_paths: HashMap<_, _> = local_paths(before)
+        .filter(|(_, path)| path.kind != crate::PathKind::Import)
+        .map(|(path_id, path)| ((relative_path(before, &path.path), path.kind), path_id))
+        .collect();
+    let after_paths: BTreeMap<_, _> = local_paths(after)
+        .filter(|(_, path)| path.kind != crate::PathKind::Import)
+        .map(|(path_id, path)| ((relative_path(after, &path.path), path.kind), path_id))
+        .collect();
+
+    // Paths sort before what is within them, so a gate is seen before those it covers
+    let mut gated: HashMap<&str, Id> = HashMap::new();
+    for ((relative, kind), after_id) in after_paths {
+        let before_id = match before_paths.get(&(relative, kind)) {
+            Some(before_id) => *before_id,
+            None => continue,
+        };
+        let (before_item, after_item) =
+            match (path_item(before, before_id), path_item(after, after_id)) {
+                (Some(before_item), Some(after_item)) => (before_item, after_item),
+                _ => continue,
+            };
+        let covered = parent_path(relative).and_then(|parent| gated.get(parent).copied());
+        if let Some(id) = gate_change(&before_item.features, &after_item.features) {
+            gated.insert(relative, id);
+            if covered != Some(id) {
+                changes.push(Diff {
+                    severity: id.default_severity,
+                    id,
+                    before: path_location(before, before_id),
+                    after: path_location(after, after_id),
+                    confidence: None,
+                    is_experimental: false,
+                });
+            }
+            continue;
+        }
+
+        let before_members: HashMap<_, _> = associated_items(before, before_item).collect();
+        for (key, after_member_id) in associated_items(after, after_item) {
+            let before_member_id = match before_members.get(&key) {
+                Some(before_member_id) => *before_member_id,
+                None => continue,
+            };
+            let id = match (
+                before.items.get(before_member_id),
+                after.items.get(after_member_id),
+            ) {
+                (Some(before_member), Some(after_member)) => {
+                    match gate_change(&before_member.features, &after_member.features) {
+                        Some(id) => id,
+                        None => continue,
+                    }
+                }
+                _ => continue,
+            };
+            changes.push(Diff {
+                severity: id.default_severity,
+                id,
+                before: path_location(before, before_id).map(|location| Location {
+                    item_id: Some(before_member_id),
+                    ..location
+                }),
+                after: path_location(after, after_id).map(|location| Location {
+                    item_id: Some(after_member_id),
+                    ..location
+                }),
+                confidence: None,
+                is_experimental: false,
+            });
+        }
+    }
+
+    let after_impls = impls_by_key(after);
+    for (key, before_impl_id) in impls_by_key(before) {
+        let after_impl_id = match after_impls.get(&key) {
+            Some(after_impl_id) => *after_impl_id,
+            None => continue,
+        };
+        let id = match gate_change(
+            &before.impls.get(before_impl_id).unwrap().features,
+            &after.impls.get(after_impl_id).unwrap().features,
+        ) {
+            Some(id) => id,
+            None => continue,
+        };
+        changes.push(Diff {
+            severity: id.default_severity,
+            id,
+            before: Some(Location {
+                impl_id: Some(before_impl_id),
+                ..Default::default()
+            }),
+            after: Some(Location {
+                impl_id: Some(after_impl_id),
+                ..Default::default()
+            }),
+            confidence: None,
+            is_experimental: false,
+        });
+    }
+}
+
+/// Local paths of `kind`, relative to the crate root
+fn paths_by_kind(api: &crate::Api, kind: crate::PathKind) -> BTreeMap<&str, crate::PathId> {
+    local_paths(api)
+        .filter(|(_, path)| path.kind == kind)
+        .map(|(path_id, path)| (relative_path(api, &path.path), path_id))
+        .collect()
+}
+
+fn parent_path(path: &str) -> Option<&str> {
+    path.rsplit_once("::").map(|(parent, _)| parent)
+}
+
+fn path_item(api: &crate::Api, path_id: crate::PathId) -> Option<&crate::Item> {
+    api.paths
+        .get(path_id)
+        .and_then(|path| path.item_id)
+        .and_then(|item_id| api.items.get(item_id))
+}
+
+fn path_location(api: &crate::Api, path_id: crate::PathId) -> Option<Location> {
+    let path = api.paths.get(path_id).unwrap();
+    Some(Location {
+        crate_id: path.crate_id,
+        path_id: Some(path_id),
+        item_id: path.item_id,
+        impl_id: None,
+    })
+}
+
+fn local_paths(api: &crate::Api) -> impl Iterator<Item = (crate::PathId, &crate::Path)> {
+    api.paths.iter().filter(|(_, path)| path.crate_id.is_none())
+}
+
+/// Where each local item is defined, relative to the crate root, rather than re-exported
+fn definitions(api: &crate::Api) -> HashMap<crate::ItemId, (&str, crate::PathKind)> {
+    let mut definitions = HashMap::new();
+    for (_, path) in local_paths(api) {
+        if path.kind == crate::PathKind::Import {
+            continue;
+        }
+        if let Some(item_id) = path.item_id {
+            definitions
+                .entry(item_id)
+                .or_insert((relative_path(api, &path.path), path.kind));
+        }
+    }
+    definitions
+}
+
+/// A path relative to the crate root, with re-exports taking the kind of what they point to
+fn reachable_key<'a>(
+    api: &'a crate::Api,
+    definitions: &HashMap<crate::ItemId, (&str, crate::PathKind)>,
+    path: &'a crate::Path,
+) -> (&'a str, crate::PathKind) {
+    let kind = match path.kind {
+        crate::PathKind::Import => path
+            .item_id
+            .and_then(|item_id| definitions.get(&item_id))
+            .map(|(_, kind)| *kind)
+            .unwrap_or(crate::PathKind::Import),
+        kind => kind,
+    };
+    (relative_path(api, &path.path), kind)
+}
+
+/// Re-exports are covered by what they point to, and variants don't move independently
+fn is_renameable(kind: crate::PathKind) -> bool {
+    !matches!(kind, crate::PathKind::Import | crate::PathKind::Variant)
+}
+
+/// Strip the crate name from `path`
+fn relative_path<'p>(api: &crate::Api, path: &'p str) -> &'p str {
+    let root = api
+        .root_id
+        .and_then(|root_id| api.paths.get(root_id))
+        .map(|root| root.path.as_str())
+        .unwrap_or_default();
+    path.strip_prefix(root).unwrap_or(path)
+}
+
+fn rename_confidence(
+    before_api: &crate::Api,
+    before: &crate::Path,
+    after_api: &crate::Api,
+    after: &crate::Path,
+) -> Option<u8> {
+    let before_item = before.item_id.and_then(|id| before_api.items.get(id));
+    let after_item = after.item_id.and_then(|id| after_api.items.get(id));
+    let structure = match (before_item, after_item) {
+        (Some(before_item), Some(after_item)) => structural_similarity(before_item, after_item),
+        _ => None,
+    };
+    let before_name = before.path.rsplit("::").next();
+    let after_name = after.path.rsplit("::").next();
+    match (before_name == after_name, structure) {
+        // Moved
+        (true, Some(structure)) => Some(50 + structure / 2),
+        // Only the name to go on, which is too common to pair on
+        (true, None) => None,
+        // Renamed
+        (false, Some(structure)) => Some(structure / 4 * 3),
+        (false, None) => None,
+    }
+}
+
+/// Percentage of the signature or fields shared between two items, if they have either
+fn structural_similarity(before: &crate::Item, after: &crate::Item) -> Option<u8> {
+    if before.is_unsafe != after.is_unsafe || before.abi != after.abi {
+        return Some(0);
+    }
+    match (&before.signature, &after.signature) {
+        (Some(before), Some(after)) => {
+            let total = before.inputs.len().max(after.inputs.len()) + 1;
+            let matching = before
+                .inputs
+                .iter()
+                .zip(after.inputs.iter())
+                .filter(|(before, after)| before.type_ == after.type_)
+                .count()
+                + usize::from(before.output == after.output);
+            return Some((matching * 100 / total) as u8);
+        }
+        (None, None) => {}
+        _ => return Some(0),
+    }
+    if before.fields.is_empty() && after.fields.is_empty() {
+        return None;
+    }
+    let matching = before
+        .fields
+        .iter()
+        .filter(|field| after.fields.contains(field))
+        .count();
+    let total = before.fields.len() + after.fields.len() - matching;
+    Some((matching * 100 / total) as u8)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn api(path: &str, item: crate::Item) -> (crate::Api, crate::Path) {
+        let mut api = crate::Api::new();
+        let mut path = crate::Path::new(crate::PathKind::Struct, path);
+        path.item_id = Some(api.items.push(item));
+        api.paths.push(path.clone());
+        (api, path)
+    }
+
+    fn function(output: &str) -> crate::Item {
+        let mut item = crate::Item::new();
+        let mut signature = crate::Signature::new();
+        signature.output = Some(output.to_owned());
+        item.signature = Some(signature);
+        item
+    }
+
+    #[test]
+    fn rename_confidence_moved() {
+        let (before_api, before) = api("foo::a::bar", function("u8"));
+        let (after_api, after) = api("foo::b::bar", function("u8"));
+        assert_eq!(
+            rename_confidence(&before_api, &before, &after_api, &after),
+            Some(100)
+        );
+    }
+
+    #[test]
+    fn rename_confidence_name_only() {
+        let (before_api, before) = api("foo::a::Bar", crate::Item::new());
+        let (after_api, after) = api("foo::b::Bar", crate::Item::new());
+        assert_eq!(
+            rename_confidence(&before_api, &before, &after_api, &after),
+            None
+        );
+    }
+
+    #[test]
+    fn rename_confidence_different() {
+        let (before_api, before) = api("foo::a::bar", function("u8"));
+        let (after_api, after) = api("foo::b::bar", function("u16"));
+        let confidence = rename_confidence(&before_api, &before, &after_api, &after).unwrap();
+        assert!(confidence < RENAME_CONFIDENCE);
+    }
+}
@@ -0,0 +1,638 @@
+//! Classify changes by the version bump they require
+//!
+//! Each kind of change is mapped to a [`Bump`] following the
+//! [SemVer Compatibility](https://doc.rust-lang.org/cargo/reference/semver.html) chapter of the
+//! Cargo Book, which [`Rule::reference`] links into.
+//!
+//! ```
+//! use crate_api::diff::rules::{self, Bump};
+//!
+//! let current = semver::Version::parse("1.4.2").unwrap();
+//! assert_eq!(rules::next_version(&current, Bump::Minor).to_string(), "1.5.0");
+//!
+//! let current = semver::Version::parse("0.4.2").unwrap();
+//! assert_eq!(rules::next_version(&current, Bump::Major).to_string(), "0.5.0");
+//!
+//! let next = semver::Version::parse("0.4.3").unwrap();
+//! assert_eq!(rules::bump_between(&current, &next), Some(Bump::Minor));
+//! ```
+
+/// How much a version must change for a release to be compatible
+#[derive(
+    Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash, serde::Serialize, serde::Deserialize,
+)]
+#[serde(rename_all = "snake_case")]
+pub enum Bump {
+    /// Nothing changed for users of the API
+    Patch,
+    /// New functionality that existing users can't observe
+    Minor,
+    /// Existing users may fail to build or change behavior
+    Major,
+}
+
+impl std::fmt::Display for Bump {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Patch => "patch".fmt(f),
+            Self::Minor => "minor".fmt(f),
+            Self::Major => "major".fmt(f),
+        }
+    }
+}
+
+/// The bump a kind of change requires
+#[derive(Copy, Clone, Debug, PartialEq, Eq, serde::Serialize)]
+#[serde(rename_all = "snake_case")]
+#[non_exhaustive]
+pub struct Rule {
+    pub id: super::Id,
+    pub bump: Bump,
+    /// Where the semver reference covers this change
+    pub reference: &'static str,
+}
+
+const REFERENCE: &str = "https://doc.rust-lang.org/cargo/reference/semver.html";
+
+pub const RULES: &[Rule] = &[
+    Rule {
+        id: super::DEPENDENCY_REMOVED,
+        bump: Bump::Patch,
+        reference: "https://doc.rust-lang.org/cargo/reference/semver.html#cargo",
+    },
+    Rule {
+        id: super::DEPENDENCY_ADDED,
+        bump: Bump::Minor,
+        reference: "https://doc.rust-lang.org/cargo/reference/semver.html#cargo-dependency-add",
+    },
+    Rule {
+        id: super::DEPENDENCY_AMBIGUOUS,
+        bump: Bump::Patch,
+        reference: REFERENCE,
+    },
+    Rule {
+        id: super::DEPENDENCY_REQUIREMENT,
+        bump: Bump::Major,
+        reference: "https://doc.rust-lang.org/cargo/reference/semver.html#item-remove",
+    },
+    Rule {
+        id: super::RUST_VERSION_RAISED,
+        bump: Bump::Major,
+        reference: "https://doc.rust-lang.org/cargo/reference/semver.html#env-new-rust",
+    },
+    Rule {
+        id: super::ITEM_RENAMED,
+        bump: Bump::Major,
+        reference: "https://doc.rust-lang.org/cargo/reference/semver.html#item-remove",
+    },
+    Rule {
+        id: super::ITEM_REMOVED,
+        bump: Bump::Major,
+        reference: "https://doc.rust-lang.org/cargo/reference/semver.html#item-remove",
+    },
+    Rule {
+        id: super::PATH_REMOVED,
+        bump: Bump::Major,
+        reference: "https://doc.rust-lang.org/cargo/reference/semver.html#item-remove",
+    },
+    Rule {
+        id: super::DEPRECATED_ITEM_REMOVED,
+        bump: Bump::Major,
+        reference: "https://doc.rust-lang.org/cargo/reference/semver.html#item-remove",
+    },
+    Rule {
+        id: super::DEPRECATION_ADDED,
+        bump: Bump::Minor,
+        reference: REFERENCE,
+    },
+    Rule {
+        id: super::FN_ARITY_CHANGED,
+        bump: Bump::Major,
+        reference: "https://doc.rust-lang.org/cargo/reference/semver.html#fn-change-arity",
+    },
+    Rule {
+        id: super::FN_PARAMETER_CHANGED,
+        bump: Bump::Major,
+        reference: REFERENCE,
+    },
+    Rule {
+        id: super::FN_OUTPUT_CHANGED,
+        bump: Bump::Major,
+        reference: REFERENCE,
+    },
+    Rule {
+        id: super::FN_UNSAFE_ADDED,
+        bump: Bump::Major,
+        reference: REFERENCE,
+    },
+    Rule {
+        id: super::FN_UNSAFE_REMOVED,
+        bump: Bump::Minor,
+        reference: REFERENCE,
+    },
+    Rule {
+        id: super::FN_CONST_REMOVED,
+        bump: Bump::Major,
+        reference: REFERENCE,
+    },
+    Rule {
+        id: super::FN_ASYNC_CHANGED,
+        bump: Bump::Major,
+        reference: REFERENCE,
+    },
+    Rule {
+        id: super::FN_ABI_CHANGED,
+        bump: Bump::Major,
+        reference: REFERENCE,
+    },
+    Rule {
+        id: super::CONST_TYPE_CHANGED,
+        bump: Bump::Major,
+        reference: REFERENCE,
+    },
+    Rule {
+        id: super::CONST_VALUE_CHANGED,
+        bump: Bump::Minor,
+        reference: REFERENCE,
+    },
+    Rule {
+        id: super::STATIC_MUTABILITY_CHANGED,
+        bump: Bump::Major,
+        reference: REFERENCE,
+    },
+    Rule {
+        id: super::TYPE_ALIAS_CHANGED,
+        bump: Bump::Major,
+        reference: REFERENCE,
+    },
+    Rule {
+        id: super::MACRO_RULE_REMOVED,
+        bump: Bump::Major,
+        reference: REFERENCE,
+    },
+    Rule {
+        id: super::MACRO_RULE_ADDED,
+        bump: Bump::Minor,
+        reference: REFERENCE,
+    },
+    Rule {
+        id: super::DERIVE_HELPER_REMOVED,
+        bump: Bump::Major,
+        reference: REFERENCE,
+    },
+    Rule {
+        id: super::VARIANT_ADDED,
+        bump: Bump::Major,
+        reference: "https://doc.rust-lang.org/cargo/reference/semver.html#enum-variant-new",
+    },
+    Rule {
+        id: super::NON_EXHAUSTIVE_VARIANT_ADDED,
+        bump: Bump::Minor,
+        reference: "https://doc.rust-lang.org/cargo/reference/semver.html#enum-variant-new",
+    },
+    Rule {
+        id: super::VARIANT_REMOVED,
+        bump: Bump::Major,
+        reference: "https://doc.rust-lang.org/cargo/reference/semver.html#item-remove",
+    },
+    Rule {
+        id: super::VARIANT_FIELDS_CHANGED,
+        bump: Bump::Major,
+        reference: "https://doc.rust-lang.org/cargo/reference/semver.html#enum-fields-new",
+    },
+    Rule {
+        id: super::STRUCT_FIELD_ADDED,
+        bump: Bump::Major,
+        reference: "https://doc.rust-lang.org/cargo/reference/semver.html#struct-add-public-field-when-no-private",
+    },
+    Rule {
+        id: super::STRUCT_PRIVATE_FIELD_ADDED,
+        bump: Bump::Major,
+        reference: "https://doc.rust-lang.org/cargo/reference/semver.html#struct-add-private-field-when-public",
+    },
+    Rule {
+        id: super::STRUCT_FIELD_REMOVED,
+        bump: Bump::Major,
+        reference: "https://doc.rust-lang.org/cargo/reference/semver.html#item-remove",
+    },
+    Rule {
+        id: super::STRUCT_FIELD_CHANGED,
+        bump: Bump::Major,
+        reference: REFERENCE,
+    },
+    Rule {
+        id: super::TRAIT_ITEM_ADDED,
+        bump: Bump::Major,
+        reference: "https://doc.rust-lang.org/cargo/reference/semver.html#trait-new-item-no-default",
+    },
+    Rule {
+        id: super::TRAIT_PROVIDED_ITEM_ADDED,
+        bump: Bump::Minor,
+        reference: "https://doc.rust-lang.org/cargo/reference/semver.html#trait-new-default-item",
+    },
+    Rule {
+        id: super::SEALED_TRAIT_ITEM_ADDED,
+        bump: Bump::Minor,
+        reference: REFERENCE,
+    },
+    Rule {
+        id: super::TRAIT_ITEM_REMOVED,
+        bump: Bump::Major,
+        reference: "https://doc.rust-lang.org/cargo/reference/semver.html#item-remove",
+    },
+    Rule {
+        id: super::TRAIT_ITEM_DEFAULT_REMOVED,
+        bump: Bump::Major,
+        reference: REFERENCE,
+    },
+    Rule {
+        id: super::TRAIT_ITEM_DEFAULT_ADDED,
+        bump: Bump::Minor,
+        reference: REFERENCE,
+    },
+    Rule {
+        id: super::ASSOC_CONST_TYPE_CHANGED,
+        bump: Bump::Major,
+        reference: REFERENCE,
+    },
+    Rule {
+        id: super::ASSOC_TYPE_BOUND_ADDED,
+        bump: Bump::Major,
+        reference: REFERENCE,
+    },
+    Rule {
+        id: super::ASSOC_TYPE_BOUND_REMOVED,
+        bump: Bump::Major,
+        reference: REFERENCE,
+    },
+    Rule {
+        id: super::SUPERTRAIT_ADDED,
+        bump: Bump::Major,
+        reference: REFERENCE,
+    },
+    Rule {
+        id: super::SUPERTRAIT_REMOVED,
+        bump: Bump::Major,
+        reference: REFERENCE,
+    },
+    Rule {
+        id: super::TRAIT_UNSAFE_ADDED,
+        bump: Bump::Major,
+        reference: REFERENCE,
+    },
+    Rule {
+        id: super::TRAIT_UNSAFE_REMOVED,
+        bump: Bump::Major,
+        reference: REFERENCE,
+    },
+    Rule {
+        id: super::INHERENT_ITEM_REMOVED,
+        bump: Bump::Major,
+        reference: "https://doc.rust-lang.org/cargo/reference/semver.html#item-remove",
+    },
+    Rule {
+        id: super::INHERENT_ITEM_ADDED,
+        bump: Bump::Minor,
+        reference: "https://doc.rust-lang.org/cargo/reference/semver.html#impl-item-new",
+    },
+    Rule {
+        id: super::AUTO_TRAIT_REMOVED,
+        bump: Bump::Major,
+        reference: "https://doc.rust-lang.org/cargo/reference/semver.html#item-remove",
+    },
+    Rule {
+        id: super::BLANKET_IMPL_REMOVED,
+        bump: Bump::Major,
+        reference: "https://doc.rust-lang.org/cargo/reference/semver.html#item-remove",
+    },
+    Rule {
+        id: super::GENERIC_PARAM_ADDED,
+        bump: Bump::Major,
+        reference: "https://doc.rust-lang.org/cargo/reference/semver.html#generic-new-no-default",
+    },
+    Rule {
+        id: super::DEFAULTED_GENERIC_PARAM_ADDED,
+        bump: Bump::Minor,
+        reference: "https://doc.rust-lang.org/cargo/reference/semver.html#generic-new-default",
+    },
+    Rule {
+        id: super::GENERIC_PARAM_REMOVED,
+        bump: Bump::Major,
+        reference: "https://doc.rust-lang.org/cargo/reference/semver.html#item-remove",
+    },
+    Rule {
+        id: super::GENERIC_BOUND_TIGHTENED,
+        bump: Bump::Major,
+        reference: "https://doc.rust-lang.org/cargo/reference/semver.html#generic-bounds-tighten",
+    },
+    Rule {
+        id: super::GENERIC_BOUND_LOOSENED,
+        bump: Bump::Minor,
+        reference: "https://doc.rust-lang.org/cargo/reference/semver.html#generic-bounds-loosen",
+    },
+    Rule {
+        id: super::VISIBILITY_REDUCED,
+        bump: Bump::Major,
+        reference: "https://doc.rust-lang.org/cargo/reference/semver.html#item-remove",
+    },
+    Rule {
+        id: super::REPR_ADDED,
+        bump: Bump::Minor,
+        reference: "https://doc.rust-lang.org/cargo/reference/semver.html#repr-c-add",
+    },
+    Rule {
+        id: super::REPR_CHANGED,
+        bump: Bump::Major,
+        reference: "https://doc.rust-lang.org/cargo/reference/semver.html#repr-c-remove",
+    },
+    Rule {
+        id: super::NON_EXHAUSTIVE_ADDED,
+        bump: Bump::Major,
+        reference: "https://doc.rust-lang.org/cargo/reference/semver.html#attr-adding-non-exhaustive",
+    },
+    Rule {
+        id: super::NON_EXHAUSTIVE_REMOVED,
+        bump: Bump::Minor,
+        reference: REFERENCE,
+    },
+    Rule {
+        id: super::MUST_USE_ADDED,
+        bump: Bump::Minor,
+        reference: REFERENCE,
+    },
+    Rule {
+        id: super::MUST_USE_REMOVED,
+        bump: Bump::Minor,
+        reference: REFERENCE,
+    },
+    Rule {
+        id: super::FEATURE_GATE_ADDED,
+        bump: Bump::Major,
+        reference: "https://doc.rust-lang.org/cargo/reference/semver.html#item-remove",
+    },
+    Rule {
+        id: super::FEATURE_GATE_REMOVED,
+        bump: Bump::Minor,
+        reference: REFERENCE,
+    },
+    Rule {
+        id: super::TRAIT_IMPL_REMOVED,
+        bump: Bump::Major,
+        reference: "https://doc.rust-lang.org/cargo/reference/semver.html#item-remove",
+    },
+    Rule {
+        id: super::TRAIT_IMPL_ADDED,
+        bump: Bump::Minor,
+        reference: "https://doc.rust-lang.org/cargo/reference/semver.html#impl-item-new",
+    },
+];
+
+/// The rule for changes with `id`
+///
+/// Changes without a rule are classified by their default severity, so a warning is assumed
+/// to be breaking.
+pub fn rule(id: super::Id) -> Rule {
+    RULES
+        .iter()
+        .find(|rule| rule.id.name == id.name)
+        .copied()
+        .unwrap_or(Rule {
+            id,
+            bump: match id.default_severity {
+                super::Severity::Allow => Bump::Patch,
+                super::Severity::Report => Bump::Minor,
+                super::Severity::Warn => Bump::Major,
+            },
+            reference: REFERENCE,
+        })
+}
+
+/// The bump `change` requires
+///
+/// Allowed changes don't need one, and a breaking change to an experimental item needs no more
+/// than a minor bump.  Otherwise the severity only affects reporting, so a change that is just
+/// reported, like removing a deprecated item, still needs the rule's bump.
+pub fn classify(change: &super::Diff) -> Bump {
+    let bump = rule(change.id).bump;
+    if change.severity == super::Severity::Allow {
+        Bump::Patch
+    } else if change.is_experimental {
+        bump.min(Bump::Minor)
+    } else {
+        bump
+    }
+}
+
+/// The bump a release with all of `changes` requires
+pub fn required_bump<'d>(changes: impl IntoIterator<Item = &'d super::Diff>) -> Bump {
+    changes
+        .into_iter()
+        .map(classify)
+        .max()
+        .unwrap_or(Bump::Patch)
+}
+
+/// The smallest version after `current` with `bump`
+///
+/// Before `1.0.0`, Cargo treats the left-most non-zero component as the major version, so a
+/// major bump of `0.4.2` is `0.5.0`, and every change to `0.0.3` is major.
+pub fn next_version(current: &semver::Version, bump: Bump) -> semver::Version {
+    let (major, minor, patch) = (current.major, current.minor, current.patch);
+    let (major, minor, patch) = match (bump, major, minor) {
+        (_, 0, 0) => (0, 0, patch + 1),
+        (Bump::Major, 0, _) => (0, minor + 1, 0),
+        (Bump::Major, _, _) => (major + 1, 0, 0),
+        (Bump::Minor, 0, _) | (Bump::Patch, _, _) => (major, minor, patch + 1),
+        (Bump::Minor, _, _) => (major, minor + 1, 0),
+    };
+    semver::Version::new(major, minor, patch)
+}
+
+/// The largest bump going from `before` to `after` allows, or `None` if `after` isn't newer
+///
+/// Like [`next_version`], before `1.0.0` the left-most non-zero component is the major version.
+/// Pre-releases promise no compatibility, so releasing one allows anything.
+pub fn bump_between(before: &semver::Version, after: &semver::Version) -> Option<Bump> {
+    if after <= before {
+        return None;
+    }
+    let breaking = !before.pre.is_empty()
+        || !after.pre.is_empty()
+        || before.major != after.major
+        || (before.major == 0 && (before.minor == 0 || before.minor != after.minor));
+    let bump = if breaking {
+        Bump::Major
+    } else if before.major == 0 || before.minor != after.minor {
+        Bump::Minor
+    } else {
+        Bump::Patch
+    };
+    Some(bump)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::diff::{Diff, Id, Severity};
+    use crate::diff::{
+        CONST_VALUE_CHANGED, DEPRECATED_ITEM_REMOVED, FN_ARITY_CHANGED, MACRO_RULE_ADDED,
+    };
+
+    fn version(version: &str) -> semver::Version {
+        semver::Version::parse(version).unwrap()
+    }
+
+    fn change(id: Id, severity: Severity) -> Diff {
+        Diff {
+            severity,
+            id,
+            before: None,
+            after: None,
+            confidence: None,
+            is_experimental: false,
+        }
+    }
+
+    #[test]
+    fn next_version_stable() {
+        let current = version("1.4.2");
+        assert_eq!(next_version(&current, Bump::Patch), version("1.4.3"));
+        assert_eq!(next_version(&current, Bump::Minor), version("1.5.0"));
+        assert_eq!(next_version(&current, Bump::Major), version("2.0.0"));
+    }
+
+    #[test]
+    fn next_version_unstable() {
+        let current = version("0.4.2");
+        assert_eq!(next_version(&current, Bump::Patch), version("0.4.3"));
+        assert_eq!(next_version(&current, Bump::Minor), version("0.4.3"));
+        assert_eq!(next_version(&current, Bump::Major), version("0.5.0"));
+
+        let current = version("0.0.3");
+        assert_eq!(next_version(&current, Bump::Patch), version("0.0.4"));
+        assert_eq!(next_version(&current, Bump::Minor), version("0.0.4"));
+        assert_eq!(next_version(&current, Bump::Major), version("0.0.4"));
+    }
+
+    #[test]
+    fn bump_between_stable() {
+        let before = version("1.4.2");
+        assert_eq!(bump_between(&before, &version("1.4.3")), Some(Bump::Patch));
+        assert_eq!(bump_between(&before, &version("1.5.0")), Some(Bump::Minor));
+        assert_eq!(bump_between(&before, &version("2.0.0")), Some(Bump::Major));
+    }
+
+    #[test]
+    fn bump_between_unstable() {
+        let before = version("0.4.2");
+        assert_eq!(bump_between(&before, &version("0.4.3")), Some(Bump::Minor));
+        assert_eq!(bump_between(&before, &version("0.5.0")), Some(Bump::Major));
+        assert_eq!(bump_between(&before, &version("1.0.0")), Some(Bump::Major));
+
+        let before = version("0.0.3");
+        assert_eq!(bump_between(&before, &version("0.0.4")), Some(Bump::Major));
+    }
+
+    #[test]
+    fn bump_between_pre_release() {
+        assert_eq!(
+            bump_between(&version("1.4.2"), &version("1.4.3-alpha.1")),
+            Some(Bump::Major)
+        );
+        assert_eq!(
+            bump_between(&version("1.5.0-alpha.1"), &version("1.5.0")),
+            Some(Bump::Major)
+        );
+    }
+
+    #[test]
+    fn bump_between_not_newer() {
+        let before = version("1.4.2");
+        assert_eq!(bump_between(&before, &before), None);
+        assert_eq!(bump_between(&before, &version("1.4.1")), None);
+    }
+
+    #[test]
+    fn next_version_is_allowed_by_bump() {
+        for current in ["0.0.3", "0.4.2", "1.4.2"] {
+            let current = version(current);
+            for bump in [Bump::Patch, Bump::Minor, Bump::Major] {
+                let next = next_version(&current, bump);
+                assert!(
+                    bump_between(&current, &next) >= Some(bump),
+                    "{} to {} for a {} bump",
+                    current,
+                    next,
+                    bump
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn classify_severity() {
+        assert_eq!(
+            classify(&change(FN_ARITY_CHANGED, Severity::Warn)),
+            Bump::Major
+        );
+        // Only reporting it doesn't make it compatible
+        assert_eq!(
+            classify(&change(FN_ARITY_CHANGED, Severity::Report)),
+            Bump::Major
+        );
+        assert_eq!(
+            classify(&change(FN_ARITY_CHANGED, Severity::Allow)),
+            Bump::Patch
+        );
+    }
+
+    #[test]
+    fn classify_experimental() {
+        let mut experimental = change(FN_ARITY_CHANGED, Severity::Report);
+        experimental.is_experimental = true;
+        assert_eq!(classify(&experimental), Bump::Minor);
+
+        let mut experimental = change(MACRO_RULE_ADDED, Severity::Report);
+        experimental.is_experimental = true;
+        assert_eq!(classify(&experimental), Bump::Minor);
+    }
+
+    #[test]
+    fn classify_deprecated_removal() {
+        assert_eq!(
+            classify(&change(
+                DEPRECATED_ITEM_REMOVED,
+                DEPRECATED_ITEM_REMOVED.default_severity
+            )),
+            Bump::Major
+        );
+    }
+
+    #[test]
+    fn required_bump_max() {
+        assert_eq!(required_bump(&[]), Bump::Patch);
+        let changes = [
+            change(MACRO_RULE_ADDED, Severity::Report),
+            change(CONST_VALUE_CHANGED, Severity::Allow),
+        ];
+        assert_eq!(required_bump(&changes), Bump::Minor);
+        let changes = [
+            change(MACRO_RULE_ADDED, Severity::Report),
+            change(FN_ARITY_CHANGED, Severity::Warn),
+        ];
+        assert_eq!(required_bump(&changes), Bump::Major);
+    }
+
+    #[test]
+    fn rules_are_for_known_ids() {
+        for rule in RULES {
+            assert!(
+                crate::diff::ALL_IDS
+                    .iter()
+                    .any(|id| id.name == rule.id.name),
+                "{} isn't in `ALL_IDS`",
+                rule.id.name
+            );
+        }
+    }
+}
@@ -42,5 +42,13 @@ impl std::error::Error for Error {
 #[non_exhaustive]
 pub enum ErrorKind {
     ApiParse,
+    /// `cargo doc` could not be run or failed
+    DocBuild,
+    /// rustdoc emitted a JSON format version this crate doesn't support
+    FormatVersion,
+    /// The package couldn't be found, like with no manifest or not being a workspace member
+    Package,
+    /// The rustup toolchain, like `nightly`, isn't installed or available
+    Toolchain,
     Unknown,
 }
@@ -0,0 +1,159 @@
+/// Match item paths, like `mycrate::net::*`
+///
+/// - `*` matches within a single segment
+/// - `**` matches any number of segments
+/// - A pattern matching a path also matches everything under it
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct PathGlob {
+    pattern: String,
+    segments: Vec<String>,
+}
+
+impl PathGlob {
+    pub fn new(pattern: impl Into<String>) -> Self {
+        let pattern = pattern.into();
+        let segments = pattern
+            .split("::")
+            .map(|segment| segment.trim().to_owned())
+            .collect();
+        Self { pattern, segments }
+    }
+
+    pub fn as_str(&self) -> &str {
+        &self.pattern
+    }
+
+    pub fn matches(&self, path: &str) -> bool {
+        let path: Vec<_> = path.split("::").collect();
+        matches_segments(&self.segments, &path)
+    }
+}
+
+impl std::fmt::Display for PathGlob {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        self.pattern.fmt(f)
+    }
+}
+
+impl std::str::FromStr for PathGlob {
+    type Err = std::convert::Infallible;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Ok(Self::new(s))
+    }
+}
+
+impl serde::Serialize for PathGlob {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        self.pattern.serialize(serializer)
+    }
+}
+
+impl<'de> serde::Deserialize<'de> for PathGlob {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        String::deserialize(deserializer).map(Self::new)
+    }
+}
+
+fn matches_segments(pattern: &[String], path: &[&str]) -> bool {
+    match pattern.split_first() {
+        // Everything under a matched path is matched
+        None => true,
+        Some((first, rest)) if first == "**" => {
+            (0..=path.len()).any(|skip| matches_segments(rest, &path[skip..]))
+        }
+        Some((first, rest)) => match path.split_first() {
+            Some((segment, path)) => {
+                matches_segment(first, segment) && matches_segments(rest, path)
+            }
+            None => false,
+        },
+    }
+}
+
+fn matches_segment(pattern: &str, segment: &str) -> bool {
+    match pattern.split_once('*') {
+        None => pattern == segment,
+        Some((prefix, rest)) => {
+            let remaining = match segment.strip_prefix(prefix) {
+                Some(remaining) => remaining,
+                None => return false,
+            };
+            (0..=remaining.len())
+                .filter(|i| remaining.is_char_boundary(*i))
+                .any(|i| matches_segment(rest, &remaining[i..]))
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn literal() {
+        let glob = PathGlob::new("mycrate::net");
+        assert!(glob.matches("mycrate::net"));
+        assert!(!glob.matches("mycrate::network"));
+        assert!(!glob.matches("mycrate"));
+        assert!(!glob.matches("other::net"));
+    }
+
+    #[test]
+    fn matches_everything_under_it() {
+        let glob = PathGlob::new("mycrate::net");
+        assert!(glob.matches("mycrate::net::TcpStream"));
+        assert!(glob.matches("mycrate::net::tcp::TcpStream::connect"));
+    }
+
+    #[test]
+    fn star_within_segment() {
+        let glob = PathGlob::new("mycrate::*::Error");
+        assert!(glob.matches("mycrate::net::Error"));
+        assert!(glob.matches("mycrate::io::Error::kind"));
+        assert!(!glob.matches("mycrate::Error"));
+        assert!(!glob.matches("mycrate::net::tcp::Error"));
+
+        let glob = PathGlob::new("mycrate::Tcp*");
+        assert!(glob.matches("mycrate::Tcp"));
+        assert!(glob.matches("mycrate::TcpStream"));
+        assert!(!glob.matches("mycrate::UdpSocket"));
+
+        let glob = PathGlob::new("mycrate::*Ext*");
+        assert!(glob.matches("mycrate::ReadExt"));
+        assert!(glob.matches("mycrate::ExtraStuff"));
+        assert!(!glob.matches("mycrate::Read"));
+    }
+
+    #[test]
+    fn double_star_across_segments() {
+        let glob = PathGlob::new("mycrate::**::Error");
+        assert!(glob.matches("mycrate::Error"));
+        assert!(glob.matches("mycrate::net::Error"));
+        assert!(glob.matches("mycrate::net::tcp::Error"));
+        assert!(!glob.matches("mycrate::net::ErrorKind"));
+        assert!(!glob.matches("other::Error"));
+
+        let glob = PathGlob::new("**");
+        assert!(glob.matches("mycrate"));
+        assert!(glob.matches("mycrate::net::Error"));
+    }
+
+    #[test]
+    fn char_boundaries() {
+        let glob = PathGlob::new("mycrate::*é");
+        assert!(glob.matches("mycrate::café"));
+        assert!(!glob.matches("mycrate::cafe"));
+
+        let glob = PathGlob::new("mycrate::é*");
+        assert!(glob.matches("mycrate::été"));
+        assert!(!glob.matches("mycrate::ete"));
+    }
+
+    #[test]
+    fn whitespace_around_segments() {
+        let glob = PathGlob::new("mycrate :: net");
+        assert!(glob.matches("mycrate::net"));
+        assert_eq!(glob.as_str(), "mycrate :: net");
+    }
+}
@@ -1,10 +1,14 @@
 mod api;
+mod builder;
 mod error;
+mod glob;
 
 pub mod diff;
 pub mod manifest;
 pub mod rustdoc;
 
 pub use api::*;
+pub use builder::*;
 pub use error::*;
+pub use glob::*;
 pub use rustdoc::RustDocBuilder;
@@ -1,5 +1,21 @@
 use std::collections::HashMap;
 
+/// Find the `Cargo.toml` for `dir`, walking up parent directories like cargo does
+pub fn discover(dir: &std::path::Path) -> Result<std::path::PathBuf, crate::Error> {
+    dir.ancestors()
+        .map(|dir| dir.join("Cargo.toml"))
+        .find(|path| path.is_file())
+        .ok_or_else(|| {
+            crate::Error::new(
+                crate::ErrorKind::Package,
+                format!(
+                    "could not find `Cargo.toml` in `{}` or any parent directory",
+                    dir.display()
+                ),
+            )
+        })
+}
+
 #[derive(Clone, Debug, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
 #[serde(rename_all = "snake_case")]
 #[non_exhaustive]
@@ -8,6 +24,10 @@ pub struct Manifest {
     pub version: cargo_metadata::Version,
     pub dependencies: Vec<Dependency>,
     pub features: HashMap<String, AnyFeature>,
+    #[serde(default)]
+    pub rust_version: Option<cargo_metadata::VersionReq>,
+    #[serde(default)]
+    pub edition: Option<String>,
 }
 
 impl Manifest {
@@ -38,6 +58,8 @@ impl Manifest {
                 .into_iter()
                 .map(|(name, feature)| (name, crate::AnyFeature::from(feature))),
         );
+        api.rust_version = self.rust_version.as_ref().and_then(render_rust_version);
+        api.edition = self.edition;
     }
 }
 
@@ -64,10 +86,22 @@ impl<'p> From<&'p cargo_metadata::Package> for Manifest {
             version: pkg.version.clone(),
             dependencies,
             features,
+            rust_version: pkg.rust_version.clone(),
+            edition: Some(pkg.edition.clone()),
         }
     }
 }
 
+/// `rust-version` as written, like `1.60`, rather than the `^1.60` cargo parses it into
+fn render_rust_version(rust_version: &cargo_metadata::VersionReq) -> Option<String> {
+    let comparator = rust_version.comparators.first()?;
+    let mut rendered = comparator.major.to_string();
+    for component in [comparator.minor, comparator.patch].into_iter().flatten() {
+        rendered.push_str(&format!(".{}", component));
+    }
+    Some(rendered)
+}
+
 #[derive(Clone, Debug, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
 #[serde(rename_all = "snake_case")]
 pub enum AnyFeature {
@@ -1,12 +1,53 @@
 use std::collections::HashMap;
+use std::collections::HashSet;
 use std::collections::VecDeque;
 
+/// A crate of a package, for [`RustDocBuilder::doc_target`]
+#[derive(Clone, Debug, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "snake_case")]
+#[non_exhaustive]
+pub enum DocTarget {
+    /// The library
+    Lib,
+    /// The binary with this name
+    Bin(String),
+}
+
+impl std::fmt::Display for DocTarget {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Lib => "lib".fmt(f),
+            Self::Bin(name) => write!(f, "bin {}", name),
+        }
+    }
+}
+
+/// Each workspace member's API, or why it couldn't be extracted
+type MemberApis = Vec<(cargo_metadata::PackageId, Result<crate::Api, crate::Error>)>;
+
 #[derive(Clone, Debug, PartialEq, Eq)]
 pub struct RustDocBuilder {
     deps: bool,
     target_directory: Option<std::path::PathBuf>,
     silence: bool,
     color: Option<bool>,
+    features: Vec<String>,
+    no_default_features: bool,
+    all_features: Option<bool>,
+    scope: Option<String>,
+    package: Option<String>,
+    target: Option<String>,
+    toolchain: Option<String>,
+    offline: bool,
+    locked: bool,
+    frozen: bool,
+    jobs: Option<u32>,
+    doc_target: Option<DocTarget>,
+    raw: Option<String>,
+    private_items: bool,
+    hidden_items: bool,
+    rustdoc_flags: Vec<String>,
+    envs: Vec<(std::ffi::OsString, std::ffi::OsString)>,
 }
 
 impl RustDocBuilder {
@@ -16,6 +57,23 @@ impl RustDocBuilder {
             target_directory: None,
             silence: false,
             color: None,
+            features: Vec::new(),
+            no_default_features: false,
+            all_features: None,
+            scope: None,
+            package: None,
+            target: None,
+            toolchain: None,
+            offline: false,
+            locked: false,
+            frozen: false,
+            jobs: None,
+            doc_target: None,
+            raw: None,
+            private_items: false,
+            hidden_items: false,
+            rustdoc_flags: Vec::new(),
+            envs: Vec::new(),
         }
     }
 
@@ -52,38 +110,311 @@ impl RustDocBuilder {
         self
     }
 
+    /// Enable these features, instead of all of them
+    ///
+    /// Default features are still enabled, see [`RustDocBuilder::no_default_features`].
+    pub fn features(mut self, features: impl IntoIterator<Item = impl Into<String>>) -> Self {
+        self.features = features.into_iter().map(|f| f.into()).collect();
+        self
+    }
+
+    /// Don't enable the `default` feature, nor all features
+    pub fn no_default_features(mut self, yes: bool) -> Self {
+        self.no_default_features = yes;
+        self
+    }
+
+    /// Enable all features, overriding [`RustDocBuilder::features`] and
+    /// [`RustDocBuilder::no_default_features`]
+    ///
+    /// By default, all features are enabled unless some other feature selection was made.
+    pub fn all_features(mut self, yes: bool) -> Self {
+        self.all_features = Some(yes);
+        self
+    }
+
+    /// Document for this target triple, like `x86_64-pc-windows-msvc`, rather than the host
+    ///
+    /// The target's standard library must be installed, like with `rustup target add`.
+    pub fn target(mut self, triple: impl Into<String>) -> Self {
+        self.target = Some(triple.into());
+        self
+    }
+
+    /// Run this rustup toolchain, like `nightly-2024-05-01`, rather than `nightly`
+    ///
+    /// Pinning the toolchain keeps the rustdoc JSON reproducible across runs.
+    ///
+    /// By default, a nightly pinned by the package's `rust-toolchain.toml` is used.
+    pub fn toolchain(mut self, toolchain: impl Into<String>) -> Self {
+        let toolchain = toolchain.into();
+        self.toolchain = Some(toolchain.strip_prefix('+').unwrap_or(&toolchain).to_owned());
+        self
+    }
+
+    /// Run cargo without accessing the network
+    pub fn offline(mut self, yes: bool) -> Self {
+        self.offline = yes;
+        self
+    }
+
+    /// Fail rather than update `Cargo.lock`
+    pub fn locked(mut self, yes: bool) -> Self {
+        self.locked = yes;
+        self
+    }
+
+    /// Both [`RustDocBuilder::offline`] and [`RustDocBuilder::locked`]
+    pub fn frozen(mut self, yes: bool) -> Self {
+        self.frozen = yes;
+        self
+    }
+
+    /// Run at most this many jobs in parallel, rather than one per CPU
+    pub fn jobs(mut self, jobs: u32) -> Self {
+        self.jobs = Some(jobs);
+        self
+    }
+
+    /// Document this crate of the package, rather than the library or, without one, its binary
+    pub fn doc_target(mut self, target: DocTarget) -> Self {
+        self.doc_target = Some(target);
+        self
+    }
+
+    /// Include private items, for analyzing the whole crate rather than its public API
+    ///
+    /// See [`Item::visibility`][crate::Item::visibility].
+    pub fn private_items(mut self, yes: bool) -> Self {
+        self.private_items = yes;
+        self
+    }
+
+    /// Include `#[doc(hidden)]` items, like when auditing everything reachable
+    ///
+    /// Hidden items are usually not meant to be public API, so are left out by default.  See
+    /// [`Item::is_hidden`][crate::Item::is_hidden].
+    pub fn hidden_items(mut self, yes: bool) -> Self {
+        self.hidden_items = yes;
+        self
+    }
+
+    /// Pass more flags to rustdoc, like `--cfg docsrs`
+    pub fn extra_rustdoc_flags(
+        mut self,
+        flags: impl IntoIterator<Item = impl Into<String>>,
+    ) -> Self {
+        self.rustdoc_flags
+            .extend(flags.into_iter().map(|f| f.into()));
+        self
+    }
+
+    /// Set an environment variable for `cargo doc`, like one a build script reads
+    pub fn env(
+        mut self,
+        key: impl Into<std::ffi::OsString>,
+        value: impl Into<std::ffi::OsString>,
+    ) -> Self {
+        self.envs.push((key.into(), value.into()));
+        self
+    }
+
+    /// Parse this rustdoc JSON rather than running `cargo doc`, like from a build system's cache
+    pub fn raw(mut self, raw: impl Into<String>) -> Self {
+        self.raw = Some(raw.into());
+        self
+    }
+
+    /// Only parse the subtree under this path, like `mycrate::net`
+    ///
+    /// Ancestors of the path are kept so the subtree is still reachable from the root.
+    pub fn scope(mut self, path: impl Into<String>) -> Self {
+        self.scope = Some(path.into());
+        self
+    }
+
+    /// Document this workspace member, like `mysubcrate` or `mysubcrate@1.0.0`
+    ///
+    /// The manifest passed in may then be for any part of the workspace, including a virtual
+    /// manifest.
+    pub fn package(mut self, spec: impl Into<String>) -> Self {
+        self.package = Some(spec.into());
+        self
+    }
+
+    /// `manifest_path` may also be a directory to [`discover`][crate::manifest::discover] the
+    /// manifest from, like with [`RustDocBuilder::into_api`].
     pub fn dump_raw(self, manifest_path: &std::path::Path) -> Result<String, crate::Error> {
-        let manifest = std::fs::read_to_string(manifest_path).map_err(|e| {
-            crate::Error::new(
-                crate::ErrorKind::ApiParse,
-                format!("Failed when reading {}: {}", manifest_path.display(), e),
-            )
-        })?;
-        let manifest: toml_edit::Document = manifest.parse().map_err(|e| {
-            crate::Error::new(
-                crate::ErrorKind::ApiParse,
-                format!("Failed to parse {}: {}", manifest_path.display(), e),
-            )
-        })?;
-        let crate_name = manifest["package"]["name"].as_str().ok_or_else(|| {
+        let manifest_path = self.resolve_manifest(manifest_path)?;
+        self._dump_raw(&manifest_path)
+    }
+
+    /// Extract the API for the package at `manifest_path`
+    ///
+    /// `manifest_path` may also be a directory, like a module's, to
+    /// [`discover`][crate::manifest::discover] the manifest from.
+    pub fn into_api(self, manifest_path: &std::path::Path) -> Result<crate::Api, crate::Error> {
+        let manifest_path = self.resolve_manifest(manifest_path)?;
+        let scope = self.scope.clone();
+        let raw = self._dump_raw(&manifest_path)?;
+        let mut parser = RustDocParser::new();
+        parser.scope = scope;
+        parser.parse(&raw, &manifest_path)
+    }
+
+    /// Extract the API of the library and each binary of the package at `manifest_path`
+    ///
+    /// This overrides any [`RustDocBuilder::doc_target`].
+    pub fn into_target_apis(
+        self,
+        manifest_path: &std::path::Path,
+    ) -> Result<Vec<(DocTarget, crate::Api)>, crate::Error> {
+        let manifest_path = self.resolve_manifest(manifest_path)?;
+        let metadata = cargo_metadata::MetadataCommand::new()
+            .manifest_path(&manifest_path)
+            .no_deps()
+            .exec()
+            .map_err(|e| crate::Error::new(crate::ErrorKind::Package, e))?;
+        let pkg = _find_package(&metadata, &manifest_path)?;
+        let mut apis = Vec::new();
+        for target in &pkg.targets {
+            let target = if _is_lib(target) {
+                DocTarget::Lib
+            } else if target.kind.iter().any(|kind| kind == "bin") {
+                DocTarget::Bin(target.name.clone())
+            } else {
+                continue;
+            };
+            let mut builder = self.clone();
+            builder.package = None;
+            let api = builder
+                .doc_target(target.clone())
+                .into_api(&manifest_path)?;
+            apis.push((target, api));
+        }
+        Ok(apis)
+    }
+
+    /// Extract the API of every workspace member that can be published
+    ///
+    /// Members with `publish = false` are skipped.  As with [`RustDocBuilder::into_api`], the
+    /// manifest isn't included; see [`Manifest`][crate::manifest::Manifest].
+    pub fn into_workspace_apis(
+        self,
+        manifest_path: &std::path::Path,
+    ) -> Result<Vec<(cargo_metadata::PackageId, crate::Api)>, crate::Error> {
+        let mut apis = Vec::new();
+        for (id, api) in self._workspace_apis(manifest_path, false)? {
+            apis.push((id, api?));
+        }
+        Ok(apis)
+    }
+
+    /// Like [`RustDocBuilder::into_workspace_apis`] but keep going past members that fail,
+    /// recording their errors
+    pub fn into_workspace_results(
+        self,
+        manifest_path: &std::path::Path,
+    ) -> Result<MemberApis, crate::Error> {
+        self._workspace_apis(manifest_path, true)
+    }
+
+    fn _workspace_apis(
+        self,
+        manifest_path: &std::path::Path,
+        keep_going: bool,
+    ) -> Result<MemberApis, crate::Error> {
+        let metadata = cargo_metadata::MetadataCommand::new()
+            .manifest_path(_manifest_file(manifest_path)?)
+            .no_deps()
+            .exec()
+            .map_err(|e| crate::Error::new(crate::ErrorKind::Package, e))?;
+        let mut apis = Vec::new();
+        for pkg in &metadata.packages {
+            if pkg
+                .publish
+                .as_ref()
+                .map_or(false, |registries| registries.is_empty())
+            {
+                tracing::debug!(package = %pkg.name, "skipping private package");
+                continue;
+            }
+            let mut builder = self.clone();
+            builder.package = None;
+            let api = builder.into_api(pkg.manifest_path.as_std_path());
+            let failed = api.is_err();
+            apis.push((pkg.id.clone(), api));
+            if failed && !keep_going {
+                break;
+            }
+        }
+        Ok(apis)
+    }
+
+    /// The manifest of the selected [`RustDocBuilder::package`], defaulting to `manifest_path`
+    ///
+    fn resolve_manifest(
+        &self,
+        manifest_path: &std::path::Path,
+    ) -> Result<std::path::PathBuf, crate::Error> {
+        let manifest_path = _manifest_file(manifest_path)?;
+        let manifest_path = manifest_path.as_path();
+        let spec = match self.package.as_deref() {
+            Some(spec) => spec,
+            None => return Ok(manifest_path.to_owned()),
+        };
+        let (name, version) = match spec.split_once('@') {
+            Some((name, version)) => (name, Some(version)),
+            None => (spec, None),
+        };
+
+        let metadata = cargo_metadata::MetadataCommand::new()
+            .manifest_path(manifest_path)
+            .no_deps()
+            .exec()
+            .map_err(|e| crate::Error::new(crate::ErrorKind::Package, e))?;
+        let mut candidates = metadata.packages.iter().filter(|pkg| {
+            pkg.name == name && version.map_or(true, |version| pkg.version.to_string() == version)
+        });
+        let pkg = candidates.next().ok_or_else(|| {
             crate::Error::new(
-                crate::ErrorKind::ApiParse,
+                crate::ErrorKind::Package,
                 format!(
-                    "Failed to parse {}: invalid package.name",
-                    manifest_path.display()
+                    "Package `{}` is not a member of the workspace at {}",
+                    spec, metadata.workspace_root
                 ),
             )
         })?;
+        if candidates.next().is_some() {
+            return Err(crate::Error::new(
+                crate::ErrorKind::Package,
+                format!("Package `{}` is ambiguous, include the version", spec),
+            ));
+        }
+        tracing::debug!(spec, manifest = %pkg.manifest_path, "resolved package");
+        Ok(pkg.manifest_path.clone().into_std_path_buf())
+    }
+
+    fn _dump_raw(&self, manifest_path: &std::path::Path) -> Result<String, crate::Error> {
+        if let Some(raw) = self.raw.as_ref() {
+            return Ok(raw.clone());
+        }
+
+        let _span = tracing::debug_span!("extract", manifest = %manifest_path.display()).entered();
+        let metadata = cargo_metadata::MetadataCommand::new()
+            .manifest_path(manifest_path)
+            .no_deps()
+            .exec()
+            .map_err(|e| crate::Error::new(crate::ErrorKind::ApiParse, e))?;
+        let pkg = _find_package(&metadata, manifest_path)?;
+        // rustdoc names the JSON after the crate, not the package
+        let crate_name = self._doc_target(pkg)?.name.replace('-', "_");
+        let crate_name = crate_name.as_str();
 
         let manifest_target_directory;
         let target_dir = if let Some(target_dir) = self.target_directory.as_deref() {
             target_dir
         } else {
-            let metadata = cargo_metadata::MetadataCommand::new()
-                .manifest_path(manifest_path)
-                .no_deps()
-                .exec()
-                .map_err(|e| crate::Error::new(crate::ErrorKind::ApiParse, e))?;
             manifest_target_directory = metadata
                 .target_directory
                 .as_path()
@@ -92,6 +423,24 @@ impl RustDocBuilder {
                 .join("crate-api/target");
             manifest_target_directory.as_path()
         };
+        tracing::debug!(crate_name, target_dir = %target_dir.display(), "documenting");
+
+        let toolchain = match self.toolchain.clone() {
+            Some(toolchain) => toolchain,
+            None => match _pinned_toolchain(manifest_path) {
+                Some((_, channel)) if channel.starts_with("nightly") => channel,
+                Some((path, channel)) => {
+                    // Running in the directory would pick it up, so be explicit about overriding it
+                    tracing::info!(
+                        "{} pins `{}`, which can't emit rustdoc JSON; using `nightly`",
+                        path.display(),
+                        channel
+                    );
+                    "nightly".to_owned()
+                }
+                None => "nightly".to_owned(),
+            },
+        };
 
         let stderr = if self.silence {
             std::process::Stdio::piped()
@@ -100,18 +449,66 @@ impl RustDocBuilder {
             std::process::Stdio::inherit()
         };
 
+        let mut rustdocflags = "-Z unstable-options --output-format=json".to_owned();
+        if self.hidden_items {
+            rustdocflags.push_str(" --document-hidden-items");
+        }
+        if self.private_items {
+            rustdocflags.push_str(" --document-private-items");
+        }
+        for flag in &self.rustdoc_flags {
+            rustdocflags.push(' ');
+            rustdocflags.push_str(flag);
+        }
+
         let mut cmd = std::process::Command::new("cargo");
-        cmd.env(
-            "RUSTDOCFLAGS",
-            "-Z unstable-options --document-hidden-items --output-format=json",
-        )
-        .stdout(std::process::Stdio::null()) // Don't pollute cargo api output
-        .stderr(stderr)
-        .args(["+nightly", "doc", "--all-features"])
-        .arg("--manifest-path")
-        .arg(manifest_path)
-        .arg("--target-dir")
-        .arg(target_dir);
+        cmd.envs(self.envs.iter().map(|(key, value)| (key, value)))
+            .env("RUSTDOCFLAGS", rustdocflags)
+            .stdout(std::process::Stdio::null()) // Don't pollute cargo api output
+            .stderr(stderr)
+            .arg(format!("+{}", toolchain))
+            .arg("doc")
+            .arg("--manifest-path")
+            .arg(manifest_path)
+            .arg("--target-dir")
+            .arg(target_dir);
+        let all_features = self
+            .all_features
+            .unwrap_or(self.features.is_empty() && !self.no_default_features);
+        if all_features {
+            cmd.arg("--all-features");
+        } else {
+            if self.no_default_features {
+                cmd.arg("--no-default-features");
+            }
+            if !self.features.is_empty() {
+                cmd.arg("--features").arg(self.features.join(","));
+            }
+        }
+        if let Some(target) = self.target.as_deref() {
+            cmd.arg("--target").arg(target);
+        }
+        if self.offline {
+            cmd.arg("--offline");
+        }
+        if self.locked {
+            cmd.arg("--locked");
+        }
+        if self.frozen {
+            cmd.arg("--frozen");
+        }
+        if let Some(jobs) = self.jobs {
+            cmd.arg("--jobs").arg(jobs.to_string());
+        }
+        match &self.doc_target {
+            Some(DocTarget::Lib) => {
+                cmd.arg("--lib");
+            }
+            Some(DocTarget::Bin(name)) => {
+                cmd.arg("--bin").arg(name);
+            }
+            None => {}
+        }
         if !self.deps {
             cmd.arg("--no-deps");
         }
@@ -123,9 +520,42 @@ impl RustDocBuilder {
             }
         }
 
+        let doc_dir = match self.target.as_deref() {
+            Some(target) => target_dir.join(target).join("doc"),
+            None => target_dir.join("doc"),
+        };
+        let json_path = doc_dir.join(format!("{}.json", crate_name));
+        let fingerprint_path = doc_dir.join(format!("{}.crate-api-fingerprint", crate_name));
+        let version = _toolchain_version(&toolchain)?;
+        let path_dependencies: Vec<_> = pkg
+            .dependencies
+            .iter()
+            .filter_map(|dep| dep.path.as_deref())
+            .map(|path| path.as_std_path())
+            .collect();
+        let fingerprint = version.and_then(|version| {
+            _fingerprint(
+                &version,
+                &cmd,
+                manifest_path,
+                target_dir,
+                &path_dependencies,
+            )
+        });
+        if let Some(fingerprint) = fingerprint.as_deref() {
+            let last = std::fs::read_to_string(&fingerprint_path).ok();
+            if last.as_deref() == Some(fingerprint) {
+                if let Ok(raw) = std::fs::read_to_string(&json_path) {
+                    tracing::debug!(path = %json_path.display(), "rustdoc json is fresh");
+                    return Ok(raw);
+                }
+            }
+        }
+
+        tracing::debug!(?cmd, "running cargo-doc");
         let output = cmd
             .output()
-            .map_err(|e| crate::Error::new(crate::ErrorKind::ApiParse, e))?;
+            .map_err(|e| crate::Error::new(crate::ErrorKind::DocBuild, e))?;
         if !output.status.success() {
             let message = if self.silence {
                 format!(
@@ -139,21 +569,221 @@ impl RustDocBuilder {
                     manifest_path.display(),
                 )
             };
-            return Err(crate::Error::new(crate::ErrorKind::ApiParse, message));
+            return Err(crate::Error::new(crate::ErrorKind::DocBuild, message));
         }
 
-        let json_path = target_dir.join(format!("doc/{}.json", crate_name));
-        std::fs::read_to_string(&json_path).map_err(|e| {
+        tracing::debug!(path = %json_path.display(), "loading rustdoc json");
+        let raw = std::fs::read_to_string(&json_path).map_err(|e| {
             crate::Error::new(
                 crate::ErrorKind::ApiParse,
                 format!("Failed when loading {}: {}", json_path.display(), e),
             )
+        })?;
+        if let Some(fingerprint) = fingerprint {
+            if let Err(err) = std::fs::write(&fingerprint_path, fingerprint) {
+                tracing::debug!(path = %fingerprint_path.display(), %err, "not fingerprinted");
+            }
+        }
+        Ok(raw)
+    }
+}
+
+/// `rustc -vV` for the toolchain, if `rustc` could be found
+///
+/// Rather than leaving the user with rustup's error, a missing toolchain is reported with how
+/// to install it.
+fn _toolchain_version(toolchain: &str) -> Result<Option<Vec<u8>>, crate::Error> {
+    let output = match std::process::Command::new("rustc")
+        .arg(format!("+{}", toolchain))
+        .arg("-vV")
+        .output()
+    {
+        Ok(output) => output,
+        Err(err) => {
+            tracing::debug!(%err, "could not run rustc");
+            return Ok(None);
+        }
+    };
+    if output.status.success() {
+        return Ok(Some(output.stdout));
+    }
+    // Like rustup's "toolchain 'nightly-...' is not installed" or, when it tried installing
+    // it, "no release found"
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    let reason = stderr
+        .lines()
+        .find_map(|line| line.strip_prefix("error: "))
+        .unwrap_or("`rustc` failed");
+    Err(crate::Error::new(
+        crate::ErrorKind::Toolchain,
+        format!(
+            "The `{0}` toolchain is unavailable ({1}); install it with `rustup toolchain install {0}`",
+            toolchain, reason
+        ),
+    ))
+}
+
+/// Identify what `cmd` would document, to tell whether its last JSON is still fresh
+///
+/// This covers the toolchain, the command line, and the size and modification time of the
+/// package's files and `Cargo.lock`.  There is none when a path dependency is outside of the
+/// package, as its files, and those of its own path dependencies, could change too.
+fn _fingerprint(
+    version: &[u8],
+    cmd: &std::process::Command,
+    manifest_path: &std::path::Path,
+    target_dir: &std::path::Path,
+    path_dependencies: &[&std::path::Path],
+) -> Option<String> {
+    use std::hash::Hash;
+    use std::hash::Hasher;
+
+    let mut hasher = std::collections::hash_map::DefaultHasher::new();
+    // Nightlies change under the same toolchain name
+    version.hash(&mut hasher);
+    format!("{:?}", cmd).hash(&mut hasher);
+
+    let root = manifest_path
+        .parent()
+        .filter(|dir| !dir.as_os_str().is_empty())
+        .unwrap_or_else(|| std::path::Path::new("."));
+    let canonical_root = root.canonicalize().ok()?;
+    if let Some(dependency) = path_dependencies.iter().find(|dependency| {
+        dependency
+            .canonicalize()
+            .map_or(true, |dependency| !dependency.starts_with(&canonical_root))
+    }) {
+        tracing::debug!(path = %dependency.display(), "path dependency outside of the package");
+        return None;
+    }
+    let mut files = Vec::new();
+    _source_files(root, target_dir, &mut files).ok()?;
+    if let Some(lock) = root
+        .ancestors()
+        .map(|dir| dir.join("Cargo.lock"))
+        .find(|lock| lock.is_file())
+    {
+        files.push(lock);
+    }
+    files.sort();
+    for path in files {
+        let metadata = std::fs::metadata(&path).ok()?;
+        path.hash(&mut hasher);
+        metadata.len().hash(&mut hasher);
+        metadata.modified().ok()?.hash(&mut hasher);
+    }
+    Some(format!("{:016x}", hasher.finish()))
+}
+
+/// The package of the manifest at `manifest_path`
+fn _find_package<'m>(
+    metadata: &'m cargo_metadata::Metadata,
+    manifest_path: &std::path::Path,
+) -> Result<&'m cargo_metadata::Package, crate::Error> {
+    let canonical =
+        |path: &std::path::Path| std::fs::canonicalize(path).unwrap_or_else(|_| path.to_owned());
+    let manifest_path = canonical(manifest_path);
+    metadata
+        .packages
+        .iter()
+        .find(|pkg| canonical(pkg.manifest_path.as_std_path()) == manifest_path)
+        .ok_or_else(|| {
+            crate::Error::new(
+                crate::ErrorKind::Package,
+                format!("{} is not a package's manifest", manifest_path.display()),
+            )
         })
+}
+
+fn _is_lib(target: &cargo_metadata::Target) -> bool {
+    target.kind.iter().any(|kind| {
+        matches!(
+            kind.as_str(),
+            "lib" | "rlib" | "dylib" | "cdylib" | "staticlib" | "proc-macro"
+        )
+    })
+}
+
+/// The channel from the `rust-toolchain.toml` (or `rust-toolchain`) governing `manifest_path`
+fn _pinned_toolchain(manifest_path: &std::path::Path) -> Option<(std::path::PathBuf, String)> {
+    let dir = std::fs::canonicalize(manifest_path).ok()?;
+    let path = dir.ancestors().skip(1).find_map(|dir| {
+        ["rust-toolchain.toml", "rust-toolchain"]
+            .iter()
+            .map(|name| dir.join(name))
+            .find(|path| path.is_file())
+    })?;
+    let content = std::fs::read_to_string(&path).ok()?;
+    let channel = match content.parse::<toml_edit::Document>() {
+        Ok(document) => document
+            .get("toolchain")
+            .and_then(|toolchain| toolchain.get("channel"))
+            .and_then(|channel| channel.as_str())?
+            .to_owned(),
+        // The legacy `rust-toolchain` is just the channel
+        Err(_) => content.trim().to_owned(),
+    };
+    tracing::debug!(path = %path.display(), channel = %channel, "found pinned toolchain");
+    Some((path, channel))
+}
+
+/// Collect the files under `dir`, skipping build output and hidden directories like `.git`
+fn _source_files(
+    dir: &std::path::Path,
+    target_dir: &std::path::Path,
+    files: &mut Vec<std::path::PathBuf>,
+) -> std::io::Result<()> {
+    for entry in std::fs::read_dir(dir)? {
+        let entry = entry?;
+        let path = entry.path();
+        if entry.file_type()?.is_dir() {
+            let name = entry.file_name();
+            if name.to_string_lossy().starts_with('.') || name == "target" || path == target_dir {
+                continue;
+            }
+            _source_files(&path, target_dir, files)?;
+        } else {
+            files.push(path);
+        }
     }
+    Ok(())
+}
 
-    pub fn into_api(self, manifest_path: &std::path::Path) -> Result<crate::Api, crate::Error> {
-        let raw = self.dump_raw(manifest_path)?;
-        parse_raw(&raw, manifest_path)
+impl RustDocBuilder {
+    /// The target `cargo doc` will document in `pkg`
+    fn _doc_target<'p>(
+        &self,
+        pkg: &'p cargo_metadata::Package,
+    ) -> Result<&'p cargo_metadata::Target, crate::Error> {
+        let bins = || {
+            pkg.targets
+                .iter()
+                .filter(|target| target.kind.iter().any(|kind| kind == "bin"))
+        };
+        let found = match &self.doc_target {
+            Some(DocTarget::Lib) => pkg.targets.iter().find(|target| _is_lib(target)),
+            Some(DocTarget::Bin(name)) => bins().find(|target| target.name == *name),
+            None => pkg
+                .targets
+                .iter()
+                .find(|target| _is_lib(target))
+                .or_else(|| bins().find(|target| target.name == pkg.name))
+                .or_else(|| {
+                    let mut bins = bins();
+                    bins.next().filter(|_| bins.next().is_none())
+                }),
+        };
+        found.ok_or_else(|| {
+            let message = match &self.doc_target {
+                Some(DocTarget::Lib) => format!("{} has no library", pkg.name),
+                Some(DocTarget::Bin(name)) => format!("{} has no binary `{}`", pkg.name, name),
+                None => format!(
+                    "{} has several binaries and no library; select one to document",
+                    pkg.name
+                ),
+            };
+            crate::Error::new(crate::ErrorKind::Package, message)
+        })
     }
 }
 
@@ -163,19 +793,45 @@ impl Default for RustDocBuilder {
     }
 }
 
+/// Find the manifest for `path` if it's a directory
+fn _manifest_file(path: &std::path::Path) -> Result<std::path::PathBuf, crate::Error> {
+    if path.is_dir() {
+        let discovered = crate::manifest::discover(path)?;
+        tracing::debug!(manifest = %discovered.display(), "discovered manifest");
+        Ok(discovered)
+    } else {
+        Ok(path.to_owned())
+    }
+}
+
 pub fn parse_raw(raw: &str, manifest_path: &std::path::Path) -> Result<crate::Api, crate::Error> {
     RustDocParser::new().parse(raw, manifest_path)
 }
 
 #[derive(Default)]
 struct RustDocParser {
+    scope: Option<String>,
     unprocessed: VecDeque<(Option<crate::PathId>, rustdoc_json_types_fork::Id)>,
-    deferred_imports: Vec<(crate::PathId, String, rustdoc_json_types_fork::Id)>,
+    deferred_imports: Vec<(
+        crate::PathId,
+        String,
+        rustdoc_json_types_fork::Id,
+        crate::Visibility,
+    )>,
+    deferred_trait_items: Vec<(crate::ItemId, Vec<rustdoc_json_types_fork::Id>)>,
+    deferred_inherent_items: Vec<(
+        crate::ItemId,
+        usize,
+        Vec<String>,
+        Vec<rustdoc_json_types_fork::Id>,
+    )>,
+    deferred_blanket_impls: Vec<(crate::ItemId, String, String)>,
 
     api: crate::Api,
     crate_ids: HashMap<u32, Option<crate::CrateId>>,
     path_ids: HashMap<rustdoc_json_types_fork::Id, Option<crate::PathId>>,
     item_ids: HashMap<rustdoc_json_types_fork::Id, Option<crate::ItemId>>,
+    impl_ids: HashMap<rustdoc_json_types_fork::Id, Option<crate::ImplId>>,
 }
 
 impl RustDocParser {
@@ -188,6 +844,8 @@ impl RustDocParser {
         raw: &str,
         manifest_path: &std::path::Path,
     ) -> Result<crate::Api, crate::Error> {
+        let _span = tracing::debug_span!("parse", manifest = %manifest_path.display()).entered();
+        _check_format_version(raw, manifest_path)?;
         let raw: rustdoc_json_types_fork::Crate = serde_json::from_str(raw).map_err(|e| {
             crate::Error::new(
                 crate::ErrorKind::ApiParse,
@@ -215,7 +873,9 @@ impl RustDocParser {
             self._parse_item(&raw, &raw_item_id, path_id, crate_id);
         }
 
-        for (parent_path_id, name, raw_target_id) in self.deferred_imports {
+        for (parent_path_id, name, raw_target_id, visibility) in
+            std::mem::take(&mut self.deferred_imports)
+        {
             let target_path_id = self.path_ids.get(&raw_target_id).unwrap().unwrap();
             let target_path = self
                 .api
@@ -237,6 +897,8 @@ impl RustDocParser {
             path.crate_id = parent_path.crate_id;
             path.item_id = target_path.item_id;
             path.children = target_path.children.clone();
+            path.is_experimental = target_path.is_experimental || parent_path.is_experimental;
+            path.visibility = visibility;
             let path_id = self.api.paths.push(path);
 
             self.api
@@ -247,9 +909,304 @@ impl RustDocParser {
                 .push(path_id);
         }
 
+        // Trait items are only parsed after the trait
+        for (trait_item_id, raw_item_ids) in std::mem::take(&mut self.deferred_trait_items) {
+            let trait_items = raw_item_ids
+                .iter()
+                .filter_map(|raw_item_id| self.item_ids.get(raw_item_id).copied().flatten())
+                .collect();
+            self.api
+                .items
+                .get_mut(trait_item_id)
+                .expect("item_id to always be valid")
+                .trait_items = trait_items;
+        }
+
+        for (type_item_id, index, features, raw_item_ids) in
+            std::mem::take(&mut self.deferred_inherent_items)
+        {
+            let items: Vec<_> = raw_item_ids
+                .iter()
+                .filter_map(|raw_item_id| self.item_ids.get(raw_item_id).copied().flatten())
+                .collect();
+            for item_id in &items {
+                let item = self
+                    .api
+                    .items
+                    .get_mut(*item_id)
+                    .expect("item_id to always be valid");
+                item.features = _merge_features(&features, &item.features);
+            }
+            self.api
+                .items
+                .get_mut(type_item_id)
+                .expect("item_id to always be valid")
+                .inherent_impls[index]
+                .items = items;
+        }
+
+        self._inherit_features();
+
+        // Blanket impls are only recorded once their trait is parsed
+        let blanket_impls: HashMap<_, _> = self
+            .api
+            .impls
+            .iter()
+            .map(|(impl_id, impl_)| ((impl_.trait_.clone(), impl_.for_.clone()), impl_id))
+            .collect();
+        for (type_item_id, trait_, for_) in std::mem::take(&mut self.deferred_blanket_impls) {
+            if let Some(impl_id) = blanket_impls.get(&(trait_, for_)) {
+                self.api
+                    .items
+                    .get_mut(type_item_id)
+                    .expect("item_id to always be valid")
+                    .blanket_impls
+                    .push(*impl_id);
+            }
+        }
+
+        tracing::debug!(
+            paths = self.api.paths.len(),
+            items = self.api.items.len(),
+            impls = self.api.impls.len(),
+            "parsed"
+        );
+        if let Some(scope) = self.scope.as_deref() {
+            if !self.api.paths.iter().any(|(_, path)| path.path == scope) {
+                return Err(crate::Error::new(
+                    crate::ErrorKind::ApiParse,
+                    format!(
+                        "`{}` is not in the API of {}",
+                        scope,
+                        manifest_path.display()
+                    ),
+                ));
+            }
+        }
+
         Ok(self.api)
     }
 
+    /// Whether a module's item leads into the scope
+    ///
+    /// Everything under the scope is kept while, above it, only the modules leading to it are.
+    fn _in_scope(
+        &self,
+        raw: &rustdoc_json_types_fork::Crate,
+        module_path_id: Option<crate::PathId>,
+        raw_item_id: &rustdoc_json_types_fork::Id,
+    ) -> bool {
+        let scope = match self.scope.as_deref() {
+            Some(scope) => scope,
+            None => return true,
+        };
+        let within = |path: &str| {
+            path == scope
+                || path
+                    .strip_prefix(scope)
+                    .map(|rest| rest.starts_with("::"))
+                    .unwrap_or(false)
+        };
+
+        let module_path = module_path_id
+            .and_then(|path_id| self.api.paths.get(path_id))
+            .map(|path| path.path.as_str());
+        if module_path.map(within).unwrap_or(false) {
+            return true;
+        }
+
+        match raw.paths.get(raw_item_id) {
+            Some(raw_path) => {
+                let path = raw_path.path.join("::");
+                within(&path)
+                    || scope
+                        .strip_prefix(path.as_str())
+                        .map(|rest| rest.starts_with("::"))
+                        .unwrap_or(false)
+            }
+            // Imports and impls only belong to the modules they are in
+            None => false,
+        }
+    }
+
+    /// Record trait impls, other than auto-trait and blanket impls
+    fn _parse_impls(
+        &mut self,
+        raw: &rustdoc_json_types_fork::Crate,
+        raw_impl_ids: &[rustdoc_json_types_fork::Id],
+    ) {
+        for raw_impl_id in raw_impl_ids {
+            // Impls are listed for both the trait and the type
+            if self.impl_ids.contains_key(raw_impl_id) {
+                continue;
+            }
+            let raw_impl = match raw.index.get(raw_impl_id) {
+                Some(raw_impl) => raw_impl,
+                // Not documented, like when dependencies aren't included
+                None => continue,
+            };
+            let impl_ = match &raw_impl.inner {
+                rustdoc_json_types_fork::ItemEnum::Impl(impl_) => impl_,
+                _ => continue,
+            };
+            let impl_id = _parse_trait_impl(raw, impl_).map(|mut trait_impl| {
+                trait_impl.crate_id = self._parse_crate(raw, raw_impl.crate_id);
+                trait_impl.features = _parse_features(&raw_impl.attrs);
+                trait_impl.span = raw_impl.span.clone().map(|raw_span| crate::Span {
+                    filename: raw_span.filename,
+                    begin: raw_span.begin,
+                    end: raw_span.end,
+                });
+                self.api.impls.push(trait_impl)
+            });
+            self.impl_ids.insert(raw_impl_id.clone(), impl_id);
+        }
+    }
+
+    /// The trait impls already parsed for a type
+    fn _trait_impls(&self, inner: &rustdoc_json_types_fork::ItemEnum) -> Vec<crate::ImplId> {
+        let raw_impl_ids = match inner {
+            rustdoc_json_types_fork::ItemEnum::Struct(struct_) => &struct_.impls,
+            rustdoc_json_types_fork::ItemEnum::Union(union_) => &union_.impls,
+            rustdoc_json_types_fork::ItemEnum::Enum(enum_) => &enum_.impls,
+            _ => return Vec::new(),
+        };
+        raw_impl_ids
+            .iter()
+            .filter_map(|raw_impl_id| self.impl_ids.get(raw_impl_id).copied().flatten())
+            .collect()
+    }
+
+    /// Look up the blanket impls covering a type once they are all parsed
+    ///
+    /// rustdoc lists a copy of each blanket impl under the types it covers, which is matched up
+    /// with the original by its trait and generic type.  Blanket impls from other crates, like
+    /// `impl<T> From<T> for T`, aren't recorded, so they are left out.
+    fn _defer_blanket_impls(
+        &mut self,
+        raw: &rustdoc_json_types_fork::Crate,
+        item_id: crate::ItemId,
+        raw_impl_ids: &[rustdoc_json_types_fork::Id],
+    ) {
+        for raw_impl_id in raw_impl_ids {
+            let impl_ = match raw.index.get(raw_impl_id).map(|raw_impl| &raw_impl.inner) {
+                Some(rustdoc_json_types_fork::ItemEnum::Impl(impl_)) => impl_,
+                _ => continue,
+            };
+            if let (Some(trait_), Some(blanket)) = (&impl_.trait_, &impl_.blanket_impl) {
+                self.deferred_blanket_impls.push((
+                    item_id,
+                    _render_full_type(raw, trait_),
+                    _render_full_type(raw, blanket),
+                ));
+            }
+        }
+    }
+
+    /// Gate items behind the features of the modules, traits, and types they are in
+    ///
+    /// Only declarations are followed, as a re-export's features are those of its target.
+    fn _inherit_features(&mut self) {
+        let mut unvisited: Vec<_> = self
+            .api
+            .root_id
+            .map(|root_id| (root_id, Vec::new()))
+            .into_iter()
+            .collect();
+        let mut visited = HashSet::new();
+        while let Some((path_id, inherited)) = unvisited.pop() {
+            if !visited.insert(path_id) {
+                continue;
+            }
+            let path = self
+                .api
+                .paths
+                .get(path_id)
+                .expect("path_id to always be valid");
+            if path.kind == crate::PathKind::Import {
+                continue;
+            }
+            let children = path.children.clone();
+            let features = match path.item_id {
+                Some(item_id) => {
+                    let item = self
+                        .api
+                        .items
+                        .get_mut(item_id)
+                        .expect("item_id to always be valid");
+                    item.features = _merge_features(&inherited, &item.features);
+                    let features = item.features.clone();
+                    let members: Vec<_> = item
+                        .trait_items
+                        .iter()
+                        .chain(
+                            item.inherent_impls
+                                .iter()
+                                .flat_map(|inherent_impl| inherent_impl.items.iter()),
+                        )
+                        .copied()
+                        .collect();
+                    for member_id in members {
+                        let member = self
+                            .api
+                            .items
+                            .get_mut(member_id)
+                            .expect("item_id to always be valid");
+                        member.features = _merge_features(&features, &member.features);
+                    }
+                    features
+                }
+                None => inherited,
+            };
+            unvisited.extend(children.into_iter().map(|child| (child, features.clone())));
+        }
+    }
+
+    /// Record a type's own impl blocks, whose items are only parsed after the type
+    fn _parse_inherent_impls(
+        &mut self,
+        raw: &rustdoc_json_types_fork::Crate,
+        item_id: crate::ItemId,
+        path_id: Option<crate::PathId>,
+        raw_impl_ids: &[rustdoc_json_types_fork::Id],
+    ) {
+        for raw_impl_id in raw_impl_ids {
+            let (raw_impl, impl_) = match raw.index.get(raw_impl_id) {
+                Some(
+                    raw_impl @ rustdoc_json_types_fork::Item {
+                        inner: rustdoc_json_types_fork::ItemEnum::Impl(impl_),
+                        ..
+                    },
+                ) if impl_.trait_.is_none() => (raw_impl, impl_),
+                _ => continue,
+            };
+            let mut inherent_impl = crate::InherentImpl::new(_render_type(&impl_.for_));
+            inherent_impl.generics = _convert_generics(&impl_.generics);
+            let item = self
+                .api
+                .items
+                .get_mut(item_id)
+                .expect("item_id to always be valid");
+            let index = item.inherent_impls.len();
+            item.inherent_impls.push(inherent_impl);
+            // Items of impls from other crates aren't always documented
+            let raw_item_ids: Vec<_> = impl_
+                .items
+                .iter()
+                .filter(|i| raw.index.contains_key(i))
+                .cloned()
+                .collect();
+            self.unprocessed
+                .extend(raw_item_ids.iter().map(|i| (path_id, i.clone())));
+            self.deferred_inherent_items.push((
+                item_id,
+                index,
+                _parse_features(&raw_impl.attrs),
+                raw_item_ids,
+            ));
+        }
+    }
+
     fn _parse_crate(
         &mut self,
         raw: &rustdoc_json_types_fork::Crate,
@@ -297,6 +1254,16 @@ impl RustDocParser {
                 begin: raw_span.begin,
                 end: raw_span.end,
             });
+            path.visibility = _parse_visibility(&raw_item.visibility);
+            path.is_experimental = raw_item
+                .docs
+                .as_deref()
+                .map(_is_experimental)
+                .unwrap_or(false)
+                || parent_path_id
+                    .and_then(|parent_path_id| self.api.paths.get(parent_path_id))
+                    .map(|parent| parent.is_experimental)
+                    .unwrap_or(false);
             let path_id = self.api.paths.push(path);
 
             if let Some(parent_path_id) = parent_path_id {
@@ -330,11 +1297,15 @@ impl RustDocParser {
             .get(raw_item_id)
             .expect("all item ids are in `index`");
 
-        let item_id = match &raw_item.inner {
+        match &raw_item.inner {
             rustdoc_json_types_fork::ItemEnum::Module(module) => {
-                self.unprocessed
-                    .extend(module.items.iter().map(move |i| (path_id, i.clone())));
-                None
+                let items: Vec<_> = module
+                    .items
+                    .iter()
+                    .filter(|i| self._in_scope(raw, path_id, i))
+                    .map(|i| (path_id, i.clone()))
+                    .collect();
+                self.unprocessed.extend(items);
             }
             rustdoc_json_types_fork::ItemEnum::Import(import) => {
                 let raw_target_id = import.id.as_ref().unwrap();
@@ -343,51 +1314,706 @@ impl RustDocParser {
                     path_id.unwrap(),
                     import.name.clone(),
                     raw_target_id.clone(),
+                    _parse_visibility(&raw_item.visibility),
                 ));
-                None
+                self.item_ids.insert(raw_item_id.clone(), None);
+                return None;
             }
             rustdoc_json_types_fork::ItemEnum::Trait(trait_) => {
                 self.unprocessed
                     .extend(trait_.items.iter().map(move |i| (path_id, i.clone())));
-                None
+                self._parse_impls(raw, &trait_.implementors);
+            }
+            rustdoc_json_types_fork::ItemEnum::Struct(struct_) => {
+                self._parse_impls(raw, &struct_.impls);
+            }
+            rustdoc_json_types_fork::ItemEnum::Union(union_) => {
+                self._parse_impls(raw, &union_.impls);
             }
             rustdoc_json_types_fork::ItemEnum::Impl(impl_) => {
                 self.unprocessed
                     .extend(impl_.items.iter().map(move |i| (path_id, i.clone())));
-                None
+                self.item_ids.insert(raw_item_id.clone(), None);
+                return None;
             }
             rustdoc_json_types_fork::ItemEnum::Enum(enum_) => {
                 self.unprocessed
                     .extend(enum_.variants.iter().map(move |i| (path_id, i.clone())));
-                None
-            }
-            _ => {
-                assert_ne!(self.api.root_id, None, "Module should be root");
-                let mut item = crate::Item::new();
-                item.crate_id = crate_id;
-                item.name = raw_item.name.clone();
-                item.span = raw_item.span.clone().map(|raw_span| crate::Span {
-                    filename: raw_span.filename,
-                    begin: raw_span.begin,
-                    end: raw_span.end,
-                });
-                let item_id = self.api.items.push(item);
-
-                if let Some(path_id) = path_id {
-                    self.api
-                        .paths
-                        .get_mut(path_id)
-                        .expect("path_id to always be valid")
-                        .item_id = Some(item_id);
-                }
-                Some(item_id)
+                self._parse_impls(raw, &enum_.impls);
             }
-        };
+            _ => {}
+        }
+
+        assert_ne!(self.api.root_id, None, "Module should be root");
+        let mut item = crate::Item::new();
+        item.crate_id = crate_id;
+        item.name = raw_item.name.clone();
+        item.span = raw_item.span.clone().map(|raw_span| crate::Span {
+            filename: raw_span.filename,
+            begin: raw_span.begin,
+            end: raw_span.end,
+        });
+        item.docs = raw_item.docs.as_deref().and_then(_doc_summary);
+        item.deprecated = raw_item.deprecation.clone().map(|raw_deprecation| {
+            let mut deprecation = crate::Deprecation::new();
+            deprecation.since = raw_deprecation.since;
+            deprecation.note = raw_deprecation.note;
+            deprecation
+        });
+        item.is_unsafe = _is_unsafe(&raw_item.inner);
+        item.abi = _abi(&raw_item.inner);
+        item.symbol = _parse_symbol(raw_item);
+        item.signature = _parse_signature(&raw_item.inner);
+        item.generics = _parse_generics(&raw_item.inner);
+        item.features = _parse_features(&raw_item.attrs);
+        item.is_hidden = _is_hidden(&raw_item.attrs);
+        item.is_non_exhaustive = _is_non_exhaustive(&raw_item.attrs);
+        item.must_use = _parse_must_use(&raw_item.attrs);
+        item.repr = _parse_repr(&raw_item.attrs);
+        item.visibility = _parse_visibility(&raw_item.visibility);
+        item.fields = _parse_fields(raw, &raw_item.inner);
+        item.variant_kind = _parse_variant_kind(&raw_item.inner);
+        item.has_private_fields = _has_private_fields(&raw_item.inner, &item.fields);
+        item.has_default = _has_default(&raw_item.inner);
+        item.is_sealed = _is_sealed(raw, &raw_item.inner);
+        item.supertraits = _parse_supertraits(&raw_item.inner);
+        _parse_declaration(&raw_item.inner, &mut item);
+        item.matchers = _parse_matchers(&raw_item.inner);
+        item.auto_traits = _parse_auto_traits(raw, &raw_item.inner);
+        item.trait_impls = self._trait_impls(&raw_item.inner);
+        let item_id = self.api.items.push(item);
+        match &raw_item.inner {
+            rustdoc_json_types_fork::ItemEnum::Trait(trait_) => {
+                self.deferred_trait_items
+                    .push((item_id, trait_.items.clone()));
+            }
+            rustdoc_json_types_fork::ItemEnum::Struct(struct_) => {
+                self._parse_inherent_impls(raw, item_id, path_id, &struct_.impls);
+                self._defer_blanket_impls(raw, item_id, &struct_.impls);
+            }
+            rustdoc_json_types_fork::ItemEnum::Union(union_) => {
+                self._parse_inherent_impls(raw, item_id, path_id, &union_.impls);
+                self._defer_blanket_impls(raw, item_id, &union_.impls);
+            }
+            rustdoc_json_types_fork::ItemEnum::Enum(enum_) => {
+                self._parse_inherent_impls(raw, item_id, path_id, &enum_.impls);
+                self._defer_blanket_impls(raw, item_id, &enum_.impls);
+            }
+            _ => {}
+        }
+        let item_id = Some(item_id);
+
+        // Associated items have no path of their own, so don't let them take over their parent's
+        if let Some(own_path_id) = self.path_ids.get(raw_item_id).copied().flatten() {
+            self.api
+                .paths
+                .get_mut(own_path_id)
+                .expect("path_id to always be valid")
+                .item_id = item_id;
+        }
         self.item_ids.insert(raw_item_id.clone(), item_id);
         item_id
     }
 }
 
+/// Report a version mismatch directly, rather than whatever part of the format changed first
+fn _check_format_version(raw: &str, manifest_path: &std::path::Path) -> Result<(), crate::Error> {
+    #[derive(serde::Deserialize)]
+    struct Header {
+        format_version: u32,
+    }
+
+    match serde_json::from_str::<Header>(raw) {
+        Ok(header) if header.format_version != rustdoc_json_types_fork::FORMAT_VERSION => {
+            Err(crate::Error::new(
+                crate::ErrorKind::FormatVersion,
+                format!(
+                    "rustdoc json for {} is format version {} but only {} is supported",
+                    manifest_path.display(),
+                    header.format_version,
+                    rustdoc_json_types_fork::FORMAT_VERSION
+                ),
+            ))
+        }
+        // Anything malformed gets reported when parsing the full format
+        _ => Ok(()),
+    }
+}
+
+/// Auto-trait and blanket impls are left out as they follow from other parts of the API
+fn _parse_trait_impl(
+    raw: &rustdoc_json_types_fork::Crate,
+    impl_: &rustdoc_json_types_fork::Impl,
+) -> Option<crate::Impl> {
+    if impl_.synthetic || impl_.blanket_impl.is_some() {
+        return None;
+    }
+    let trait_ = impl_.trait_.as_ref()?;
+
+    let mut trait_impl = crate::Impl::new(
+        _render_full_type(raw, trait_),
+        _render_full_type(raw, &impl_.for_),
+    );
+    trait_impl.generics = _convert_generics(&impl_.generics);
+    trait_impl.is_negative = impl_.negative;
+    trait_impl.is_unsafe = impl_.is_unsafe;
+    Some(trait_impl)
+}
+
+/// Like [`_render_type`] but with the full path for the outermost type, when known
+fn _render_full_type(
+    raw: &rustdoc_json_types_fork::Crate,
+    raw_type: &rustdoc_json_types_fork::Type,
+) -> String {
+    match raw_type {
+        rustdoc_json_types_fork::Type::ResolvedPath {
+            id,
+            args,
+            param_names,
+            ..
+        } if param_names.is_empty() => match raw.paths.get(id) {
+            Some(raw_path) => {
+                let mut rendered = raw_path.path.join("::");
+                if let Some(args) = args {
+                    rendered.push_str(&_render_generic_args(args));
+                }
+                rendered
+            }
+            None => _render_type(raw_type),
+        },
+        _ => _render_type(raw_type),
+    }
+}
+
+fn _doc_summary(docs: &str) -> Option<String> {
+    let summary = docs
+        .trim()
+        .split("\n\n")
+        .next()
+        .map(|summary| summary.trim())
+        .unwrap_or_default();
+    (!summary.is_empty()).then(|| summary.to_owned())
+}
+
+fn _is_unsafe(inner: &rustdoc_json_types_fork::ItemEnum) -> bool {
+    match inner {
+        rustdoc_json_types_fork::ItemEnum::Function(func) => func
+            .header
+            .contains(&rustdoc_json_types_fork::Qualifiers::Unsafe),
+        rustdoc_json_types_fork::ItemEnum::Method(method) => method
+            .header
+            .contains(&rustdoc_json_types_fork::Qualifiers::Unsafe),
+        rustdoc_json_types_fork::ItemEnum::Trait(trait_) => trait_.is_unsafe,
+        _ => false,
+    }
+}
+
+/// The ABI of a function, when it isn't the default `"Rust"` ABI
+fn _abi(inner: &rustdoc_json_types_fork::ItemEnum) -> Option<String> {
+    let abi = match inner {
+        rustdoc_json_types_fork::ItemEnum::Function(func) => func.abi.as_str(),
+        rustdoc_json_types_fork::ItemEnum::Method(method) => method.abi.as_str(),
+        _ => return None,
+    };
+    (abi != "\"Rust\"" && abi != "Rust").then(|| abi.trim_matches('"').to_owned())
+}
+
+fn _parse_symbol(raw_item: &rustdoc_json_types_fork::Item) -> Option<String> {
+    for attr in &raw_item.attrs {
+        let attr = attr.trim();
+        if attr == "#[no_mangle]" {
+            return raw_item.name.clone();
+        }
+        if let Some(export_name) = attr
+            .strip_prefix("#[export_name")
+            .and_then(|rest| rest.trim_start().strip_prefix('='))
+        {
+            let export_name = export_name.trim().trim_end_matches(']').trim();
+            return Some(export_name.trim_matches('"').to_owned());
+        }
+    }
+    None
+}
+
+fn _parse_signature(inner: &rustdoc_json_types_fork::ItemEnum) -> Option<crate::Signature> {
+    let (decl, header) = match inner {
+        rustdoc_json_types_fork::ItemEnum::Function(func) => (&func.decl, &func.header),
+        rustdoc_json_types_fork::ItemEnum::Method(method) => (&method.decl, &method.header),
+        _ => return None,
+    };
+    let mut signature = crate::Signature::new();
+    signature.is_const = header.contains(&rustdoc_json_types_fork::Qualifiers::Const);
+    signature.is_async = header.contains(&rustdoc_json_types_fork::Qualifiers::Async);
+    signature.inputs = decl
+        .inputs
+        .iter()
+        .map(|(name, raw_type)| crate::Input::new(name, _render_type(raw_type)))
+        .collect();
+    signature.output = decl.output.as_ref().map(_render_type);
+    signature.c_variadic = decl.c_variadic;
+    Some(signature)
+}
+
+fn _parse_generics(inner: &rustdoc_json_types_fork::ItemEnum) -> crate::Generics {
+    let raw_generics = match inner {
+        rustdoc_json_types_fork::ItemEnum::Function(func) => &func.generics,
+        rustdoc_json_types_fork::ItemEnum::Method(method) => &method.generics,
+        rustdoc_json_types_fork::ItemEnum::Struct(struct_) => &struct_.generics,
+        rustdoc_json_types_fork::ItemEnum::Union(union_) => &union_.generics,
+        rustdoc_json_types_fork::ItemEnum::Enum(enum_) => &enum_.generics,
+        rustdoc_json_types_fork::ItemEnum::Trait(trait_) => &trait_.generics,
+        rustdoc_json_types_fork::ItemEnum::Typedef(typedef) => &typedef.generics,
+        rustdoc_json_types_fork::ItemEnum::Impl(impl_) => &impl_.generics,
+        _ => return crate::Generics::new(),
+    };
+    _convert_generics(raw_generics)
+}
+
+fn _convert_generics(raw_generics: &rustdoc_json_types_fork::Generics) -> crate::Generics {
+    let mut generics = crate::Generics::new();
+    generics.params = raw_generics
+        .params
+        .iter()
+        // `impl Trait` arguments are part of the function's inputs
+        .filter(|param| !param.name.starts_with("impl "))
+        .map(|param| match &param.kind {
+            rustdoc_json_types_fork::GenericParamDefKind::Lifetime { outlives } => {
+                let mut generic =
+                    crate::GenericParam::new(&param.name, crate::GenericParamKind::Lifetime);
+                generic.bounds = outlives.clone();
+                generic
+            }
+            rustdoc_json_types_fork::GenericParamDefKind::Type { bounds, default } => {
+                let mut generic =
+                    crate::GenericParam::new(&param.name, crate::GenericParamKind::Type);
+                generic.bounds = bounds.iter().map(_render_generic_bound).collect();
+                generic.default = default.as_ref().map(_render_type);
+                generic
+            }
+            rustdoc_json_types_fork::GenericParamDefKind::Const { ty, default } => {
+                let mut generic = crate::GenericParam::new(
+                    &param.name,
+                    crate::GenericParamKind::Const(_render_type(ty)),
+                );
+                generic.default = default.clone();
+                generic
+            }
+        })
+        .collect();
+    generics.where_predicates = raw_generics
+        .where_predicates
+        .iter()
+        .map(|predicate| match predicate {
+            rustdoc_json_types_fork::WherePredicate::BoundPredicate { ty, bounds } => {
+                crate::WherePredicate::Bound {
+                    target: _render_type(ty),
+                    bounds: bounds.iter().map(_render_generic_bound).collect(),
+                }
+            }
+            rustdoc_json_types_fork::WherePredicate::RegionPredicate { lifetime, bounds } => {
+                crate::WherePredicate::Bound {
+                    target: lifetime.clone(),
+                    bounds: bounds.iter().map(_render_generic_bound).collect(),
+                }
+            }
+            rustdoc_json_types_fork::WherePredicate::EqPredicate { lhs, rhs } => {
+                crate::WherePredicate::Eq {
+                    lhs: _render_type(lhs),
+                    rhs: _render_type(rhs),
+                }
+            }
+        })
+        .collect();
+    generics
+}
+
+fn _parse_fields(
+    raw: &rustdoc_json_types_fork::Crate,
+    inner: &rustdoc_json_types_fork::ItemEnum,
+) -> Vec<crate::Field> {
+    let raw_field_ids = match inner {
+        rustdoc_json_types_fork::ItemEnum::Struct(struct_) => &struct_.fields,
+        rustdoc_json_types_fork::ItemEnum::Union(union_) => &union_.fields,
+        rustdoc_json_types_fork::ItemEnum::Variant(rustdoc_json_types_fork::Variant::Struct(
+            fields,
+        )) => fields,
+        // Tuple fields are named by their position, like in `variant.0`
+        rustdoc_json_types_fork::ItemEnum::Variant(rustdoc_json_types_fork::Variant::Tuple(
+            raw_types,
+        )) => {
+            return raw_types
+                .iter()
+                .enumerate()
+                .map(|(i, raw_type)| {
+                    let mut field = crate::Field::new(i.to_string(), _render_type(raw_type));
+                    field.visibility = crate::Visibility::Inherited;
+                    field
+                })
+                .collect();
+        }
+        _ => return Vec::new(),
+    };
+    raw_field_ids
+        .iter()
+        .filter_map(|raw_field_id| {
+            let raw_field = raw
+                .index
+                .get(raw_field_id)
+                .expect("all item ids are in `index`");
+            match &raw_field.inner {
+                rustdoc_json_types_fork::ItemEnum::StructField(raw_type) => {
+                    let mut field = crate::Field::new(
+                        raw_field.name.clone().unwrap_or_default(),
+                        _render_type(raw_type),
+                    );
+                    field.visibility = _parse_visibility(&raw_field.visibility);
+                    Some(field)
+                }
+                _ => None,
+            }
+        })
+        .collect()
+}
+
+// This format version doesn't report explicit discriminants
+fn _parse_variant_kind(inner: &rustdoc_json_types_fork::ItemEnum) -> Option<crate::VariantKind> {
+    match inner {
+        rustdoc_json_types_fork::ItemEnum::Variant(variant) => Some(match variant {
+            rustdoc_json_types_fork::Variant::Plain => crate::VariantKind::Unit,
+            rustdoc_json_types_fork::Variant::Tuple(_) => crate::VariantKind::Tuple,
+            rustdoc_json_types_fork::Variant::Struct(_) => crate::VariantKind::Struct,
+        }),
+        _ => None,
+    }
+}
+
+fn _has_default(inner: &rustdoc_json_types_fork::ItemEnum) -> bool {
+    match inner {
+        rustdoc_json_types_fork::ItemEnum::Method(method) => method.has_body,
+        rustdoc_json_types_fork::ItemEnum::AssocConst { default, .. } => default.is_some(),
+        rustdoc_json_types_fork::ItemEnum::AssocType { default, .. } => default.is_some(),
+        _ => false,
+    }
+}
+
+/// rustdoc renders each rule of a `macro_rules!` on its own line, with its body left out
+///
+/// Matchers can contain `=>` themselves, like `($key:expr => $value:expr)`, but not the
+/// placeholder body.
+fn _parse_matchers(inner: &rustdoc_json_types_fork::ItemEnum) -> Vec<String> {
+    let source = match inner {
+        rustdoc_json_types_fork::ItemEnum::Macro(source) => source,
+        _ => return Vec::new(),
+    };
+    let rules = match source
+        .strip_prefix("macro_rules!")
+        .and_then(|rest| rest.get(rest.find('{')? + 1..rest.rfind('}')?))
+    {
+        Some(rules) => rules,
+        None => return Vec::new(),
+    };
+    rules
+        .split("=> { ... };")
+        .map(str::trim)
+        .filter(|matcher| !matcher.is_empty())
+        .map(ToOwned::to_owned)
+        .collect()
+}
+
+fn _parse_supertraits(inner: &rustdoc_json_types_fork::ItemEnum) -> Vec<String> {
+    match inner {
+        rustdoc_json_types_fork::ItemEnum::Trait(trait_) => {
+            trait_.bounds.iter().map(_render_generic_bound).collect()
+        }
+        _ => Vec::new(),
+    }
+}
+
+/// Consts and statics are declared with a type and value, type aliases with the type they stand
+/// for, derive macros with their helper attributes, and associated types and consts with bounds
+/// or a type, and maybe a default
+fn _parse_declaration(inner: &rustdoc_json_types_fork::ItemEnum, item: &mut crate::Item) {
+    match inner {
+        rustdoc_json_types_fork::ItemEnum::ProcMacro(proc_macro) => {
+            item.helpers = proc_macro.helpers.clone();
+        }
+        rustdoc_json_types_fork::ItemEnum::Typedef(typedef) => {
+            item.type_ = Some(_render_type(&typedef.type_));
+        }
+        rustdoc_json_types_fork::ItemEnum::Constant(constant) => {
+            item.type_ = Some(_render_type(&constant.type_));
+            // Other expressions are rendered lossily, if at all
+            item.value = constant.is_literal.then(|| constant.expr.clone());
+        }
+        rustdoc_json_types_fork::ItemEnum::Static(static_) => {
+            item.type_ = Some(_render_type(&static_.type_));
+            item.value = (static_.expr != "_").then(|| static_.expr.clone());
+            item.is_mutable = static_.mutable;
+        }
+        rustdoc_json_types_fork::ItemEnum::AssocConst { type_, default } => {
+            item.type_ = Some(_render_type(type_));
+            item.default = default.clone();
+        }
+        rustdoc_json_types_fork::ItemEnum::AssocType { bounds, default } => {
+            item.bounds = bounds.iter().map(_render_generic_bound).collect();
+            item.default = default.as_ref().map(_render_type);
+        }
+        _ => {}
+    }
+}
+
+/// rustdoc synthesizes impls for the auto traits a type implements
+fn _parse_auto_traits(
+    raw: &rustdoc_json_types_fork::Crate,
+    inner: &rustdoc_json_types_fork::ItemEnum,
+) -> Vec<String> {
+    let raw_impl_ids = match inner {
+        rustdoc_json_types_fork::ItemEnum::Struct(struct_) => &struct_.impls,
+        rustdoc_json_types_fork::ItemEnum::Union(union_) => &union_.impls,
+        rustdoc_json_types_fork::ItemEnum::Enum(enum_) => &enum_.impls,
+        _ => return Vec::new(),
+    };
+    let mut auto_traits: Vec<_> = raw_impl_ids
+        .iter()
+        .filter_map(|raw_impl_id| match &raw.index.get(raw_impl_id)?.inner {
+            rustdoc_json_types_fork::ItemEnum::Impl(impl_)
+                if impl_.synthetic && !impl_.negative =>
+            {
+                match impl_.trait_.as_ref()? {
+                    rustdoc_json_types_fork::Type::ResolvedPath { name, .. } => {
+                        Some(name.rsplit("::").next().unwrap_or(name).to_owned())
+                    }
+                    _ => None,
+                }
+            }
+            _ => None,
+        })
+        .collect();
+    auto_traits.sort();
+    auto_traits.dedup();
+    auto_traits
+}
+
+/// Sealed traits have a supertrait that is defined somewhere users can't reach, like
+/// `pub trait Public: private::Sealed {}`
+fn _is_sealed(
+    raw: &rustdoc_json_types_fork::Crate,
+    inner: &rustdoc_json_types_fork::ItemEnum,
+) -> bool {
+    let trait_ = match inner {
+        rustdoc_json_types_fork::ItemEnum::Trait(trait_) => trait_,
+        _ => return false,
+    };
+    trait_.bounds.iter().any(|bound| match bound {
+        rustdoc_json_types_fork::GenericBound::TraitBound {
+            trait_: rustdoc_json_types_fork::Type::ResolvedPath { id, .. },
+            ..
+        } => !raw.paths.contains_key(id),
+        _ => false,
+    })
+}
+
+/// Private fields are only listed with `--document-private-items`, otherwise they are stripped
+fn _has_private_fields(inner: &rustdoc_json_types_fork::ItemEnum, fields: &[crate::Field]) -> bool {
+    let fields_stripped = match inner {
+        rustdoc_json_types_fork::ItemEnum::Struct(struct_) => struct_.fields_stripped,
+        rustdoc_json_types_fork::ItemEnum::Union(union_) => union_.fields_stripped,
+        _ => return false,
+    };
+    fields_stripped
+        || fields
+            .iter()
+            .any(|field| field.visibility != crate::Visibility::Public)
+}
+
+/// Extract the features required by `#[cfg(...)]` and `#[doc(cfg(...))]`
+///
+/// Only features that must be enabled count, so those under `any(...)` or `not(...)` are
+/// skipped.
+fn _parse_features(attrs: &[String]) -> Vec<String> {
+    let mut features = Vec::new();
+    for attr in attrs {
+        let attr = attr.trim();
+        let mut predicate = match attr
+            .strip_prefix("#[cfg(")
+            .or_else(|| attr.strip_prefix("#[doc(cfg("))
+        {
+            Some(predicate) => predicate,
+            None => continue,
+        };
+        let mut required = Vec::new();
+        if _parse_cfg_predicate(&mut predicate, true, &mut required).is_none() {
+            tracing::debug!(attr, "unrecognized cfg");
+            continue;
+        }
+        for feature in required {
+            if !features.contains(&feature) {
+                features.push(feature);
+            }
+        }
+    }
+    features
+}
+
+/// Parse one cfg predicate off the front of `input`, collecting the features it requires
+fn _parse_cfg_predicate(
+    input: &mut &str,
+    required: bool,
+    features: &mut Vec<String>,
+) -> Option<()> {
+    let rest = input.trim_start();
+    let end = rest
+        .find(|c: char| !(c.is_alphanumeric() || c == '_'))
+        .unwrap_or(rest.len());
+    let (name, rest) = rest.split_at(end);
+    if name.is_empty() {
+        return None;
+    }
+    let rest = rest.trim_start();
+    if let Some(value) = rest.strip_prefix('=') {
+        let value = value.trim_start().strip_prefix('"')?;
+        let end = value.find('"')?;
+        if required && name == "feature" {
+            features.push(value[..end].to_owned());
+        }
+        *input = &value[end + 1..];
+    } else if let Some(mut rest) = rest.strip_prefix('(') {
+        let required = required && name == "all";
+        loop {
+            rest = rest.trim_start();
+            if let Some(after) = rest.strip_prefix(')') {
+                rest = after;
+                break;
+            }
+            _parse_cfg_predicate(&mut rest, required, features)?;
+            rest = rest.trim_start();
+            if let Some(after) = rest.strip_prefix(',') {
+                rest = after;
+            } else if let Some(after) = rest.strip_prefix(')') {
+                rest = after;
+                break;
+            } else {
+                return None;
+            }
+        }
+        *input = rest;
+    } else {
+        *input = rest;
+    }
+    Some(())
+}
+
+/// The `outer` features followed by any `inner` ones not already among them
+fn _merge_features(outer: &[String], inner: &[String]) -> Vec<String> {
+    let mut features = outer.to_vec();
+    for feature in inner {
+        if !features.contains(feature) {
+            features.push(feature.clone());
+        }
+    }
+    features
+}
+
+/// Experimental items are called out with `**Experimental**` in their docs
+fn _is_experimental(docs: &str) -> bool {
+    docs.contains("**Experimental**")
+}
+
+fn _parse_visibility(visibility: &rustdoc_json_types_fork::Visibility) -> crate::Visibility {
+    match visibility {
+        rustdoc_json_types_fork::Visibility::Public => crate::Visibility::Public,
+        rustdoc_json_types_fork::Visibility::Default => crate::Visibility::Inherited,
+        rustdoc_json_types_fork::Visibility::Crate => crate::Visibility::Crate,
+        rustdoc_json_types_fork::Visibility::Restricted { path, .. } => {
+            crate::Visibility::Restricted(path.clone())
+        }
+    }
+}
+
+fn _is_hidden(attrs: &[String]) -> bool {
+    attrs.iter().any(|attr| {
+        let attr: String = attr.chars().filter(|c| !c.is_whitespace()).collect();
+        attr.strip_prefix("#[doc(")
+            .and_then(|attr| attr.strip_suffix(")]"))
+            .map(|args| args.split(',').any(|arg| arg == "hidden"))
+            .unwrap_or(false)
+    })
+}
+
+fn _is_non_exhaustive(attrs: &[String]) -> bool {
+    attrs.iter().any(|attr| {
+        let attr: String = attr.chars().filter(|c| !c.is_whitespace()).collect();
+        attr == "#[non_exhaustive]"
+    })
+}
+
+/// `#[must_use]`, along with its message if it has one
+fn _parse_must_use(attrs: &[String]) -> Option<crate::MustUse> {
+    attrs.iter().find_map(|attr| {
+        let attr = attr.trim().strip_prefix("#[must_use")?.strip_suffix(']')?;
+        let mut must_use = crate::MustUse::new();
+        if let Some(message) = attr.trim_start().strip_prefix('=') {
+            let message = message.trim().strip_prefix('"')?.strip_suffix('"')?;
+            must_use.message = Some(_unescape(message));
+        } else if !attr.is_empty() {
+            return None;
+        }
+        Some(must_use)
+    })
+}
+
+/// The contents of a string literal, with escapes and line continuations resolved
+fn _unescape(literal: &str) -> String {
+    let mut unescaped = String::new();
+    let mut chars = literal.chars().peekable();
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            unescaped.push(c);
+            continue;
+        }
+        match chars.next() {
+            Some('\n') => while chars.next_if(|c| c.is_whitespace()).is_some() {},
+            Some('n') => unescaped.push('\n'),
+            Some('t') => unescaped.push('\t'),
+            Some(c) => unescaped.push(c),
+            None => {}
+        }
+    }
+    unescaped
+}
+
+/// Every hint from `#[repr(...)]`, in order, like `["C", "packed(2)"]` for `#[repr(C, packed(2))]`
+fn _parse_repr(attrs: &[String]) -> Vec<String> {
+    let mut repr = Vec::new();
+    for attr in attrs {
+        let attr: String = attr.chars().filter(|c| !c.is_whitespace()).collect();
+        let hints = match attr
+            .strip_prefix("#[repr(")
+            .and_then(|attr| attr.strip_suffix(")]"))
+        {
+            Some(hints) => hints,
+            None => continue,
+        };
+        let mut depth = 0;
+        let mut start = 0;
+        for (i, c) in hints.char_indices() {
+            match c {
+                '(' => depth += 1,
+                ')' => depth -= 1,
+                ',' if depth == 0 => {
+                    repr.push(hints[start..i].to_owned());
+                    start = i + 1;
+                }
+                _ => {}
+            }
+        }
+        if start < hints.len() {
+            repr.push(hints[start..].to_owned());
+        }
+    }
+    repr
+}
+
 fn _convert_path_kind(kind: rustdoc_json_types_fork::ItemKind) -> crate::PathKind {
     match kind {
         rustdoc_json_types_fork::ItemKind::Module => crate::PathKind::Module,
@@ -419,3 +2045,466 @@ fn _convert_path_kind(kind: rustdoc_json_types_fork::ItemKind) -> crate::PathKin
         }
     }
 }
+
+fn _render_type(raw_type: &rustdoc_json_types_fork::Type) -> String {
+    match raw_type {
+        rustdoc_json_types_fork::Type::ResolvedPath {
+            name,
+            args,
+            param_names,
+            ..
+        } => {
+            let mut rendered = name.clone();
+            if let Some(args) = args {
+                rendered.push_str(&_render_generic_args(args));
+            }
+            if param_names.is_empty() {
+                rendered
+            } else {
+                let mut bounds = vec![rendered];
+                bounds.extend(param_names.iter().map(_render_generic_bound));
+                format!("dyn {}", bounds.join(" + "))
+            }
+        }
+        rustdoc_json_types_fork::Type::Generic(name) => name.clone(),
+        rustdoc_json_types_fork::Type::Primitive(name) => name.clone(),
+        rustdoc_json_types_fork::Type::FunctionPointer(fn_ptr) => {
+            let mut rendered = String::new();
+            let mut decl = _render_fn_decl(&fn_ptr.decl);
+            match _elide_lifetimes(&decl, &fn_ptr.generic_params) {
+                Some(elided) => decl = elided,
+                None if !fn_ptr.generic_params.is_empty() => {
+                    rendered.push_str(&format!(
+                        "for<{}> ",
+                        _render_generic_param_defs(&fn_ptr.generic_params)
+                    ));
+                }
+                None => {}
+            }
+            rendered.push_str(&_render_qualifiers(&fn_ptr.header));
+            let abi = fn_ptr.abi.trim_matches('"');
+            if abi != "Rust" {
+                rendered.push_str(&format!("extern \"{}\" ", abi));
+            }
+            rendered.push_str("fn");
+            rendered.push_str(&decl);
+            rendered
+        }
+        rustdoc_json_types_fork::Type::Tuple(types) => {
+            if types.len() == 1 {
+                format!("({},)", _render_type(&types[0]))
+            } else {
+                format!(
+                    "({})",
+                    types
+                        .iter()
+                        .map(_render_type)
+                        .collect::<Vec<_>>()
+                        .join(", ")
+                )
+            }
+        }
+        rustdoc_json_types_fork::Type::Slice(type_) => format!("[{}]", _render_type(type_)),
+        rustdoc_json_types_fork::Type::Array { type_, len } => {
+            format!("[{}; {}]", _render_type(type_), len)
+        }
+        rustdoc_json_types_fork::Type::ImplTrait(bounds) => format!(
+            "impl {}",
+            bounds
+                .iter()
+                .map(_render_generic_bound)
+                .collect::<Vec<_>>()
+                .join(" + ")
+        ),
+        rustdoc_json_types_fork::Type::Infer => "_".to_owned(),
+        rustdoc_json_types_fork::Type::RawPointer { mutable, type_ } => {
+            let mutability = if *mutable { "mut" } else { "const" };
+            format!("*{} {}", mutability, _render_type(type_))
+        }
+        rustdoc_json_types_fork::Type::BorrowedRef {
+            lifetime,
+            mutable,
+            type_,
+        } => {
+            let mut rendered = "&".to_owned();
+            if let Some(lifetime) = lifetime {
+                rendered.push_str(lifetime);
+                rendered.push(' ');
+            }
+            if *mutable {
+                rendered.push_str("mut ");
+            }
+            rendered.push_str(&_render_type(type_));
+            rendered
+        }
+        rustdoc_json_types_fork::Type::QualifiedPath {
+            name,
+            self_type,
+            trait_,
+        } => format!(
+            "<{} as {}>::{}",
+            _render_type(self_type),
+            _render_type(trait_),
+            name
+        ),
+    }
+}
+
+fn _render_generic_args(args: &rustdoc_json_types_fork::GenericArgs) -> String {
+    match args {
+        rustdoc_json_types_fork::GenericArgs::AngleBracketed { args, bindings } => {
+            let mut rendered: Vec<_> = args
+                .iter()
+                .map(|arg| match arg {
+                    rustdoc_json_types_fork::GenericArg::Lifetime(lifetime) => lifetime.clone(),
+                    rustdoc_json_types_fork::GenericArg::Type(type_) => _render_type(type_),
+                    rustdoc_json_types_fork::GenericArg::Const(constant) => constant.expr.clone(),
+                    rustdoc_json_types_fork::GenericArg::Infer => "_".to_owned(),
+                })
+                .collect();
+            rendered.extend(bindings.iter().map(|binding| match &binding.binding {
+                rustdoc_json_types_fork::TypeBindingKind::Equality(type_) => {
+                    format!("{} = {}", binding.name, _render_type(type_))
+                }
+                rustdoc_json_types_fork::TypeBindingKind::Constraint(bounds) => {
+                    format!("{}: {}", binding.name, _render_generic_bounds(bounds))
+                }
+            }));
+            if rendered.is_empty() {
+                String::new()
+            } else {
+                format!("<{}>", rendered.join(", "))
+            }
+        }
+        rustdoc_json_types_fork::GenericArgs::Parenthesized { inputs, output } => {
+            let mut rendered = format!(
+                "({})",
+                inputs
+                    .iter()
+                    .map(_render_type)
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            );
+            if let Some(output) = output {
+                rendered.push_str(&format!(" -> {}", _render_type(output)));
+            }
+            rendered
+        }
+    }
+}
+
+fn _render_generic_bounds(bounds: &[rustdoc_json_types_fork::GenericBound]) -> String {
+    bounds
+        .iter()
+        .map(_render_generic_bound)
+        .collect::<Vec<_>>()
+        .join(" + ")
+}
+
+fn _render_generic_bound(bound: &rustdoc_json_types_fork::GenericBound) -> String {
+    match bound {
+        rustdoc_json_types_fork::GenericBound::TraitBound {
+            trait_,
+            generic_params,
+            modifier,
+        } => {
+            let mut rendered = String::new();
+            if !generic_params.is_empty() {
+                rendered.push_str(&format!(
+                    "for<{}> ",
+                    _render_generic_param_defs(generic_params)
+                ));
+            }
+            match modifier {
+                rustdoc_json_types_fork::TraitBoundModifier::None => {}
+                rustdoc_json_types_fork::TraitBoundModifier::Maybe => rendered.push('?'),
+                rustdoc_json_types_fork::TraitBoundModifier::MaybeConst => {
+                    rendered.push_str("~const ")
+                }
+            }
+            rendered.push_str(&_render_type(trait_));
+            rendered
+        }
+        rustdoc_json_types_fork::GenericBound::Outlives(lifetime) => lifetime.clone(),
+    }
+}
+
+fn _render_generic_param_defs(params: &[rustdoc_json_types_fork::GenericParamDef]) -> String {
+    params
+        .iter()
+        .map(|param| match &param.kind {
+            rustdoc_json_types_fork::GenericParamDefKind::Lifetime { outlives } => {
+                if outlives.is_empty() {
+                    param.name.clone()
+                } else {
+                    format!("{}: {}", param.name, outlives.join(" + "))
+                }
+            }
+            rustdoc_json_types_fork::GenericParamDefKind::Type { bounds, default } => {
+                let mut rendered = param.name.clone();
+                if !bounds.is_empty() {
+                    rendered.push_str(&format!(": {}", _render_generic_bounds(bounds)));
+                }
+                if let Some(default) = default {
+                    rendered.push_str(&format!(" = {}", _render_type(default)));
+                }
+                rendered
+            }
+            rustdoc_json_types_fork::GenericParamDefKind::Const { ty, default } => {
+                let mut rendered = format!("const {}: {}", param.name, _render_type(ty));
+                if let Some(default) = default {
+                    rendered.push_str(&format!(" = {}", default));
+                }
+                rendered
+            }
+        })
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+fn _render_qualifiers(
+    header: &std::collections::HashSet<rustdoc_json_types_fork::Qualifiers>,
+) -> String {
+    let mut rendered = String::new();
+    if header.contains(&rustdoc_json_types_fork::Qualifiers::Const) {
+        rendered.push_str("const ");
+    }
+    if header.contains(&rustdoc_json_types_fork::Qualifiers::Async) {
+        rendered.push_str("async ");
+    }
+    if header.contains(&rustdoc_json_types_fork::Qualifiers::Unsafe) {
+        rendered.push_str("unsafe ");
+    }
+    rendered
+}
+
+/// `for<'a> fn(&'a u8)` is the same type as `fn(&u8)`, so render it the same way
+///
+/// Only late-bound lifetimes that are each used once, by a reference, are elided, as rustc
+/// would otherwise give them different meanings.
+fn _elide_lifetimes(
+    decl: &str,
+    generic_params: &[rustdoc_json_types_fork::GenericParamDef],
+) -> Option<String> {
+    if generic_params.is_empty() {
+        return None;
+    }
+    let mut elided = decl.to_owned();
+    for param in generic_params {
+        match &param.kind {
+            rustdoc_json_types_fork::GenericParamDefKind::Lifetime { outlives }
+                if outlives.is_empty() => {}
+            _ => return None,
+        }
+        let uses = elided
+            .match_indices(param.name.as_str())
+            .filter(|(start, name)| {
+                !elided[start + name.len()..].starts_with(|c: char| c.is_alphanumeric() || c == '_')
+            })
+            .count();
+        let reference = format!("&{} ", param.name);
+        if uses != 1 || !elided.contains(&reference) {
+            return None;
+        }
+        elided = elided.replacen(&reference, "&", 1);
+    }
+    Some(elided)
+}
+
+fn _render_fn_decl(decl: &rustdoc_json_types_fork::FnDecl) -> String {
+    let mut inputs: Vec<_> = decl
+        .inputs
+        .iter()
+        .map(|(_, type_)| _render_type(type_))
+        .collect();
+    if decl.c_variadic {
+        inputs.push("...".to_owned());
+    }
+    let mut rendered = format!("({})", inputs.join(", "));
+    if let Some(output) = &decl.output {
+        rendered.push_str(&format!(" -> {}", _render_type(output)));
+    }
+    rendered
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn pinned(name: &str, content: &str) -> Option<String> {
+        let dir = tempfile::tempdir().unwrap();
+        let manifest_path = dir.path().join("Cargo.toml");
+        std::fs::write(&manifest_path, "").unwrap();
+        std::fs::write(dir.path().join(name), content).unwrap();
+        _pinned_toolchain(&manifest_path).map(|(_, channel)| channel)
+    }
+
+    #[test]
+    fn pinned_toolchain_channel() {
+        assert_eq!(
+            pinned(
+                "rust-toolchain.toml",
+                "[toolchain]\nchannel = \"nightly-2022-01-01\"\n"
+            ),
+            Some("nightly-2022-01-01".to_owned())
+        );
+        assert_eq!(
+            pinned("rust-toolchain", "nightly-2022-01-01\n"),
+            Some("nightly-2022-01-01".to_owned())
+        );
+    }
+
+    #[test]
+    fn pinned_toolchain_missing_key() {
+        assert_eq!(pinned("rust-toolchain.toml", "[toolchain]\n"), None);
+        assert_eq!(
+            pinned("rust-toolchain.toml", "[other]\nchannel = \"nightly\"\n"),
+            None
+        );
+    }
+
+    fn fingerprint(version: &[u8], dir: &std::path::Path) -> Option<String> {
+        let cmd = std::process::Command::new("cargo");
+        _fingerprint(
+            version,
+            &cmd,
+            &dir.join("Cargo.toml"),
+            &dir.join("target"),
+            &[],
+        )
+    }
+
+    fn package() -> tempfile::TempDir {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::create_dir_all(dir.path().join("src")).unwrap();
+        std::fs::create_dir_all(dir.path().join("target/doc")).unwrap();
+        std::fs::create_dir_all(dir.path().join(".git")).unwrap();
+        std::fs::write(dir.path().join("Cargo.toml"), "[package]\n").unwrap();
+        std::fs::write(dir.path().join("src/lib.rs"), "pub fn foo() {}\n").unwrap();
+        dir
+    }
+
+    #[test]
+    fn fingerprint_stable() {
+        let dir = package();
+        assert_eq!(fingerprint(b"1", dir.path()), fingerprint(b"1", dir.path()));
+        assert!(fingerprint(b"1", dir.path()).is_some());
+    }
+
+    #[test]
+    fn fingerprint_toolchain_and_command() {
+        let dir = package();
+        assert_ne!(fingerprint(b"1", dir.path()), fingerprint(b"2", dir.path()));
+
+        let manifest_path = dir.path().join("Cargo.toml");
+        let target_dir = dir.path().join("target");
+        let mut cmd = std::process::Command::new("cargo");
+        let plain = _fingerprint(b"1", &cmd, &manifest_path, &target_dir, &[]);
+        cmd.arg("--all-features");
+        let all_features = _fingerprint(b"1", &cmd, &manifest_path, &target_dir, &[]);
+        assert_ne!(plain, all_features);
+    }
+
+    #[test]
+    fn fingerprint_sources() {
+        let dir = package();
+        let before = fingerprint(b"1", dir.path());
+        std::fs::write(dir.path().join("src/lib.rs"), "pub fn foobar() {}\n").unwrap();
+        assert_ne!(before, fingerprint(b"1", dir.path()));
+
+        let before = fingerprint(b"1", dir.path());
+        std::fs::write(dir.path().join("src/new.rs"), "").unwrap();
+        assert_ne!(before, fingerprint(b"1", dir.path()));
+
+        let before = fingerprint(b"1", dir.path());
+        std::fs::write(dir.path().join("Cargo.lock"), "").unwrap();
+        assert_ne!(before, fingerprint(b"1", dir.path()));
+    }
+
+    #[test]
+    fn fingerprint_skips_output_and_hidden() {
+        let dir = package();
+        let before = fingerprint(b"1", dir.path());
+        std::fs::write(dir.path().join("target/doc/foo.json"), "{}").unwrap();
+        std::fs::write(dir.path().join(".git/index"), "").unwrap();
+        assert_eq!(before, fingerprint(b"1", dir.path()));
+    }
+
+    #[test]
+    fn fingerprint_path_dependencies() {
+        let dir = package();
+        let cmd = std::process::Command::new("cargo");
+        let manifest_path = dir.path().join("Cargo.toml");
+        let target_dir = dir.path().join("target");
+
+        let inside = dir.path().join("vendored");
+        std::fs::create_dir_all(&inside).unwrap();
+        assert!(_fingerprint(b"1", &cmd, &manifest_path, &target_dir, &[&inside]).is_some());
+
+        let outside = package();
+        assert_eq!(
+            _fingerprint(b"1", &cmd, &manifest_path, &target_dir, &[outside.path()]),
+            None
+        );
+    }
+
+    fn features(attrs: &[&str]) -> Vec<String> {
+        let attrs: Vec<_> = attrs.iter().map(|attr| (*attr).to_owned()).collect();
+        _parse_features(&attrs)
+    }
+
+    #[test]
+    fn parse_features_simple() {
+        assert_eq!(features(&[r#"#[cfg(feature = "std")]"#]), ["std"]);
+        assert_eq!(features(&[r#"#[doc(cfg(feature="std"))]"#]), ["std"]);
+        assert_eq!(
+            features(&[r#"#[doc = "feature = \"std\""]"#]),
+            Vec::<String>::new()
+        );
+    }
+
+    #[test]
+    fn parse_features_all() {
+        assert_eq!(
+            features(&[
+                r#"#[cfg(all(feature = "std", unix, all(feature = "serde")))]"#,
+                r#"#[cfg(feature = "std")]"#
+            ]),
+            ["std", "serde"]
+        );
+    }
+
+    #[test]
+    fn parse_features_any_not() {
+        assert_eq!(
+            features(&[r#"#[cfg(any(feature = "std", feature = "alloc"))]"#]),
+            Vec::<String>::new()
+        );
+        assert_eq!(
+            features(&[r#"#[cfg(not(feature = "std"))]"#]),
+            Vec::<String>::new()
+        );
+        assert_eq!(
+            features(&[r#"#[cfg(all(feature = "std", not(feature = "nightly")))]"#]),
+            ["std"]
+        );
+        assert_eq!(
+            features(&[r#"#[cfg(all(feature = "std", any(feature = "a", unix)))]"#]),
+            ["std"]
+        );
+    }
+
+    #[test]
+    fn parse_features_malformed() {
+        assert_eq!(
+            features(&[r#"#[cfg(all(feature = "std" feature = "alloc"))]"#]),
+            Vec::<String>::new()
+        );
+    }
+
+    #[test]
+    fn pinned_toolchain_empty_file() {
+        assert_eq!(pinned("rust-toolchain.toml", ""), None);
+        assert_eq!(pinned("rust-toolchain", ""), None);
+    }
+}
@@ -34,7 +34,7 @@ fn main() {
             }
         },
         move |input_path| {
-            let input = std::fs::read_to_string(&input_path).map_err(|e| e.to_string())?;
+            let input = std::fs::read_to_string(input_path).map_err(|e| e.to_string())?;
             let mut actual =
                 crate_api::rustdoc::parse_raw(&input, input_path).map_err(|e| e.to_string())?;
 
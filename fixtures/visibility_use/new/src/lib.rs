@@ -0,0 +1,5 @@
+pub mod a {
+    pub struct Abc;
+}
+
+pub(crate) use a as b;